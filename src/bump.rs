@@ -0,0 +1,208 @@
+use chrono::Local;
+use con_comm::changelog::links::Links;
+use con_comm::changelog::{update_changelog, ChangelogRenderer};
+use con_comm::config::Config;
+use con_comm::core::contributors::Identities;
+use con_comm::git;
+use con_comm::git::Repository;
+use con_comm::manifest::{find_manifests, update_manifests, ManifestUpdate};
+use con_comm::monorepo::{Monorepo, PackageRelease};
+use con_comm::release::{prepare_release, process_args, release_notes, BumpArgs};
+use std::env;
+use std::path::{Path, PathBuf};
+
+/*
+Prints the version that the commits since the latest release tag call for. With `--tag`,
+it also creates an annotated tag for that version, with release notes as the tag message.
+With `--pre <channel>`, the version is a pre-release on that channel, e.g. `2.0.0-rc.1`.
+With `--write`, the version is written into project manifests and the release notes,
+rendered with the `[changelog]` template, are prepended to the changelog. `--dry-run` prints
+the changes as a diff instead, and doesn't create the tag.
+
+In a monorepo, every package is released on its own, with `<package>@<version>` tags.
+*/
+fn main() {
+    let args: BumpArgs = process_args(&env::args().collect::<Vec<String>>());
+
+    let current_dir = env::current_dir()
+        .unwrap_or_else(|e| panic!("Couldn't determine current directory: {}", e));
+    let config = Config::discover(&current_dir)
+        .unwrap_or_else(|e| panic!("Couldn't load configuration: {}", e));
+    let options = config
+        .read_options()
+        .unwrap_or_else(|e| panic!("Couldn't load configuration: {}", e));
+    let repository = git::open_history(&current_dir, options)
+        .unwrap_or_else(|e| panic!("Couldn't open repository: {}", e));
+
+    let monorepo = Monorepo::from(&config, repository.root())
+        .unwrap_or_else(|e| panic!("Couldn't read monorepo packages: {}", e));
+    match monorepo {
+        Some(monorepo) => release_packages(&args, &config, repository.as_ref(), &monorepo),
+        None => release_repository(&args, &config, repository.as_ref()),
+    }
+}
+
+fn release_repository(args: &BumpArgs, config: &Config, repository: &dyn Repository) {
+    let prefix = args
+        .tag_prefix
+        .as_ref()
+        .unwrap_or(&config.release.tag_prefix);
+    let release = prepare_release(repository, prefix, args.pre.as_ref())
+        .unwrap_or_else(|e| panic!("Couldn't determine next version: {}", e));
+
+    println!("{}", release.version);
+    if !release.has_changes() {
+        let since = match &release.previous {
+            Some(tag) => tag.name.as_str(),
+            None => "the first commit",
+        };
+        eprintln!("No changes requiring a release since {}", since);
+        return;
+    }
+
+    if args.write {
+        let root = repository.root();
+        let manifests: Vec<PathBuf> = match config.release.manifests.is_empty() {
+            true => {
+                find_manifests(root).unwrap_or_else(|e| panic!("Couldn't find manifests: {}", e))
+            }
+            false => config
+                .release
+                .manifests
+                .iter()
+                .map(|path| root.join(path))
+                .collect(),
+        };
+
+        let mut updates: Vec<ManifestUpdate> = update_manifests(root, &manifests, &release.version)
+            .unwrap_or_else(|e| panic!("Couldn't update manifest {}", e));
+        if !config.release.changelog.is_empty() {
+            let renderer = load_renderer(config, repository);
+            let path = root.join(&config.release.changelog);
+            updates.push(
+                update_changelog(&path, &renderer, &release, Local::now().date_naive())
+                    .unwrap_or_else(|e| panic!("Couldn't update changelog {}", e)),
+            );
+        }
+        apply_updates(&updates, root, args.dry_run);
+    }
+
+    if args.create_tag {
+        create_tag(
+            repository,
+            &release.tag_name,
+            &release_notes(&release, &load_identities(config, repository)),
+            args.dry_run,
+        );
+    }
+}
+
+fn release_packages(
+    args: &BumpArgs,
+    config: &Config,
+    repository: &dyn Repository,
+    monorepo: &Monorepo,
+) {
+    if args.tag_prefix.is_some() {
+        panic!(
+            "--tag-prefix can't be used in a monorepo, packages are tagged as <package>@<version>"
+        );
+    }
+
+    let releases: Vec<PackageRelease> = monorepo
+        .prepare_releases(repository, args.pre.as_ref())
+        .unwrap_or_else(|e| panic!("Couldn't determine next versions: {}", e))
+        .into_iter()
+        .filter(|package_release| package_release.release.has_changes())
+        .collect();
+
+    if releases.is_empty() {
+        eprintln!("No changes requiring a release in any package");
+        return;
+    }
+    for package_release in &releases {
+        println!("{}", package_release.release.tag_name);
+    }
+
+    if args.write {
+        let root = repository.root();
+        let mut updates = monorepo
+            .update_manifests(root, &releases)
+            .unwrap_or_else(|e| panic!("Couldn't update manifest {}", e));
+        if !config.release.changelog.is_empty() {
+            let renderer = load_renderer(config, repository);
+            for package_release in &releases {
+                let path = root
+                    .join(&package_release.package.path)
+                    .join(&config.release.changelog);
+                updates.push(
+                    update_changelog(
+                        &path,
+                        &renderer,
+                        &package_release.release,
+                        Local::now().date_naive(),
+                    )
+                    .unwrap_or_else(|e| panic!("Couldn't update changelog {}", e)),
+                );
+            }
+        }
+        apply_updates(&updates, root, args.dry_run);
+    }
+
+    if args.create_tag {
+        for package_release in &releases {
+            let release = &package_release.release;
+            create_tag(
+                repository,
+                &release.tag_name,
+                &release_notes(release, &load_identities(config, repository)),
+                args.dry_run,
+            );
+        }
+    }
+}
+
+fn load_renderer(config: &Config, repository: &dyn Repository) -> ChangelogRenderer {
+    let remote_url = repository
+        .remote_url(&config.changelog.links.remote)
+        .unwrap_or_else(|e| panic!("Couldn't read remote URL: {}", e));
+    let links = Links::from(&config.changelog.links, remote_url.as_deref())
+        .unwrap_or_else(|e| panic!("Couldn't configure changelog links: {}", e));
+
+    return ChangelogRenderer::from(config)
+        .unwrap_or_else(|e| panic!("Couldn't load changelog template: {}", e))
+        .with_links(links)
+        .with_identities(load_identities(config, repository));
+}
+
+fn load_identities(config: &Config, repository: &dyn Repository) -> Identities {
+    return config
+        .identities(repository.root())
+        .unwrap_or_else(|e| panic!("Couldn't load contributor identities: {}", e));
+}
+
+fn apply_updates(updates: &[ManifestUpdate], root: &Path, dry_run: bool) {
+    for update in updates.iter().filter(|update| update.is_changed()) {
+        match dry_run {
+            true => print!("{}", update.diff(root)),
+            false => {
+                update
+                    .write()
+                    .unwrap_or_else(|e| panic!("Couldn't write {}", e));
+                eprintln!("Updated {}", update.path.display());
+            }
+        }
+    }
+}
+
+fn create_tag(repository: &dyn Repository, name: &str, message: &str, dry_run: bool) {
+    if dry_run {
+        eprintln!("Would create tag {}", name);
+        return;
+    }
+
+    repository
+        .create_tag(name, "HEAD", message)
+        .unwrap_or_else(|e| panic!("Couldn't create tag {}: {}", name, e));
+    eprintln!("Created tag {}", name);
+}
@@ -0,0 +1,377 @@
+extern crate lazy_static;
+
+use crate::config::{ConfigError, LinkConfig, CONFIG_FILE_NAME};
+use crate::core::conventional_commit::ConventionalCommit;
+use crate::hooks::prepare_msg::render_template;
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+
+lazy_static! {
+    static ref MENTION_REGEX: Regex = Regex::new(r"(^|[^\w&/])#(\d+)\b").unwrap();
+    static ref REFERENCE_REGEX: Regex = Regex::new(r"#(\d+)\b|\b([A-Z][A-Z0-9]*-\d+)\b").unwrap();
+}
+
+/// Trailers referencing issues.
+const ISSUE_TRAILERS: [&str; 3] = ["Refs", "Closes", "Fixes"];
+
+/// Hosting services with known URL layouts.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Host {
+    GitHub,
+    GitLab,
+    Gitea,
+    Bitbucket,
+}
+
+impl Host {
+    pub fn from(name: &str) -> Option<Self> {
+        match name {
+            "github" => Some(Host::GitHub),
+            "gitlab" => Some(Host::GitLab),
+            "gitea" => Some(Host::Gitea),
+            "bitbucket" => Some(Host::Bitbucket),
+            _ => None,
+        }
+    }
+
+    /// Guesses the service from a host name, e.g. `gitlab.example.com`.
+    pub fn detect(host_name: &str) -> Option<Self> {
+        let host_name = host_name.to_lowercase();
+        if host_name.contains("github") {
+            return Some(Host::GitHub);
+        }
+        if host_name.contains("gitlab") {
+            return Some(Host::GitLab);
+        }
+        if host_name.contains("bitbucket") {
+            return Some(Host::Bitbucket);
+        }
+        // Codeberg runs Forgejo, which keeps the URL layout of Gitea
+        if host_name.contains("gitea") || host_name == "codeberg.org" {
+            return Some(Host::Gitea);
+        }
+
+        return None;
+    }
+
+    fn commit_template(&self) -> &'static str {
+        match self {
+            Host::GitHub | Host::Gitea => "{{repository}}/commit/{{id}}",
+            Host::GitLab => "{{repository}}/-/commit/{{id}}",
+            Host::Bitbucket => "{{repository}}/commits/{{id}}",
+        }
+    }
+
+    fn compare_template(&self) -> &'static str {
+        match self {
+            Host::GitHub | Host::Gitea => "{{repository}}/compare/{{from}}...{{to}}",
+            Host::GitLab => "{{repository}}/-/compare/{{from}}...{{to}}",
+            Host::Bitbucket => "{{repository}}/branches/compare/{{to}}%0D{{from}}",
+        }
+    }
+
+    fn issue_template(&self) -> &'static str {
+        match self {
+            Host::GitHub | Host::Gitea | Host::Bitbucket => "{{repository}}/issues/{{id}}",
+            Host::GitLab => "{{repository}}/-/issues/{{id}}",
+        }
+    }
+}
+
+/// Host name and web address of the repository behind a remote URL, e.g. `github.com` and
+/// `https://github.com/owner/repo` for `git@github.com:owner/repo.git`.
+pub fn parse_remote_url(url: &str) -> Option<(String, String)> {
+    let url = url.trim().trim_end_matches('/');
+    let url = url.strip_suffix(".git").unwrap_or(url);
+
+    let (authority, path) = match url.split_once("://") {
+        Some((_, rest)) => rest.split_once('/')?,
+        // scp-like syntax, e.g. git@github.com:owner/repo
+        None => url.split_once(':')?,
+    };
+    let host = authority.rsplit('@').next()?;
+    let host = host.split(':').next()?;
+    let path = path.trim_matches('/');
+    if host.is_empty() || path.is_empty() {
+        return None;
+    }
+
+    return Some((String::from(host), format!("https://{}/{}", host, path)));
+}
+
+/// Issue referenced by a trailer of a commit.
+#[derive(Debug, PartialEq)]
+pub struct IssueReference {
+    /// Trailer the reference comes from, e.g. `Closes`.
+    pub key: String,
+    /// Issue number or key, e.g. `123` or `JIRA-123`.
+    pub id: String,
+    /// The reference as written, e.g. `#123` or `JIRA-123`.
+    pub reference: String,
+}
+
+/// Issues referenced by `Refs`, `Closes` and `Fixes` trailers of the commit.
+pub fn find_issue_references(commit: &ConventionalCommit) -> Vec<IssueReference> {
+    let Some(footer) = &commit.footer else {
+        return vec![];
+    };
+
+    let mut references: Vec<IssueReference> = vec![];
+    for element in &footer.elements {
+        let key = element.key();
+        if !ISSUE_TRAILERS.iter().any(|t| t.eq_ignore_ascii_case(key)) {
+            continue;
+        }
+
+        for captures in REFERENCE_REGEX.captures_iter(element.value()) {
+            let id = captures.get(1).or(captures.get(2)).unwrap().as_str();
+            references.push(IssueReference {
+                key: String::from(key),
+                id: String::from(id),
+                reference: String::from(captures.get(0).unwrap().as_str()),
+            });
+        }
+    }
+
+    return references;
+}
+
+/// URL templates with the repository address they refer to.
+#[derive(Debug, Default, Clone)]
+pub struct Links {
+    repository: Option<String>,
+    commit: Option<String>,
+    compare: Option<String>,
+    issue: Option<String>,
+    /// Whether issue keys like `JIRA-123` are linked too, not only issue numbers. Presets
+    /// only link numbers, as keys usually belong to an external tracker.
+    link_keys: bool,
+}
+
+impl Links {
+    /// Templates from the configuration, completed with the preset of the hosting service,
+    /// which is detected from `remote_url` unless configured.
+    pub fn from(config: &LinkConfig, remote_url: Option<&str>) -> Result<Self, ConfigError> {
+        let remote = remote_url.and_then(parse_remote_url);
+        let host = match &config.host {
+            Some(name) => Some(Host::from(name).ok_or_else(|| ConfigError {
+                path: String::from(CONFIG_FILE_NAME),
+                reason: format!("Unknown hosting service: {}", name),
+            })?),
+            None => remote.as_ref().and_then(|(host, _)| Host::detect(host)),
+        };
+
+        return Ok(Self {
+            repository: remote.map(|(_, repository)| repository),
+            commit: get_template(&config.commit, host.map(|h| h.commit_template())),
+            compare: get_template(&config.compare, host.map(|h| h.compare_template())),
+            issue: get_template(&config.issue, host.map(|h| h.issue_template())),
+            link_keys: config.issue.is_some(),
+        });
+    }
+
+    pub fn commit_url(&self, id: &str) -> Option<String> {
+        return self.render(&self.commit, &[("id", id)]);
+    }
+
+    pub fn compare_url(&self, from: &str, to: &str) -> Option<String> {
+        return self.render(&self.compare, &[("from", from), ("to", to)]);
+    }
+
+    pub fn issue_url(&self, id: &str) -> Option<String> {
+        if !self.link_keys && id.parse::<u64>().is_err() {
+            return None;
+        }
+
+        return self.render(&self.issue, &[("id", id)]);
+    }
+
+    /// Replaces `#123` mentions in markdown text with links to the issues.
+    pub fn link_issues(&self, text: &str) -> String {
+        return MENTION_REGEX
+            .replace_all(text, |captures: &Captures| {
+                match self.issue_url(&captures[2]) {
+                    Some(url) => format!("{}[#{}]({})", &captures[1], &captures[2], url),
+                    None => String::from(&captures[0]),
+                }
+            })
+            .to_string();
+    }
+
+    fn render(&self, template: &Option<String>, values: &[(&str, &str)]) -> Option<String> {
+        let template = template.as_ref()?;
+        let mut values: HashMap<&str, String> = values
+            .iter()
+            .map(|(name, value)| (*name, String::from(*value)))
+            .collect();
+        match &self.repository {
+            Some(repository) => values.insert("repository", repository.clone()),
+            // templates relying on the remote can't be rendered without it
+            None if template.contains("{{repository}}") => return None,
+            None => None,
+        };
+
+        return Some(render_template(template, &values));
+    }
+}
+
+fn get_template(configured: &Option<String>, preset: Option<&str>) -> Option<String> {
+    return configured.clone().or(preset.map(String::from));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::changelog::links::{find_issue_references, parse_remote_url, Host, Links};
+    use crate::config::Config;
+    use crate::core::conventional_commit::ConventionalCommit;
+    use std::str::FromStr;
+
+    fn links(config: &str, remote_url: Option<&str>) -> Links {
+        let config = Config::parse(config).unwrap();
+        return Links::from(&config.changelog.links, remote_url).unwrap();
+    }
+
+    #[test]
+    fn should_parse_remote_urls() {
+        for (url, host, repository) in [
+            (
+                "git@github.com:owner/repo.git",
+                "github.com",
+                "https://github.com/owner/repo",
+            ),
+            (
+                "https://gitlab.com/group/sub/repo.git",
+                "gitlab.com",
+                "https://gitlab.com/group/sub/repo",
+            ),
+            (
+                "ssh://git@gitea.example.com:2222/owner/repo.git",
+                "gitea.example.com",
+                "https://gitea.example.com/owner/repo",
+            ),
+            (
+                "https://jane@bitbucket.org/team/repo/",
+                "bitbucket.org",
+                "https://bitbucket.org/team/repo",
+            ),
+        ] {
+            assert_eq!(
+                parse_remote_url(url),
+                Some((String::from(host), String::from(repository))),
+                "{}",
+                url
+            );
+        }
+        assert_eq!(parse_remote_url("/srv/git/repo.git"), None);
+    }
+
+    #[test]
+    fn should_detect_hosting_service() {
+        assert_eq!(Host::detect("github.com"), Some(Host::GitHub));
+        assert_eq!(Host::detect("gitlab.example.com"), Some(Host::GitLab));
+        assert_eq!(Host::detect("codeberg.org"), Some(Host::Gitea));
+        assert_eq!(Host::detect("bitbucket.org"), Some(Host::Bitbucket));
+        assert_eq!(Host::detect("git.example.com"), None);
+    }
+
+    #[test]
+    fn should_use_preset_of_hosting_service() {
+        // given
+        let github = links("", Some("git@github.com:owner/repo.git"));
+        let gitlab = links("", Some("https://gitlab.com/group/repo.git"));
+        let bitbucket = links("", Some("git@bitbucket.org:team/repo.git"));
+
+        // then
+        assert_eq!(
+            github.commit_url("abc").unwrap(),
+            "https://github.com/owner/repo/commit/abc"
+        );
+        assert_eq!(
+            github.compare_url("v1.0.0", "v1.1.0").unwrap(),
+            "https://github.com/owner/repo/compare/v1.0.0...v1.1.0"
+        );
+        assert_eq!(
+            gitlab.issue_url("12").unwrap(),
+            "https://gitlab.com/group/repo/-/issues/12"
+        );
+        assert_eq!(
+            bitbucket.compare_url("v1.0.0", "v1.1.0").unwrap(),
+            "https://bitbucket.org/team/repo/branches/compare/v1.1.0%0Dv1.0.0"
+        );
+        assert_eq!(github.issue_url("JIRA-12"), None);
+    }
+
+    #[test]
+    fn should_prefer_configured_templates_and_host() {
+        // given
+        let config = r#"
+[changelog.links]
+host = "gitea"
+issue = "https://jira.example.com/browse/{{id}}"
+"#;
+
+        // when
+        let links = links(config, Some("git@git.example.com:owner/repo.git"));
+
+        // then
+        assert_eq!(
+            links.commit_url("abc").unwrap(),
+            "https://git.example.com/owner/repo/commit/abc"
+        );
+        assert_eq!(
+            links.issue_url("JIRA-12").unwrap(),
+            "https://jira.example.com/browse/JIRA-12"
+        );
+    }
+
+    #[test]
+    fn should_not_link_without_known_host() {
+        // given
+        let unknown = links("", Some("git@git.example.com:owner/repo.git"));
+        let missing = links("[changelog.links]\nhost = \"github\"", None);
+
+        // then
+        assert_eq!(unknown.commit_url("abc"), None);
+        assert_eq!(missing.commit_url("abc"), None);
+        assert_eq!(missing.link_issues("fix #12"), "fix #12");
+    }
+
+    #[test]
+    fn should_link_issue_mentions() {
+        // given
+        let links = links("", Some("git@github.com:owner/repo.git"));
+
+        // then
+        assert_eq!(
+            links.link_issues("fix #12, not a#3 or &#38;"),
+            "fix [#12](https://github.com/owner/repo/issues/12), not a#3 or &#38;"
+        );
+    }
+
+    #[test]
+    fn should_find_issue_references_in_trailers() {
+        // given
+        let commit = ConventionalCommit::from_str(
+            "fix: crash\n\nCloses #12, #13\nRefs: JIRA-7\nReviewed-by: Z #4",
+        )
+        .unwrap();
+
+        // when
+        let references = find_issue_references(&commit);
+
+        // then
+        let ids: Vec<(&str, &str, &str)> = references
+            .iter()
+            .map(|r| (r.key.as_str(), r.id.as_str(), r.reference.as_str()))
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                ("Closes", "12", "#12"),
+                ("Closes", "13", "#13"),
+                ("Refs", "JIRA-7", "JIRA-7"),
+            ]
+        );
+    }
+}
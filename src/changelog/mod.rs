@@ -0,0 +1,1121 @@
+pub mod links;
+pub mod presets;
+
+use crate::changelog::links::{find_issue_references, Links};
+use crate::changelog::presets::Preset;
+use crate::config::{Config, ConfigError, SectionDefinition, CONFIG_FILE_NAME};
+use crate::core::contributors::{Contributor, Identities};
+use crate::core::history::HistoryEntry;
+use crate::manifest::{ManifestError, ManifestUpdate};
+use crate::release::Release;
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use tera::{Context, Tera, Value};
+
+const TEMPLATE_NAME: &str = "changelog";
+// the extension turns on autoescaping
+const HTML_TEMPLATE_NAME: &str = "changelog.html";
+/// Version of the JSON output, incremented with incompatible changes only.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub struct ChangelogArgs {
+    /// Overrides `release.tag_prefix` from the configuration.
+    pub tag_prefix: Option<String>,
+    pub format: Format,
+    /// Write the changelog to this file instead of the standard output.
+    pub output: Option<String>,
+}
+
+pub fn process_args(args: &[String]) -> ChangelogArgs {
+    let mut tag_prefix: Option<String> = None;
+    let mut format = Format::Template;
+    let mut output: Option<String> = None;
+
+    let mut iterator = args.iter().skip(1);
+    while let Some(arg) = iterator.next() {
+        match arg.as_str() {
+            "--tag-prefix" => match iterator.next() {
+                Some(prefix) => tag_prefix = Some(prefix.clone()),
+                None => panic!("Missing prefix after --tag-prefix"),
+            },
+            "--format" => match iterator.next() {
+                Some(name) => match Format::from(name) {
+                    Some(value) => format = value,
+                    None => panic!("Unknown format: {}", name),
+                },
+                None => panic!("Missing format after --format"),
+            },
+            "--output" => match iterator.next() {
+                Some(path) => output = Some(path.clone()),
+                None => panic!("Missing path after --output"),
+            },
+            _ => panic!("Unknown argument: {}", arg),
+        }
+    }
+
+    return ChangelogArgs {
+        tag_prefix,
+        format,
+        output,
+    };
+}
+
+/// Output format of the changelog.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Format {
+    /// The template from the `[changelog]` configuration.
+    Template,
+    /// One of the built-in templates, regardless of the configuration.
+    Preset(Preset),
+    Json,
+    /// Standalone page.
+    Html,
+}
+
+impl Format {
+    /// `json`, `html` or the name of a preset.
+    pub fn from(name: &str) -> Option<Self> {
+        match name {
+            "json" => Some(Format::Json),
+            "html" => Some(Format::Html),
+            _ => Preset::from(name).map(Format::Preset),
+        }
+    }
+}
+
+/// Everything a changelog template can refer to, as `releases`. The JSON output has the same
+/// structure, along with `schema_version`.
+#[derive(Debug, Serialize)]
+pub struct ChangelogContext {
+    /// Newest first.
+    pub releases: Vec<ReleaseContext>,
+}
+
+#[derive(Serialize)]
+struct JsonChangelog<'a> {
+    schema_version: u32,
+    releases: &'a [ReleaseContext],
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReleaseContext {
+    pub version: String,
+    pub tag: String,
+    pub previous_tag: Option<String>,
+    /// Release date, e.g. `2024-05-01`.
+    pub date: String,
+    pub is_pre_release: bool,
+    /// Non-empty sections, in the configured order.
+    pub sections: Vec<SectionContext>,
+    pub breaking_changes: Vec<BreakingChangeContext>,
+    /// All commits of the release, newest first.
+    pub commits: Vec<CommitContext>,
+    /// Distinct scopes of the commits.
+    pub scopes: Vec<String>,
+    /// Distinct commit authors, by email, in the order of their first commit.
+    pub authors: Vec<AuthorContext>,
+    /// Distinct authors, co-authors and people who signed off commits, in the order of their
+    /// first contribution, without excluded contributors.
+    pub contributors: Vec<AuthorContext>,
+    /// Tags of dependencies released along with this release, in a monorepo.
+    pub dependencies: Vec<String>,
+    /// Link comparing the previous release with this one.
+    pub compare_url: Option<String>,
+    pub statistics: StatisticsContext,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatisticsContext {
+    /// Commits since the previous release, conventional or not.
+    pub commits: usize,
+    /// Commits not following the conventional commits format, left out of the release.
+    pub skipped: usize,
+    /// Reverts and the commits they revert within the release, left out of it.
+    pub reverted: usize,
+    /// Number of commits of each type, most frequent first.
+    pub types: Vec<TypeCountContext>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TypeCountContext {
+    #[serde(rename = "type")]
+    pub commit_type: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SectionContext {
+    pub title: String,
+    pub commits: Vec<CommitContext>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct CommitContext {
+    pub id: String,
+    pub short_id: String,
+    #[serde(rename = "type")]
+    pub commit_type: String,
+    pub scopes: Vec<String>,
+    /// Scopes joined with `, `.
+    pub scope: Option<String>,
+    pub description: String,
+    /// Paragraphs of the body separated by blank lines.
+    pub body: Option<String>,
+    pub breaking: bool,
+    pub trailers: Vec<TrailerContext>,
+    /// Issues referenced by `Refs`, `Closes` and `Fixes` trailers.
+    pub issues: Vec<IssueContext>,
+    pub author: AuthorContext,
+    /// Commit date, e.g. `2024-05-01`.
+    pub date: String,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct BreakingChangeContext {
+    pub scope: Option<String>,
+    /// Text of the `BREAKING CHANGE` trailer, or the description when `!` marks the change.
+    pub description: String,
+    pub commit: CommitContext,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct TrailerContext {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct IssueContext {
+    /// Trailer the reference comes from, e.g. `Closes`.
+    pub key: String,
+    /// Issue number or key, e.g. `123` or `JIRA-123`.
+    pub id: String,
+    /// The reference as written, e.g. `#123`.
+    pub reference: String,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct AuthorContext {
+    pub name: String,
+    pub email: String,
+}
+
+impl AuthorContext {
+    pub fn from(contributor: &Contributor) -> Self {
+        Self {
+            name: contributor.name.clone(),
+            email: contributor.email.clone(),
+        }
+    }
+}
+
+impl CommitContext {
+    /// Context of the entry, with its author resolved with the mailmap of `identities`.
+    pub fn from(entry: &HistoryEntry, links: &Links, identities: &Identities) -> Self {
+        let commit = &entry.commit;
+        let scopes = commit.scopes.clone().unwrap_or_default();
+        let body = commit.body.as_ref().map(|body| {
+            body.paragraphs
+                .iter()
+                .map(|paragraph| paragraph.lines.join("\n"))
+                .collect::<Vec<String>>()
+                .join("\n\n")
+        });
+        let trailers = match &commit.footer {
+            Some(footer) => footer
+                .elements
+                .iter()
+                .map(|element| TrailerContext {
+                    key: String::from(element.key()),
+                    value: String::from(element.value()),
+                })
+                .collect(),
+            None => vec![],
+        };
+        let issues = find_issue_references(commit)
+            .into_iter()
+            .map(|issue| IssueContext {
+                url: links.issue_url(&issue.id),
+                key: issue.key,
+                id: issue.id,
+                reference: issue.reference,
+            })
+            .collect();
+
+        return Self {
+            id: entry.id.clone(),
+            short_id: entry.id.chars().take(7).collect(),
+            commit_type: String::from(commit.commit_type.as_str()),
+            scope: match scopes.is_empty() {
+                true => None,
+                false => Some(scopes.join(", ")),
+            },
+            scopes,
+            description: commit.description.clone(),
+            body,
+            breaking: commit.is_breaking_change,
+            trailers,
+            issues,
+            author: AuthorContext::from(
+                &identities.resolve(&Contributor::new(&entry.author.name, &entry.author.email)),
+            ),
+            date: entry.timestamp.format("%Y-%m-%d").to_string(),
+            url: links.commit_url(&entry.id),
+        };
+    }
+}
+
+/// Renders releases with a Tera template.
+pub struct ChangelogRenderer {
+    tera: Tera,
+    /// Where the template comes from, for errors.
+    source: String,
+    /// Text a new changelog starts with.
+    pub header: String,
+    pub sections: Vec<SectionDefinition>,
+    links: Links,
+    identities: Identities,
+}
+
+impl ChangelogRenderer {
+    pub fn new(
+        template: &str,
+        header: &str,
+        sections: Vec<SectionDefinition>,
+        source: &str,
+    ) -> Result<Self, ConfigError> {
+        let mut tera = Tera::default();
+        tera.add_raw_template(TEMPLATE_NAME, template)
+            .map_err(|e| ConfigError {
+                path: String::from(source),
+                reason: format!("Invalid changelog template: {}", get_reason(&e)),
+            })?;
+        tera.add_raw_template(HTML_TEMPLATE_NAME, presets::HTML_TEMPLATE)
+            .expect("built-in changelog templates should be valid");
+        tera.set_escape_fn(escape_html);
+
+        register_links(&mut tera, &Links::default());
+
+        return Ok(Self {
+            tera,
+            source: String::from(source),
+            header: String::from(header),
+            sections,
+            links: Links::default(),
+            identities: Identities::default(),
+        });
+    }
+
+    /// Links commits, issues and releases, which are left unlinked by default.
+    pub fn with_links(mut self, links: Links) -> Self {
+        register_links(&mut self.tera, &links);
+        self.links = links;
+        return self;
+    }
+
+    /// Resolves authors and contributors with a mailmap and leaves out excluded contributors,
+    /// which are taken as they are by default.
+    pub fn with_identities(mut self, identities: Identities) -> Self {
+        self.identities = identities;
+        return self;
+    }
+
+    pub fn preset(preset: Preset) -> Self {
+        return Self::new(
+            preset.template(),
+            preset.header(),
+            preset.sections(),
+            CONFIG_FILE_NAME,
+        )
+        .expect("built-in changelog templates should be valid");
+    }
+
+    /// Renderer of notes for a single release, with a summary of breaking changes, contributors
+    /// and statistics. Markdown, or plain text suitable as a tag message. The sections of
+    /// the `angular` or `plain` preset are used when `sections` is empty.
+    pub fn release_notes(plain: bool, sections: &[SectionDefinition]) -> Self {
+        let (template, preset) = match plain {
+            true => (presets::PLAIN_RELEASE_NOTES_TEMPLATE, Preset::Plain),
+            false => (presets::RELEASE_NOTES_TEMPLATE, Preset::Angular),
+        };
+        let sections = match sections.is_empty() {
+            true => preset.sections(),
+            false => sections.to_vec(),
+        };
+
+        return Self::new(template, "", sections, CONFIG_FILE_NAME)
+            .expect("built-in changelog templates should be valid");
+    }
+
+    /// Renderer for the `[changelog]` configuration. Sections and the header of the preset
+    /// are used unless configured, also with a custom template.
+    pub fn from(config: &Config) -> Result<Self, ConfigError> {
+        let changelog = &config.changelog;
+        let preset = Preset::from(&changelog.preset).ok_or_else(|| ConfigError {
+            path: String::from(CONFIG_FILE_NAME),
+            reason: format!("Unknown changelog preset: {}", changelog.preset),
+        })?;
+        let header = changelog.header.as_deref().unwrap_or(preset.header());
+        let sections = match changelog.sections.is_empty() {
+            true => preset.sections(),
+            false => changelog.sections.clone(),
+        };
+
+        if let Some(template) = &changelog.template {
+            return Self::new(template, header, sections, CONFIG_FILE_NAME);
+        }
+
+        if let Some(template_file) = &changelog.template_file {
+            let path = config.resolve_path(template_file);
+            let template = fs::read_to_string(&path).map_err(|e| ConfigError {
+                path: path.display().to_string(),
+                reason: format!("Couldn't read changelog template: {}", e),
+            })?;
+            return Self::new(&template, header, sections, &path.display().to_string());
+        }
+
+        return Self::new(preset.template(), header, sections, CONFIG_FILE_NAME);
+    }
+
+    pub fn release_context(&self, release: &Release, date: NaiveDate) -> ReleaseContext {
+        let entries = &release.history.entries;
+        let commits: Vec<CommitContext> = entries
+            .iter()
+            .map(|entry| CommitContext::from(entry, &self.links, &self.identities))
+            .collect();
+
+        let sections = self
+            .sections
+            .iter()
+            .map(|section| SectionContext {
+                title: section.title.clone(),
+                commits: commits
+                    .iter()
+                    .filter(|commit| section.types.contains(&commit.commit_type))
+                    .cloned()
+                    .collect(),
+            })
+            .filter(|section| !section.commits.is_empty())
+            .collect();
+
+        let breaking_changes = entries
+            .iter()
+            .zip(&commits)
+            .filter(|(entry, _)| entry.commit.is_breaking_change)
+            .map(|(entry, commit)| BreakingChangeContext {
+                scope: commit.scope.clone(),
+                description: get_breaking_change_description(entry),
+                commit: commit.clone(),
+            })
+            .collect();
+
+        let mut scopes: Vec<String> = vec![];
+        let mut authors: Vec<AuthorContext> = vec![];
+        for commit in commits.iter().rev() {
+            for scope in &commit.scopes {
+                if !scopes.contains(scope) {
+                    scopes.push(scope.clone());
+                }
+            }
+            if !authors
+                .iter()
+                .any(|a| a.email.eq_ignore_ascii_case(&commit.author.email))
+            {
+                authors.push(commit.author.clone());
+            }
+        }
+
+        let mut types: Vec<TypeCountContext> = vec![];
+        for commit in &commits {
+            match types
+                .iter_mut()
+                .find(|t| t.commit_type == commit.commit_type)
+            {
+                Some(type_count) => type_count.count += 1,
+                None => types.push(TypeCountContext {
+                    commit_type: commit.commit_type.clone(),
+                    count: 1,
+                }),
+            }
+        }
+        // stable, so types with equal counts keep the order of their newest commits
+        types.sort_by_key(|t| Reverse(t.count));
+        let statistics = StatisticsContext {
+            commits: commits.len() + release.history.skipped.len() + release.history.reverted.len(),
+            skipped: release.history.skipped.len(),
+            reverted: release.history.reverted.len(),
+            types,
+        };
+
+        let previous_tag = release.previous.as_ref().map(|tag| tag.name.clone());
+        let compare_url = previous_tag
+            .as_ref()
+            .and_then(|previous| self.links.compare_url(previous, &release.tag_name));
+
+        return ReleaseContext {
+            version: release.version.to_string(),
+            tag: release.tag_name.clone(),
+            previous_tag,
+            date: date.format("%Y-%m-%d").to_string(),
+            is_pre_release: release.version.is_pre_release(),
+            sections,
+            breaking_changes,
+            commits,
+            scopes,
+            authors,
+            contributors: release
+                .history
+                .contributors(&self.identities)
+                .iter()
+                .map(AuthorContext::from)
+                .collect(),
+            dependencies: release.dependencies.clone(),
+            compare_url,
+            statistics,
+        };
+    }
+
+    pub fn render(&self, releases: Vec<ReleaseContext>) -> Result<String, ConfigError> {
+        return self.render_template(TEMPLATE_NAME, releases);
+    }
+
+    /// Standalone HTML page listing the releases.
+    pub fn render_html(&self, releases: Vec<ReleaseContext>) -> Result<String, ConfigError> {
+        return self.render_template(HTML_TEMPLATE_NAME, releases);
+    }
+
+    /// Pretty printed JSON with `schema_version` and `releases`.
+    pub fn render_json(&self, releases: &[ReleaseContext]) -> String {
+        let changelog = JsonChangelog {
+            schema_version: JSON_SCHEMA_VERSION,
+            releases,
+        };
+        let json = serde_json::to_string_pretty(&changelog)
+            .expect("changelog context should serialize to JSON");
+
+        return format!("{}\n", json);
+    }
+
+    fn render_template(
+        &self,
+        name: &str,
+        releases: Vec<ReleaseContext>,
+    ) -> Result<String, ConfigError> {
+        let context =
+            Context::from_serialize(ChangelogContext { releases }).map_err(|e| ConfigError {
+                path: self.source.clone(),
+                reason: get_reason(&e),
+            })?;
+
+        return self.tera.render(name, &context).map_err(|e| ConfigError {
+            path: self.source.clone(),
+            reason: format!("Couldn't render changelog: {}", get_reason(&e)),
+        });
+    }
+
+    pub fn render_release(
+        &self,
+        release: &Release,
+        date: NaiveDate,
+    ) -> Result<String, ConfigError> {
+        return self.render(vec![self.release_context(release, date)]);
+    }
+}
+
+/// Inserts the section above previous releases: before the first markdown release heading,
+/// or below the header, or at the top of the changelog.
+pub fn prepend_changelog(content: &str, header: &str, section: &str) -> String {
+    if content.trim().is_empty() {
+        return match header.is_empty() {
+            true => String::from(section),
+            false => format!("{}\n{}", header, section),
+        };
+    }
+
+    let position = match content.find("\n## ") {
+        Some(position) => position + 1,
+        None if content.starts_with("## ") => 0,
+        None if !header.is_empty() && content.starts_with(header) => {
+            content.len() - content[header.len()..].trim_start_matches('\n').len()
+        }
+        None if content.starts_with("# ") => content.len(),
+        None => 0,
+    };
+    let (head, tail) = content.split_at(position);
+    let separator = match head.is_empty() || head.ends_with("\n\n") {
+        true => "",
+        false if head.ends_with('\n') => "\n",
+        false => "\n\n",
+    };
+    let gap = match tail.is_empty() {
+        true => "",
+        false => "\n",
+    };
+
+    return format!("{}{}{}{}{}", head, separator, section, gap, tail);
+}
+
+/// Reads the changelog at `path`, if there is one, and prepends the release to it.
+pub fn update_changelog(
+    path: &Path,
+    renderer: &ChangelogRenderer,
+    release: &Release,
+    date: NaiveDate,
+) -> Result<ManifestUpdate, ManifestError> {
+    let before = match path.is_file() {
+        true => fs::read_to_string(path).map_err(|e| ManifestError {
+            path: path.display().to_string(),
+            reason: e.to_string(),
+        })?,
+        false => String::new(),
+    };
+    let section = renderer
+        .render_release(release, date)
+        .map_err(|e| ManifestError {
+            path: e.path,
+            reason: e.reason,
+        })?;
+    let after = prepend_changelog(&before, &renderer.header, &section);
+
+    return Ok(ManifestUpdate {
+        path: path.to_path_buf(),
+        before,
+        after,
+    });
+}
+
+/// Text of the `BREAKING CHANGE` trailer, or the description when `!` marks the change.
+fn get_breaking_change_description(entry: &HistoryEntry) -> String {
+    let trailer = entry.commit.footer.as_ref().and_then(|footer| {
+        footer
+            .elements
+            .iter()
+            .find(|element| element.has_breaking_change)
+            .map(|element| String::from(element.value()))
+    });
+
+    return trailer.unwrap_or_else(|| entry.commit.description.clone());
+}
+
+/// Makes the `link_issues` filter, replacing `#123` mentions with markdown links, available
+/// to templates.
+fn register_links(tera: &mut Tera, links: &Links) {
+    let links = links.clone();
+    tera.register_filter(
+        "link_issues",
+        move |value: &Value, _: &HashMap<String, Value>| {
+            let text = tera::try_get_value!("link_issues", "value", String, value);
+            return Ok(Value::String(links.link_issues(&text)));
+        },
+    );
+}
+
+/// Escapes text for HTML content and quoted attributes. Unlike the default of Tera, slashes
+/// are kept, so that URLs stay readable.
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#x27;"),
+            _ => escaped.push(character),
+        }
+    }
+
+    return escaped;
+}
+
+/// Tera errors describe the problem in their sources, e.g. the line of a syntax error.
+fn get_reason(error: &tera::Error) -> String {
+    let mut reason = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        reason.push_str(&format!(": {}", cause));
+        source = cause.source();
+    }
+
+    return reason;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::changelog::links::Links;
+    use crate::changelog::presets::Preset;
+    use crate::changelog::{
+        prepend_changelog, process_args, update_changelog, ChangelogRenderer, Format,
+    };
+    use crate::config::Config;
+    use crate::core::contributors::{Identities, Mailmap};
+    use crate::core::history::tests::commit;
+    use crate::core::history::History;
+    use crate::core::semantic_version::SemanticVersion;
+    use crate::release::{Release, VersionTag};
+    use chrono::NaiveDate;
+    use regex::Regex;
+    use std::fs;
+
+    fn release() -> Release {
+        return Release {
+            previous: Some(VersionTag {
+                name: String::from("v1.0.0"),
+                target: String::from("0000000"),
+                version: SemanticVersion::new(1, 0, 0, None, None),
+            }),
+            version: SemanticVersion::new(2, 0, 0, None, None),
+            tag_name: String::from("v2.0.0"),
+            history: History::from(&[
+                commit("d4c3b2a1", "docs: readme"),
+                commit("c3b2a1d4", "fix(cli,api): crash\n\nCloses #12"),
+                commit("b2a1d4c3", "feat(api)!: add endpoint"),
+                commit(
+                    "a1d4c3b2",
+                    "feat: drop config\n\nBREAKING CHANGE: config file is gone",
+                ),
+            ]),
+            dependencies: vec![String::from("core@2.0.0")],
+        };
+    }
+
+    fn date() -> NaiveDate {
+        return NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+    }
+
+    #[test]
+    fn should_resolve_contributors_with_identities() {
+        // given
+        let mut release = release();
+        release.history = History::from(&[
+            commit(
+                "b",
+                "fix: crash\n\n\
+                Co-authored-by: John Roe <john@old.example.com>\n\
+                Signed-off-by: renovate[bot] <bot@example.com>",
+            ),
+            commit("a", "feat: add api"),
+        ]);
+        let renderer = ChangelogRenderer::new(
+            "{% for author in releases[0].authors %}{{ author.name }};{% endfor %}\n\
+            {% for contributor in releases[0].contributors %}{{ contributor.email }};{% endfor %}",
+            "",
+            vec![],
+            "test",
+        )
+        .unwrap()
+        .with_identities(Identities {
+            mailmap: Mailmap::from(
+                "Jane <jane@example.com>\nJohn Roe <john@example.com> <john@old.example.com>",
+            ),
+            exclude: vec![Regex::new(r"\[bot\]").unwrap()],
+        });
+
+        // when
+        let text = renderer.render_release(&release, date()).unwrap();
+
+        // then
+        assert_eq!(text, "Jane;\njane@example.com;john@example.com;");
+    }
+
+    #[test]
+    fn should_render_keep_a_changelog_preset() {
+        // given
+        let renderer = ChangelogRenderer::preset(Preset::KeepAChangelog);
+
+        // when
+        let section = renderer.render_release(&release(), date()).unwrap();
+
+        // then
+        assert_eq!(
+            section,
+            "## 2.0.0 - 2024-05-01\n\n\
+            ### Breaking changes\n\n- api: add endpoint\n- config file is gone\n\n\
+            ### Added\n\n- api: add endpoint\n- drop config\n\n\
+            ### Fixed\n\n- cli, api: crash (#12)\n\n\
+            ### Dependencies\n\n- core@2.0.0\n"
+        );
+    }
+
+    #[test]
+    fn should_render_angular_preset() {
+        // given
+        let renderer = ChangelogRenderer::preset(Preset::Angular);
+
+        // when
+        let section = renderer.render_release(&release(), date()).unwrap();
+
+        // then
+        assert_eq!(
+            section,
+            "## 2.0.0 (2024-05-01)\n\n\
+            ### Features\n\n* **api:** add endpoint (b2a1d4c)\n* drop config (a1d4c3b)\n\n\
+            ### Bug Fixes\n\n* **cli, api:** crash (c3b2a1d), closes #12\n\n\
+            ### Dependencies\n\n* core@2.0.0\n\n\
+            ### BREAKING CHANGES\n\n* **api:** add endpoint\n* config file is gone\n"
+        );
+    }
+
+    #[test]
+    fn should_link_releases_commits_and_issues() {
+        // given
+        let config = Config::parse("").unwrap();
+        let links = Links::from(
+            &config.changelog.links,
+            Some("git@github.com:owner/repo.git"),
+        )
+        .unwrap();
+        let renderer = ChangelogRenderer::preset(Preset::KeepAChangelog).with_links(links);
+        let release = Release {
+            history: History::from(&[commit("c3b2a1d4", "fix: crash in #7\n\nCloses #12")]),
+            dependencies: vec![],
+            ..release()
+        };
+
+        // when
+        let section = renderer.render_release(&release, date()).unwrap();
+
+        // then
+        assert_eq!(
+            section,
+            "## [2.0.0](https://github.com/owner/repo/compare/v1.0.0...v2.0.0) - 2024-05-01\n\n\
+            ### Fixed\n\n\
+            - crash in [#7](https://github.com/owner/repo/issues/7) \
+            ([#12](https://github.com/owner/repo/issues/12)) \
+            ([c3b2a1d](https://github.com/owner/repo/commit/c3b2a1d4))\n"
+        );
+    }
+
+    #[test]
+    fn should_render_asciidoc_preset() {
+        // given
+        let renderer = ChangelogRenderer::preset(Preset::AsciiDoc);
+
+        // when
+        let section = renderer.render_release(&release(), date()).unwrap();
+
+        // then
+        assert_eq!(
+            section,
+            "== 2.0.0 (2024-05-01)\n\n\
+            === Breaking changes\n\n* *api:* add endpoint\n* config file is gone\n\n\
+            === Features\n\n* *api:* add endpoint\n* drop config\n\n\
+            === Bug Fixes\n\n* *cli, api:* crash (#12)\n\n\
+            === Dependencies\n\n* core@2.0.0\n"
+        );
+        assert_eq!(renderer.header, "= Changelog\n");
+    }
+
+    #[test]
+    fn should_render_html_page_with_escaped_text() {
+        // given
+        let config = Config::parse("").unwrap();
+        let links = Links::from(
+            &config.changelog.links,
+            Some("https://github.com/owner/repo.git"),
+        )
+        .unwrap();
+        let renderer = ChangelogRenderer::preset(Preset::KeepAChangelog).with_links(links);
+        let release = Release {
+            history: History::from(&[commit("c3b2a1d4", "fix: render <b> & \"quotes\"")]),
+            ..release()
+        };
+
+        // when
+        let page = renderer
+            .render_html(vec![renderer.release_context(&release, date())])
+            .unwrap();
+
+        // then
+        assert!(page.starts_with("<!DOCTYPE html>"));
+        assert!(page.contains(
+            "<h2><a href=\"https://github.com/owner/repo/compare/v1.0.0...v2.0.0\">2.0.0</a> \
+            <time datetime=\"2024-05-01\">2024-05-01</time></h2>"
+        ));
+        assert!(page.contains(
+            "<li>render &lt;b&gt; &amp; &quot;quotes&quot; \
+            (<a href=\"https://github.com/owner/repo/commit/c3b2a1d4\"><code>c3b2a1d</code></a>)</li>"
+        ));
+        assert!(page.contains("<li>core@2.0.0</li>"));
+        assert!(page.ends_with("</html>\n"));
+    }
+
+    #[test]
+    fn should_render_json_with_schema_version() {
+        // given
+        let renderer = ChangelogRenderer::preset(Preset::KeepAChangelog);
+
+        // when
+        let json = renderer.render_json(&[renderer.release_context(&release(), date())]);
+
+        // then
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let release = &value["releases"][0];
+        assert_eq!(value["schema_version"], 1);
+        assert_eq!(release["version"], "2.0.0");
+        assert_eq!(release["previous_tag"], "v1.0.0");
+        assert_eq!(release["compare_url"], serde_json::Value::Null);
+        assert_eq!(release["commits"][1]["type"], "fix");
+        assert_eq!(release["commits"][1]["scopes"][0], "cli");
+        assert_eq!(release["commits"][1]["issues"][0]["id"], "12");
+        assert_eq!(release["sections"][0]["title"], "Added");
+        assert_eq!(
+            release["breaking_changes"][1]["description"],
+            "config file is gone"
+        );
+    }
+
+    #[test]
+    fn should_process_args() {
+        // given
+        let args: Vec<String> = [
+            "changelog",
+            "--format",
+            "asciidoc",
+            "--output",
+            "CHANGELOG.adoc",
+        ]
+        .iter()
+        .map(|arg| String::from(*arg))
+        .collect();
+
+        // when
+        let args = process_args(&args);
+
+        // then
+        assert_eq!(args.format, Format::Preset(Preset::AsciiDoc));
+        assert_eq!(args.output, Some(String::from("CHANGELOG.adoc")));
+        assert_eq!(args.tag_prefix, None);
+        assert_eq!(Format::from("json"), Some(Format::Json));
+        assert_eq!(Format::from("html"), Some(Format::Html));
+        assert_eq!(Format::from("docx"), None);
+    }
+
+    #[test]
+    fn should_render_release_notes_with_contributors_and_statistics() {
+        // given
+        let renderer = ChangelogRenderer::release_notes(false, &[]);
+        let mut release = release();
+        release.history.skipped.push(String::from("e5"));
+
+        // when
+        let notes = renderer.render_release(&release, date()).unwrap();
+
+        // then
+        assert_eq!(
+            notes,
+            "## 2.0.0 (2024-05-01)\n\n\
+            ### Breaking changes\n\n- **api:** add endpoint\n- config file is gone\n\n\
+            ### Features\n\n- **api:** add endpoint\n- drop config\n\n\
+            ### Bug Fixes\n\n- **cli, api:** crash (#12)\n\n\
+            ### Dependencies\n\n- core@2.0.0\n\n\
+            ### Contributors\n\n- Jane Doe\n\n\
+            ### Statistics\n\n\
+            - Commits: 5 (1 not following conventional commits)\n\
+            - Types: feat 2, docs 1, fix 1\n\
+            - Breaking changes: 2\n\
+            - Contributors: 1\n"
+        );
+    }
+
+    #[test]
+    fn should_render_plain_release_notes() {
+        // given
+        let renderer = ChangelogRenderer::release_notes(true, &[]);
+        let release = Release {
+            history: History::from(&[commit("a", "fix: crash")]),
+            dependencies: vec![],
+            ..release()
+        };
+
+        // when
+        let notes = renderer.render_release(&release, date()).unwrap();
+
+        // then
+        assert_eq!(
+            notes,
+            "Release 2.0.0\n\n\
+            Bug fixes:\n- crash\n\n\
+            Contributors:\n- Jane Doe\n\n\
+            Statistics:\n- Commits: 1\n- Types: fix 1\n- Breaking changes: 0\n- Contributors: 1\n"
+        );
+    }
+
+    #[test]
+    fn should_render_plain_preset_for_several_releases() {
+        // given
+        let renderer = ChangelogRenderer::preset(Preset::Plain);
+        let first = Release {
+            previous: None,
+            version: SemanticVersion::new(1, 0, 0, None, None),
+            tag_name: String::from("v1.0.0"),
+            history: History::from(&[commit("a", "fix: first")]),
+            dependencies: vec![],
+        };
+
+        // when
+        let text = renderer
+            .render(vec![
+                renderer.release_context(&release(), date()),
+                renderer.release_context(&first, date()),
+            ])
+            .unwrap();
+
+        // then
+        assert!(text.ends_with("- core@2.0.0\n\nRelease 1.0.0\n\nBug fixes:\n- first\n"));
+    }
+
+    #[test]
+    fn should_expose_trailers_authors_and_scopes_to_custom_templates() {
+        // given
+        let config = Config::parse(
+            r#"
+[changelog]
+template = """
+{{ releases[0].previous_tag }}..{{ releases[0].tag }} {{ releases[0].scopes | join(sep=",") }}
+{% for author in releases[0].authors %}{{ author.name }} <{{ author.email }}>{% endfor %}
+{% for commit in releases[0].commits %}{% for trailer in commit.trailers %}{{ commit.type }} {{ trailer.key }}={{ trailer.value }};{% endfor %}{% endfor %}
+"""
+"#,
+        )
+        .unwrap();
+        let renderer = ChangelogRenderer::from(&config).unwrap();
+
+        // when
+        let text = renderer.render_release(&release(), date()).unwrap();
+
+        // then
+        assert_eq!(
+            text,
+            "v1.0.0..v2.0.0 api,cli\n\
+            Jane Doe <jane@example.com>\n\
+            fix Closes=#12;feat BREAKING CHANGE=config file is gone;\n"
+        );
+    }
+
+    #[test]
+    fn should_use_configured_sections_and_template_file() {
+        // given
+        let root = std::env::temp_dir().join("con-comm-changelog-template");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(
+            root.join("changelog.tera"),
+            "{% for section in releases[0].sections %}{{ section.title }}: {{ section.commits | length }}\n{% endfor %}",
+        )
+        .unwrap();
+        let mut config = Config::parse(
+            r#"
+[changelog]
+template_file = "changelog.tera"
+
+[[changelog.sections]]
+title = "Everything"
+types = ["feat", "fix", "docs"]
+"#,
+        )
+        .unwrap();
+        config.root = root;
+        let renderer = ChangelogRenderer::from(&config).unwrap();
+
+        // when
+        let text = renderer.render_release(&release(), date()).unwrap();
+
+        // then
+        assert_eq!(text, "Everything: 4\n");
+        assert_eq!(renderer.header, Preset::KeepAChangelog.header());
+    }
+
+    #[test]
+    fn should_report_invalid_templates() {
+        // given
+        let unknown = Config::parse("[changelog]\npreset = \"gnu\"").unwrap();
+        let invalid = Config::parse("[changelog]\ntemplate = \"{% for %}\"").unwrap();
+        let failing = Config::parse("[changelog]\ntemplate = \"{{ missing.value }}\"").unwrap();
+
+        // when
+        let unknown = ChangelogRenderer::from(&unknown).err().unwrap();
+        let invalid = ChangelogRenderer::from(&invalid).err().unwrap();
+        let failing = ChangelogRenderer::from(&failing)
+            .unwrap()
+            .render_release(&release(), date())
+            .err()
+            .unwrap();
+
+        // then
+        assert_eq!(unknown.reason, "Unknown changelog preset: gnu");
+        assert!(invalid.reason.starts_with("Invalid changelog template"));
+        assert!(
+            failing.reason.contains("missing.value"),
+            "{}",
+            failing.reason
+        );
+    }
+
+    #[test]
+    fn should_prepend_section_below_changelog_title() {
+        // given
+        let section = "## 1.1.0 - 2024-05-01\n\n### Features\n\n- new\n";
+        let existing = "# Changelog\n\nAll notable changes.\n\n## 1.0.0 - 2024-04-01\n\n- old\n";
+
+        // then
+        assert_eq!(
+            prepend_changelog(existing, "# Changelog\n", section),
+            "# Changelog\n\nAll notable changes.\n\n\
+            ## 1.1.0 - 2024-05-01\n\n### Features\n\n- new\n\n\
+            ## 1.0.0 - 2024-04-01\n\n- old\n"
+        );
+        assert_eq!(
+            prepend_changelog("", "# Changelog\n", section),
+            format!("# Changelog\n\n{}", section)
+        );
+        assert_eq!(
+            prepend_changelog("# Changelog\n", "# Changelog\n", section),
+            format!("# Changelog\n\n{}", section)
+        );
+    }
+
+    #[test]
+    fn should_prepend_plain_text_releases_below_header() {
+        // given
+        let section = "Release 1.1.0\n\nFeatures:\n- new\n";
+        let existing = "Release 1.0.0\n\nFeatures:\n- old\n";
+
+        // then
+        assert_eq!(
+            prepend_changelog(existing, "", section),
+            format!("{}\n{}", section, existing)
+        );
+        assert_eq!(
+            prepend_changelog(&format!("Changes\n\n{}", existing), "Changes\n", section),
+            format!("Changes\n\n{}\n{}", section, existing)
+        );
+    }
+
+    #[test]
+    fn should_start_new_changelog_with_header() {
+        // given
+        let path = std::env::temp_dir().join("con-comm-changelog-new/CHANGELOG.md");
+        let _ = fs::remove_file(&path);
+        let renderer = ChangelogRenderer::preset(Preset::KeepAChangelog);
+
+        // when
+        let update = update_changelog(&path, &renderer, &release(), date()).unwrap();
+
+        // then
+        assert!(update.before.is_empty());
+        assert!(update
+            .after
+            .starts_with("# Changelog\n\nAll notable changes to this project"));
+        assert!(update.after.contains(
+            "Semantic Versioning](https://semver.org/spec/v2.0.0.html).\n\n## 2.0.0 - 2024-05-01\n"
+        ));
+    }
+}
@@ -0,0 +1,326 @@
+use crate::config::SectionDefinition;
+
+/// Built-in changelog templates.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Preset {
+    /// Markdown following https://keepachangelog.com.
+    KeepAChangelog,
+    /// Markdown in the format of conventional-changelog-angular.
+    Angular,
+    /// Plain text, also used for tag messages.
+    Plain,
+    AsciiDoc,
+}
+
+impl Preset {
+    pub fn from(name: &str) -> Option<Self> {
+        match name {
+            "keep-a-changelog" => Some(Preset::KeepAChangelog),
+            "angular" => Some(Preset::Angular),
+            "plain" => Some(Preset::Plain),
+            "asciidoc" => Some(Preset::AsciiDoc),
+            _ => None,
+        }
+    }
+
+    pub fn template(&self) -> &'static str {
+        match self {
+            Preset::KeepAChangelog => KEEP_A_CHANGELOG_TEMPLATE,
+            Preset::Angular => ANGULAR_TEMPLATE,
+            Preset::Plain => PLAIN_TEMPLATE,
+            Preset::AsciiDoc => ASCIIDOC_TEMPLATE,
+        }
+    }
+
+    /// Text a new changelog starts with.
+    pub fn header(&self) -> &'static str {
+        match self {
+            Preset::KeepAChangelog => KEEP_A_CHANGELOG_HEADER,
+            Preset::Angular => "# Changelog\n",
+            Preset::Plain => "",
+            Preset::AsciiDoc => "= Changelog\n",
+        }
+    }
+
+    pub fn sections(&self) -> Vec<SectionDefinition> {
+        match self {
+            Preset::KeepAChangelog => vec![
+                SectionDefinition::new("Added", &["feat"]),
+                SectionDefinition::new("Changed", &["perf"]),
+                SectionDefinition::new("Fixed", &["fix"]),
+            ],
+            Preset::Angular | Preset::AsciiDoc => vec![
+                SectionDefinition::new("Features", &["feat"]),
+                SectionDefinition::new("Bug Fixes", &["fix"]),
+                SectionDefinition::new("Performance Improvements", &["perf"]),
+                SectionDefinition::new("Reverts", &["revert"]),
+            ],
+            Preset::Plain => vec![
+                SectionDefinition::new("Features", &["feat"]),
+                SectionDefinition::new("Bug fixes", &["fix"]),
+            ],
+        }
+    }
+}
+
+const KEEP_A_CHANGELOG_HEADER: &str = "# Changelog
+
+All notable changes to this project will be documented in this file.
+
+The format is based on [Keep a Changelog](https://keepachangelog.com/en/1.1.0/),
+and this project adheres to [Semantic Versioning](https://semver.org/spec/v2.0.0.html).
+";
+
+const KEEP_A_CHANGELOG_TEMPLATE: &str = "\
+{% for release in releases -%}
+{% if not loop.first %}
+{% endif -%}
+## {% if release.compare_url %}[{{ release.version }}]({{ release.compare_url }}){% else %}{{ release.version }}{% endif %} - {{ release.date }}
+{% if release.breaking_changes %}
+### Breaking changes
+
+{% for change in release.breaking_changes -%}
+- {% if change.scope %}{{ change.scope }}: {% endif %}{{ change.description | link_issues }}
+{% endfor -%}
+{% endif -%}
+{% for section in release.sections %}
+### {{ section.title }}
+
+{% for commit in section.commits -%}
+- {% if commit.scope %}{{ commit.scope }}: {% endif %}{{ commit.description | link_issues }}
+{%- for issue in commit.issues %} ({% if issue.url %}[{{ issue.reference }}]({{ issue.url }}){% else %}{{ issue.reference }}{% endif %}){% endfor %}
+{%- if commit.url %} ([{{ commit.short_id }}]({{ commit.url }})){% endif %}
+{% endfor -%}
+{% endfor -%}
+{% if release.dependencies %}
+### Dependencies
+
+{% for dependency in release.dependencies -%}
+- {{ dependency }}
+{% endfor -%}
+{% endif -%}
+{% endfor -%}
+";
+
+const ANGULAR_TEMPLATE: &str = "\
+{% for release in releases -%}
+{% if not loop.first %}
+{% endif -%}
+## {% if release.compare_url %}[{{ release.version }}]({{ release.compare_url }}){% else %}{{ release.version }}{% endif %} ({{ release.date }})
+{% for section in release.sections %}
+### {{ section.title }}
+
+{% for commit in section.commits -%}
+* {% if commit.scope %}**{{ commit.scope }}:** {% endif %}{{ commit.description | link_issues }} ({% if commit.url %}[{{ commit.short_id }}]({{ commit.url }}){% else %}{{ commit.short_id }}{% endif %})
+{%- for issue in commit.issues %}, {{ issue.key | lower }} {% if issue.url %}[{{ issue.reference }}]({{ issue.url }}){% else %}{{ issue.reference }}{% endif %}{% endfor %}
+{% endfor -%}
+{% endfor -%}
+{% if release.dependencies %}
+### Dependencies
+
+{% for dependency in release.dependencies -%}
+* {{ dependency }}
+{% endfor -%}
+{% endif -%}
+{% if release.breaking_changes %}
+### BREAKING CHANGES
+
+{% for change in release.breaking_changes -%}
+* {% if change.scope %}**{{ change.scope }}:** {% endif %}{{ change.description | link_issues }}
+{% endfor -%}
+{% endif -%}
+{% endfor -%}
+";
+
+// headings aren't prefixed with `#`, as git treats such lines as comments in tag messages
+const PLAIN_TEMPLATE: &str = "\
+{% for release in releases -%}
+{% if not loop.first %}
+{% endif -%}
+Release {{ release.version }}
+{% if release.breaking_changes %}
+Breaking changes:
+{% for change in release.breaking_changes -%}
+- {% if change.scope %}{{ change.scope }}: {% endif %}{{ change.description }}
+{% endfor -%}
+{% endif -%}
+{% for section in release.sections %}
+{{ section.title }}:
+{% for commit in section.commits -%}
+- {% if commit.scope %}{{ commit.scope }}: {% endif %}{{ commit.description }}
+{% endfor -%}
+{% endfor -%}
+{% if release.dependencies %}
+Dependencies:
+{% for dependency in release.dependencies -%}
+- {{ dependency }}
+{% endfor -%}
+{% endif -%}
+{% endfor -%}
+";
+
+/// Notes of a single release, for a release page.
+pub const RELEASE_NOTES_TEMPLATE: &str = "\
+{% for release in releases -%}
+## {% if release.compare_url %}[{{ release.version }}]({{ release.compare_url }}){% else %}{{ release.version }}{% endif %} ({{ release.date }})
+{% if release.breaking_changes %}
+### Breaking changes
+
+{% for change in release.breaking_changes -%}
+- {% if change.scope %}**{{ change.scope }}:** {% endif %}{{ change.description | link_issues }}
+{%- if change.commit.url %} ([{{ change.commit.short_id }}]({{ change.commit.url }})){% endif %}
+{% endfor -%}
+{% endif -%}
+{% for section in release.sections %}
+### {{ section.title }}
+
+{% for commit in section.commits -%}
+- {% if commit.scope %}**{{ commit.scope }}:** {% endif %}{{ commit.description | link_issues }}
+{%- for issue in commit.issues %} ({% if issue.url %}[{{ issue.reference }}]({{ issue.url }}){% else %}{{ issue.reference }}{% endif %}){% endfor %}
+{%- if commit.url %} ([{{ commit.short_id }}]({{ commit.url }})){% endif %}
+{% endfor -%}
+{% endfor -%}
+{% if release.dependencies %}
+### Dependencies
+
+{% for dependency in release.dependencies -%}
+- {{ dependency }}
+{% endfor -%}
+{% endif -%}
+{% if release.contributors %}
+### Contributors
+
+{% for contributor in release.contributors -%}
+- {{ contributor.name }}
+{% endfor -%}
+{% endif %}
+### Statistics
+
+- Commits: {{ release.statistics.commits }}
+{%- if release.statistics.skipped %} ({{ release.statistics.skipped }} not following conventional commits){% endif %}
+{% if release.statistics.types -%}
+- Types: {% for type in release.statistics.types %}{{ type.type }} {{ type.count }}{% if not loop.last %}, {% endif %}{% endfor %}
+{% endif -%}
+- Breaking changes: {{ release.breaking_changes | length }}
+- Contributors: {{ release.contributors | length }}
+{% endfor -%}
+";
+
+/// Notes of a single release, suitable as a tag message.
+pub const PLAIN_RELEASE_NOTES_TEMPLATE: &str = "\
+{% for release in releases -%}
+Release {{ release.version }}
+{% if release.breaking_changes %}
+Breaking changes:
+{% for change in release.breaking_changes -%}
+- {% if change.scope %}{{ change.scope }}: {% endif %}{{ change.description }}
+{% endfor -%}
+{% endif -%}
+{% for section in release.sections %}
+{{ section.title }}:
+{% for commit in section.commits -%}
+- {% if commit.scope %}{{ commit.scope }}: {% endif %}{{ commit.description }}
+{% endfor -%}
+{% endfor -%}
+{% if release.dependencies %}
+Dependencies:
+{% for dependency in release.dependencies -%}
+- {{ dependency }}
+{% endfor -%}
+{% endif -%}
+{% if release.contributors %}
+Contributors:
+{% for contributor in release.contributors -%}
+- {{ contributor.name }}
+{% endfor -%}
+{% endif %}
+Statistics:
+- Commits: {{ release.statistics.commits }}
+{%- if release.statistics.skipped %} ({{ release.statistics.skipped }} not following conventional commits){% endif %}
+{% if release.statistics.types -%}
+- Types: {% for type in release.statistics.types %}{{ type.type }} {{ type.count }}{% if not loop.last %}, {% endif %}{% endfor %}
+{% endif -%}
+- Breaking changes: {{ release.breaking_changes | length }}
+- Contributors: {{ release.contributors | length }}
+{% endfor -%}
+";
+
+const ASCIIDOC_TEMPLATE: &str = "\
+{% for release in releases -%}
+{% if not loop.first %}
+{% endif -%}
+== {% if release.compare_url %}{{ release.compare_url }}[{{ release.version }}]{% else %}{{ release.version }}{% endif %} ({{ release.date }})
+{% if release.breaking_changes %}
+=== Breaking changes
+
+{% for change in release.breaking_changes -%}
+* {% if change.scope %}*{{ change.scope }}:* {% endif %}{{ change.description }}
+{% endfor -%}
+{% endif -%}
+{% for section in release.sections %}
+=== {{ section.title }}
+
+{% for commit in section.commits -%}
+* {% if commit.scope %}*{{ commit.scope }}:* {% endif %}{{ commit.description }}
+{%- for issue in commit.issues %} ({% if issue.url %}{{ issue.url }}[{{ issue.reference }}]{% else %}{{ issue.reference }}{% endif %}){% endfor %}
+{%- if commit.url %} ({{ commit.url }}[{{ commit.short_id }}]){% endif %}
+{% endfor -%}
+{% endfor -%}
+{% if release.dependencies %}
+=== Dependencies
+
+{% for dependency in release.dependencies -%}
+* {{ dependency }}
+{% endfor -%}
+{% endif -%}
+{% endfor -%}
+";
+
+/// Standalone page listing the releases. Rendered with autoescaping, unlike other templates.
+pub const HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Changelog</title>
+<style>
+body { font-family: sans-serif; line-height: 1.5; max-width: 48em; margin: 2em auto; padding: 0 1em; }
+.scope { font-weight: bold; }
+.breaking { color: #b00020; }
+</style>
+</head>
+<body>
+<h1>Changelog</h1>
+{% for release in releases -%}
+<section id="{{ release.tag }}">
+<h2>{% if release.compare_url %}<a href="{{ release.compare_url }}">{{ release.version }}</a>{% else %}{{ release.version }}{% endif %} <time datetime="{{ release.date }}">{{ release.date }}</time></h2>
+{% if release.breaking_changes -%}
+<h3 class="breaking">Breaking changes</h3>
+<ul>
+{% for change in release.breaking_changes -%}
+<li>{% if change.scope %}<span class="scope">{{ change.scope }}:</span> {% endif %}{{ change.description }}</li>
+{% endfor -%}
+</ul>
+{% endif -%}
+{% for section in release.sections -%}
+<h3>{{ section.title }}</h3>
+<ul>
+{% for commit in section.commits -%}
+<li>{% if commit.scope %}<span class="scope">{{ commit.scope }}:</span> {% endif %}{{ commit.description }}
+{%- for issue in commit.issues %} ({% if issue.url %}<a href="{{ issue.url }}">{{ issue.reference }}</a>{% else %}{{ issue.reference }}{% endif %}){% endfor %}
+{%- if commit.url %} (<a href="{{ commit.url }}"><code>{{ commit.short_id }}</code></a>){% endif %}</li>
+{% endfor -%}
+</ul>
+{% endfor -%}
+{% if release.dependencies -%}
+<h3>Dependencies</h3>
+<ul>
+{% for dependency in release.dependencies -%}
+<li>{{ dependency }}</li>
+{% endfor -%}
+</ul>
+{% endif -%}
+</section>
+{% endfor -%}
+</body>
+</html>
+"#;
@@ -1,5 +1,7 @@
-use con_comm::core::conventional_commit::ConventionalCommit;
+use con_comm::config::Config;
 use con_comm::hooks::commit_msg::{process_args, CommitMsgArgs};
+use con_comm::lint::fix::fix;
+use con_comm::lint::lint;
 use std::{env, fs};
 
 /*
@@ -7,13 +9,36 @@ When a `commit-msg` hook is called, the OS will execute a binary with a path to
 file passed as a first argument. This file contains a commit message that we want our linter
 to run against.
 */
-fn main() -> () {
-    let args: CommitMsgArgs = process_args(&env::args().collect());
+fn main() {
+    let args: CommitMsgArgs = process_args(&env::args().collect::<Vec<String>>());
 
-    let file_content = fs::read_to_string(String::from(args.filename))
+    let mut file_content = fs::read_to_string(&args.filename)
         .unwrap_or_else(|e| panic!("Couldn't open file with commit message: {}", e));
 
-    if let Err(e) = ConventionalCommit::from_str(file_content.as_str()) {
-        panic!("Commit message does not match proper format: {}", e);
+    let current_dir = env::current_dir()
+        .unwrap_or_else(|e| panic!("Couldn't determine current directory: {}", e));
+    let config = Config::discover(&current_dir)
+        .unwrap_or_else(|e| panic!("Couldn't load configuration: {}", e));
+
+    if args.fix || config.lint.fix {
+        let result = fix(file_content.as_str(), &config);
+        if !result.fixes.is_empty() {
+            if let Err(e) = fs::write(&args.filename, &result.message) {
+                panic!("Couldn't write fixed commit message: {}", e);
+            }
+            for applied_fix in &result.fixes {
+                eprintln!("fixed: {}", applied_fix);
+            }
+        }
+        file_content = result.message;
+    }
+
+    let violations = lint(file_content.as_str(), &config);
+    if !violations.is_empty() {
+        let reasons: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+        panic!(
+            "Commit message does not match proper format: {}",
+            reasons.join("; ")
+        );
     }
 }
@@ -0,0 +1,55 @@
+use con_comm::config::Config;
+use con_comm::git;
+use con_comm::release::find_version_tags;
+use con_comm::stats::{process_args, Statistics, StatsArgs};
+use std::env;
+use std::fs;
+
+/*
+Reports how a range of history (`from..to`, HEAD by default) follows the conventional commits
+format: counts per type and scope, the share of conventional commits, breaking changes per
+release, a breakdown per author, resolved with the mailmap, and a weekly time series.
+`--format json` prints the same report as JSON. With `--output <file>`, it is written to the
+file instead of the standard output. Commits are read like for changelogs, with message
+overrides, ignored commits and squashed entries applied.
+*/
+fn main() {
+    let args: StatsArgs = process_args(&env::args().collect::<Vec<String>>());
+
+    let current_dir = env::current_dir()
+        .unwrap_or_else(|e| panic!("Couldn't determine current directory: {}", e));
+    let config = Config::discover(&current_dir)
+        .unwrap_or_else(|e| panic!("Couldn't load configuration: {}", e));
+    let options = config
+        .read_options()
+        .unwrap_or_else(|e| panic!("Couldn't load configuration: {}", e));
+    let repository = git::open_history(&current_dir, options)
+        .unwrap_or_else(|e| panic!("Couldn't open repository: {}", e));
+
+    let prefix = args
+        .tag_prefix
+        .as_ref()
+        .unwrap_or(&config.release.tag_prefix);
+    let commits = repository
+        .history_commits(&args.range)
+        .unwrap_or_else(|e| panic!("Couldn't read commits: {}", e));
+    let tags = find_version_tags(repository.as_ref(), prefix)
+        .unwrap_or_else(|e| panic!("Couldn't read tags: {}", e));
+
+    let identities = config
+        .identities(repository.root())
+        .unwrap_or_else(|e| panic!("Couldn't load contributor identities: {}", e));
+
+    let statistics = Statistics::from(&commits, &tags, &identities);
+    let report = match args.json {
+        true => statistics.render_json(),
+        false => statistics.render_text(),
+    };
+
+    match &args.output {
+        Some(path) => {
+            fs::write(path, report).unwrap_or_else(|e| panic!("Couldn't write {}: {}", path, e))
+        }
+        None => print!("{}", report),
+    }
+}
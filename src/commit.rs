@@ -0,0 +1,78 @@
+use con_comm::config::Config;
+use con_comm::git;
+use con_comm::hooks::prepare_msg::TemplateContext;
+use con_comm::scope::ScopeResolver;
+use con_comm::wizard::{process_args, run, Answers, CommitArgs, IoPrompter};
+use std::path::Path;
+use std::{env, fs, io, process};
+
+/*
+Interactive alternative to `git commit -m`. It asks for every part of a conventional commit
+message, validates the answers and then either runs `git commit` with the assembled message
+or writes it to a file, e.g. the one passed to the `prepare-commit-msg` hook.
+*/
+fn main() {
+    let args: CommitArgs = process_args(&env::args().collect::<Vec<String>>());
+
+    let current_dir = env::current_dir()
+        .unwrap_or_else(|e| panic!("Couldn't determine current directory: {}", e));
+    let config = Config::discover(&current_dir)
+        .unwrap_or_else(|e| panic!("Couldn't load configuration: {}", e));
+
+    let defaults = get_defaults(&current_dir, &config);
+    let stdin = io::stdin();
+    let mut prompter = IoPrompter::new(stdin.lock(), io::stdout());
+    let answers = run(&mut prompter, &config, &defaults)
+        .unwrap_or_else(|e| panic!("Couldn't prepare commit message: {}", e));
+    let message = answers
+        .to_message()
+        .unwrap_or_else(|e| panic!("Couldn't assemble commit message: {}", e));
+
+    if let Some(message_file) = args.message_file {
+        if let Err(e) = fs::write(&message_file, message) {
+            panic!("Couldn't write message to file {}: {}", message_file, e);
+        }
+        return;
+    }
+
+    let status = git::cli::commit(&message, &args.git_args)
+        .unwrap_or_else(|e| panic!("Couldn't run git commit: {}", e));
+    process::exit(status.code().unwrap_or(1));
+}
+
+/// Suggestions derived from the branch name and staged files, same as in the `prepare-msg` hook.
+fn get_defaults(current_dir: &Path, config: &Config) -> Answers {
+    let repository = match git::open(current_dir) {
+        Ok(repository) => repository,
+        Err(_) => return Answers::default(),
+    };
+
+    let inferred_scopes = match ScopeResolver::from(config) {
+        Ok(resolver) if !resolver.is_empty() => {
+            resolver.infer(&repository.staged_paths().unwrap_or_default())
+        }
+        _ => vec![],
+    };
+
+    let branch = repository.current_branch().ok().flatten();
+    let context = match TemplateContext::from(branch, inferred_scopes, config) {
+        Ok(context) => context,
+        Err(_) => return Answers::default(),
+    };
+
+    let branch_info = context.branch_info;
+    let commit_type = branch_info
+        .commit_type
+        .clone()
+        .filter(|commit_type| config.find_type(commit_type).is_some());
+
+    return Answers {
+        commit_type: commit_type.unwrap_or_default(),
+        scope: branch_info.scope.clone(),
+        references: branch_info
+            .trailer(&config.branches.ticket_trailer)
+            .into_iter()
+            .collect(),
+        ..Answers::default()
+    };
+}
@@ -0,0 +1,736 @@
+use crate::core::contributors::{Identities, Mailmap};
+use crate::core::glob::Glob;
+use crate::git::reader::{IgnoreRules, ReadOptions};
+use regex::Regex;
+use serde::Deserialize;
+use std::fmt;
+use std::fmt::Formatter;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const CONFIG_FILE_NAME: &str = ".conventional-commits.toml";
+
+#[derive(Debug)]
+pub struct ConfigError {
+    pub path: String,
+    pub reason: String,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.reason)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Directory containing the configuration file; relative paths are resolved against it.
+    #[serde(skip)]
+    pub root: PathBuf,
+    pub types: Vec<TypeDefinition>,
+    pub scopes: Vec<String>,
+    pub prepare_msg: PrepareMessageConfig,
+    pub branches: BranchConfig,
+    pub scope_inference: ScopeInferenceConfig,
+    pub lint: LintConfig,
+    pub release: ReleaseConfig,
+    pub monorepo: MonorepoConfig,
+    pub changelog: ChangelogConfig,
+    pub contributors: ContributorConfig,
+    pub history: HistoryConfig,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct TypeDefinition {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+impl TypeDefinition {
+    pub fn new(name: &str, description: Option<&str>) -> Self {
+        Self {
+            name: String::from(name),
+            description: description.map(String::from),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct PrepareMessageConfig {
+    /// Inline template, takes precedence over `template_file`.
+    pub template: Option<String>,
+    pub template_file: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct BranchConfig {
+    /// Regular expressions with optional `type`, `scope` and `ticket` named groups.
+    pub patterns: Vec<String>,
+    /// Trailer used to reference the ticket found in the branch name.
+    pub ticket_trailer: String,
+}
+
+impl Default for BranchConfig {
+    fn default() -> Self {
+        Self {
+            patterns: vec![
+                // feat/payments-api, fix/JIRA-123-null-pointer
+                String::from(
+                    r"^(?<type>[a-z]+)/(?:(?<ticket>[A-Z][A-Z0-9]*-\d+)(?:-.*)?|(?<scope>[a-z0-9][a-z0-9._-]*))$",
+                ),
+                // JIRA-123-null-pointer
+                String::from(r"^(?<ticket>[A-Z][A-Z0-9]*-\d+)(?:-.*)?$"),
+            ],
+            ticket_trailer: String::from("Refs"),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ScopeInferenceConfig {
+    /// Glob rules, checked in order before workspace packages.
+    pub rules: Vec<ScopeRule>,
+    /// Whether Cargo workspace members should be used as scopes.
+    pub workspace: bool,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct ScopeRule {
+    pub glob: String,
+    pub scope: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct LintConfig {
+    /// Reject types missing from the type registry.
+    pub enforce_types: bool,
+    /// Longest allowed header, unlimited by default.
+    pub max_header_length: Option<usize>,
+    /// Longest allowed body line, unlimited by default. `--fix` wraps longer lines, at 72
+    /// characters when not configured.
+    pub max_body_line_length: Option<usize>,
+    /// Report problems `--fix` can fix, such as an upper-cased type or a trailing period,
+    /// which the conventional commits grammar itself accepts.
+    pub enforce_style: bool,
+    /// Fix mechanically fixable problems even when `--fix` was not given, e.g. in the hook.
+    pub fix: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct ReleaseConfig {
+    /// Prefix of release tags, e.g. `v` for `v1.2.3` or `mylib-v` for `mylib-v1.2.3`.
+    pub tag_prefix: String,
+    /// Manifests the version is written into, relative to the repository root. Known manifests
+    /// in the root and manifests of Cargo workspace members are used when empty.
+    pub manifests: Vec<String>,
+    /// Changelog release notes are prepended to, relative to the repository root or to the
+    /// package directory in a monorepo. An empty path disables the changelog.
+    pub changelog: String,
+}
+
+impl Default for ReleaseConfig {
+    fn default() -> Self {
+        Self {
+            tag_prefix: String::from("v"),
+            manifests: vec![],
+            changelog: String::from("CHANGELOG.md"),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct MonorepoConfig {
+    /// Packages released independently, in addition to Cargo workspace members.
+    pub packages: Vec<PackageDefinition>,
+    /// Whether Cargo workspace members should be released independently.
+    pub workspace: bool,
+    pub attribution: Attribution,
+    /// Release packages depending on a released package, with at least a patch increment.
+    pub bump_dependents: bool,
+}
+
+impl Default for MonorepoConfig {
+    fn default() -> Self {
+        Self {
+            packages: vec![],
+            workspace: false,
+            attribution: Attribution::default(),
+            bump_dependents: true,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct PackageDefinition {
+    pub name: String,
+    /// Slash separated path of the package directory, relative to the repository root.
+    pub path: String,
+    /// Scopes attributing commits to the package, in addition to its name.
+    #[serde(default)]
+    pub scopes: Vec<String>,
+}
+
+/// How commits are attributed to packages of a monorepo.
+#[derive(Debug, Default, Deserialize, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum Attribution {
+    /// Commits touching files of the package.
+    Files,
+    /// Commits with the package name, or one of its scopes, among their scopes.
+    Scope,
+    #[default]
+    FilesOrScope,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct ChangelogConfig {
+    /// Built-in template: `keep-a-changelog`, `angular` or `plain`.
+    pub preset: String,
+    /// Inline Tera template, takes precedence over `template_file` and the preset.
+    pub template: Option<String>,
+    pub template_file: Option<String>,
+    /// Text a new changelog starts with, the preset's header by default.
+    pub header: Option<String>,
+    /// Sections commits are grouped into, in order. The preset's sections by default.
+    pub sections: Vec<SectionDefinition>,
+    pub links: LinkConfig,
+}
+
+impl Default for ChangelogConfig {
+    fn default() -> Self {
+        Self {
+            preset: String::from("keep-a-changelog"),
+            template: None,
+            template_file: None,
+            header: None,
+            sections: vec![],
+            links: LinkConfig::default(),
+        }
+    }
+}
+
+/// URL templates for links in changelogs. Templates missing here come from the preset of the
+/// hosting service.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct LinkConfig {
+    /// Remote the repository URL is read from.
+    pub remote: String,
+    /// Hosting service: `github`, `gitlab`, `gitea` or `bitbucket`. Detected from the host
+    /// name of the remote when missing.
+    pub host: Option<String>,
+    /// Link to a commit, with `{{repository}}` and `{{id}}` placeholders.
+    pub commit: Option<String>,
+    /// Link comparing two tags, with `{{repository}}`, `{{from}}` and `{{to}}` placeholders.
+    pub compare: Option<String>,
+    /// Link to an issue, with `{{repository}}` and `{{id}}` placeholders.
+    pub issue: Option<String>,
+}
+
+impl Default for LinkConfig {
+    fn default() -> Self {
+        Self {
+            remote: String::from("origin"),
+            host: None,
+            commit: None,
+            compare: None,
+            issue: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct SectionDefinition {
+    pub title: String,
+    /// Commit types listed in the section.
+    pub types: Vec<String>,
+}
+
+impl SectionDefinition {
+    pub fn new(title: &str, types: &[&str]) -> Self {
+        Self {
+            title: String::from(title),
+            types: types.iter().map(|t| String::from(*t)).collect(),
+        }
+    }
+}
+
+/// Identities of commit authors and co-authors in contributor lists.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ContributorConfig {
+    /// Mailmap file, `.mailmap` in the repository root by default.
+    pub mailmap: Option<String>,
+    /// Regular expressions matched against names and emails of contributors to leave out,
+    /// e.g. `"\\[bot\\]"` for bots.
+    pub exclude: Vec<String>,
+}
+
+/// How commits are read into releases and changelogs.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct HistoryConfig {
+    /// Read conventional entries listed in the body of squash commits, e.g. `* feat: ...`, as
+    /// commits of their own.
+    pub squashed_entries: bool,
+    /// Notes reference holding replacement messages of commits. Empty disables overrides.
+    pub notes_ref: String,
+    pub ignore: IgnoreConfig,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            squashed_entries: false,
+            notes_ref: String::from("refs/notes/conventional"),
+            ignore: IgnoreConfig::default(),
+        }
+    }
+}
+
+/// Commits left out of linted ranges, releases and changelogs.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct IgnoreConfig {
+    /// Tag or commit ignored along with all of its ancestors, e.g. the last commit before
+    /// adopting conventional commits.
+    pub before: Option<String>,
+    /// Ids of ignored commits, possibly abbreviated.
+    pub commits: Vec<String>,
+    /// Regular expressions matched against messages of ignored commits.
+    pub messages: Vec<String>,
+    /// Globs of repository relative paths, commits changing only such paths are ignored.
+    pub paths: Vec<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            root: PathBuf::from("."),
+            types: default_types(),
+            scopes: vec![],
+            prepare_msg: PrepareMessageConfig::default(),
+            branches: BranchConfig::default(),
+            scope_inference: ScopeInferenceConfig::default(),
+            lint: LintConfig::default(),
+            release: ReleaseConfig::default(),
+            monorepo: MonorepoConfig::default(),
+            changelog: ChangelogConfig::default(),
+            contributors: ContributorConfig::default(),
+            history: HistoryConfig::default(),
+        }
+    }
+}
+
+impl Config {
+    pub fn parse(content: &str) -> Result<Self, ConfigError> {
+        return toml::from_str::<Config>(content).map_err(|e| ConfigError {
+            path: String::from(CONFIG_FILE_NAME),
+            reason: e.message().to_string(),
+        });
+    }
+
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let content = fs::read_to_string(path).map_err(|e| ConfigError {
+            path: path.display().to_string(),
+            reason: e.to_string(),
+        })?;
+
+        let mut config = Self::parse(&content).map_err(|e| ConfigError {
+            path: path.display().to_string(),
+            reason: e.reason,
+        })?;
+        if let Some(root) = path.parent() {
+            config.root = root.to_path_buf();
+        }
+
+        return Ok(config);
+    }
+
+    /// Looks for the configuration file in `start` and its ancestors, falling back to defaults.
+    pub fn discover(start: &Path) -> Result<Self, ConfigError> {
+        for directory in start.ancestors() {
+            let candidate = directory.join(CONFIG_FILE_NAME);
+            if candidate.is_file() {
+                return Self::load(&candidate);
+            }
+        }
+
+        return Ok(Self {
+            root: start.to_path_buf(),
+            ..Self::default()
+        });
+    }
+
+    pub fn resolve_path(&self, path: &str) -> PathBuf {
+        self.root.join(path)
+    }
+
+    /// Mailmap and excluded contributors of the `[contributors]` configuration. A missing
+    /// default mailmap in the repository root is the same as an empty one.
+    pub fn identities(&self, repository_root: &Path) -> Result<Identities, ConfigError> {
+        let path = match &self.contributors.mailmap {
+            Some(mailmap) => self.resolve_path(mailmap),
+            None => repository_root.join(".mailmap"),
+        };
+        let mailmap = match fs::read_to_string(&path) {
+            Ok(content) => Mailmap::from(&content),
+            Err(_) if self.contributors.mailmap.is_none() && !path.exists() => Mailmap::default(),
+            Err(e) => {
+                return Err(ConfigError {
+                    path: path.display().to_string(),
+                    reason: format!("Couldn't read mailmap: {}", e),
+                })
+            }
+        };
+
+        let exclude = self
+            .contributors
+            .exclude
+            .iter()
+            .map(|pattern| {
+                Regex::new(pattern).map_err(|e| ConfigError {
+                    path: String::from(CONFIG_FILE_NAME),
+                    reason: format!("Invalid contributor pattern {}: {}", pattern, e),
+                })
+            })
+            .collect::<Result<Vec<Regex>, ConfigError>>()?;
+
+        return Ok(Identities { mailmap, exclude });
+    }
+
+    /// Options of the `[history]` configuration for reading commits.
+    pub fn read_options(&self) -> Result<ReadOptions, ConfigError> {
+        let ignore = &self.history.ignore;
+        let invalid = |pattern: &str, reason: String| ConfigError {
+            path: String::from(CONFIG_FILE_NAME),
+            reason: format!("Invalid ignore pattern {}: {}", pattern, reason),
+        };
+        let messages = ignore
+            .messages
+            .iter()
+            .map(|pattern| Regex::new(pattern).map_err(|e| invalid(pattern, e.to_string())))
+            .collect::<Result<Vec<Regex>, ConfigError>>()?;
+        let paths = ignore
+            .paths
+            .iter()
+            .map(|pattern| Glob::new(pattern).map_err(|e| invalid(pattern, e.reason)))
+            .collect::<Result<Vec<Glob>, ConfigError>>()?;
+
+        return Ok(ReadOptions {
+            squashed_entries: self.history.squashed_entries,
+            notes_ref: Some(self.history.notes_ref.clone())
+                .filter(|notes_ref| !notes_ref.is_empty()),
+            ignore: IgnoreRules {
+                before: ignore.before.clone(),
+                commits: ignore.commits.clone(),
+                messages,
+                paths,
+            },
+        });
+    }
+
+    pub fn find_type(&self, name: &str) -> Option<&TypeDefinition> {
+        self.types.iter().find(|t| t.name == name)
+    }
+}
+
+fn default_types() -> Vec<TypeDefinition> {
+    vec![
+        TypeDefinition::new("fix", Some("correlates with PATCH in semver")),
+        TypeDefinition::new("feat", Some("correlates with MINOR in semver")),
+        TypeDefinition::new("build", None),
+        TypeDefinition::new("chore", None),
+        TypeDefinition::new("ci", None),
+        TypeDefinition::new("docs", None),
+        TypeDefinition::new("style", None),
+        TypeDefinition::new("refactor", None),
+        TypeDefinition::new("perf", None),
+        TypeDefinition::new("test", None),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{
+        Attribution, Config, PackageDefinition, ScopeRule, SectionDefinition, TypeDefinition,
+    };
+    use crate::core::contributors::Contributor;
+    use std::fs;
+
+    #[test]
+    fn should_use_default_types_when_config_is_empty() {
+        // given
+        let content = "";
+
+        // when
+        let config = Config::parse(content).unwrap();
+
+        // then
+        assert!(config.find_type("feat").is_some());
+        assert!(config.find_type("fix").is_some());
+        assert!(config.scopes.is_empty());
+        assert!(config.prepare_msg.template.is_none());
+    }
+
+    #[test]
+    fn should_parse_types_scopes_and_template() {
+        // given
+        let content = r#"
+scopes = ["api", "ui"]
+
+[[types]]
+name = "feat"
+description = "a new feature"
+
+[[types]]
+name = "wip"
+
+[prepare_msg]
+template = "{{branch}}"
+"#;
+
+        // when
+        let config = Config::parse(content).unwrap();
+
+        // then
+        assert_eq!(
+            config.types,
+            vec![
+                TypeDefinition::new("feat", Some("a new feature")),
+                TypeDefinition::new("wip", None),
+            ]
+        );
+        assert_eq!(config.scopes, vec![String::from("api"), String::from("ui")]);
+        assert_eq!(
+            config.prepare_msg.template,
+            Some(String::from("{{branch}}"))
+        );
+    }
+
+    #[test]
+    fn should_parse_branch_patterns() {
+        // given
+        let content = r#"
+[branches]
+patterns = ['^(?<type>\w+)-(?<scope>\w+)$']
+ticket_trailer = "Closes"
+"#;
+
+        // when
+        let config = Config::parse(content).unwrap();
+
+        // then
+        assert_eq!(
+            config.branches.patterns,
+            vec![String::from(r"^(?<type>\w+)-(?<scope>\w+)$")]
+        );
+        assert_eq!(config.branches.ticket_trailer, "Closes");
+    }
+
+    #[test]
+    fn should_parse_scope_inference_rules() {
+        // given
+        let content = r#"
+[scope_inference]
+workspace = true
+
+[[scope_inference.rules]]
+glob = "src/api/**"
+scope = "api"
+"#;
+
+        // when
+        let config = Config::parse(content).unwrap();
+
+        // then
+        assert!(config.scope_inference.workspace);
+        assert_eq!(
+            config.scope_inference.rules,
+            vec![ScopeRule {
+                glob: String::from("src/api/**"),
+                scope: String::from("api"),
+            }]
+        );
+    }
+
+    #[test]
+    fn should_parse_release_tag_prefix() {
+        // when
+        let default = Config::parse("").unwrap();
+        let custom = Config::parse(
+            "[release]\ntag_prefix = \"mylib-v\"\nmanifests = [\"Cargo.toml\", \"web/package.json\"]",
+        )
+        .unwrap();
+
+        // then
+        assert_eq!(default.release.tag_prefix, "v");
+        assert!(default.release.manifests.is_empty());
+        assert_eq!(custom.release.tag_prefix, "mylib-v");
+        assert_eq!(
+            custom.release.manifests,
+            vec![String::from("Cargo.toml"), String::from("web/package.json")]
+        );
+    }
+
+    #[test]
+    fn should_parse_monorepo_packages() {
+        // given
+        let content = r#"
+[monorepo]
+workspace = true
+attribution = "files"
+bump_dependents = false
+
+[[monorepo.packages]]
+name = "web"
+path = "frontend/web"
+scopes = ["ui"]
+"#;
+
+        // when
+        let config = Config::parse(content).unwrap();
+        let default = Config::parse("").unwrap();
+
+        // then
+        assert!(config.monorepo.workspace);
+        assert_eq!(config.monorepo.attribution, Attribution::Files);
+        assert!(!config.monorepo.bump_dependents);
+        assert_eq!(
+            config.monorepo.packages,
+            vec![PackageDefinition {
+                name: String::from("web"),
+                path: String::from("frontend/web"),
+                scopes: vec![String::from("ui")],
+            }]
+        );
+        assert_eq!(default.monorepo.attribution, Attribution::FilesOrScope);
+        assert!(default.monorepo.bump_dependents);
+        assert_eq!(default.release.changelog, "CHANGELOG.md");
+    }
+
+    #[test]
+    fn should_parse_changelog_sections() {
+        // given
+        let content = r#"
+[changelog]
+preset = "angular"
+template_file = "changelog.tera"
+
+[[changelog.sections]]
+title = "New"
+types = ["feat"]
+"#;
+
+        // when
+        let config = Config::parse(content).unwrap();
+        let default = Config::parse("").unwrap();
+
+        // then
+        assert_eq!(config.changelog.preset, "angular");
+        assert_eq!(
+            config.changelog.template_file,
+            Some(String::from("changelog.tera"))
+        );
+        assert_eq!(
+            config.changelog.sections,
+            vec![SectionDefinition::new("New", &["feat"])]
+        );
+        assert_eq!(default.changelog.preset, "keep-a-changelog");
+        assert!(default.changelog.sections.is_empty());
+    }
+
+    #[test]
+    fn should_parse_history_options() {
+        // given
+        let content = r#"
+[history]
+squashed_entries = true
+notes_ref = ""
+
+[history.ignore]
+before = "v0.9.0"
+commits = ["abc1234"]
+messages = ["^Merge "]
+paths = ["docs/**"]
+"#;
+
+        // when
+        let options = Config::parse(content).unwrap().read_options().unwrap();
+        let default = Config::default().read_options().unwrap();
+
+        // then
+        assert!(options.squashed_entries);
+        assert_eq!(options.notes_ref, None);
+        assert_eq!(options.ignore.before, Some(String::from("v0.9.0")));
+        assert_eq!(options.ignore.commits, vec![String::from("abc1234")]);
+        assert!(options.ignore.messages[0].is_match("Merge branch 'main'"));
+        assert!(options.ignore.paths[0].matches("docs/guide/index.md"));
+        assert!(Config::parse("[history.ignore]\nmessages = ['(']")
+            .unwrap()
+            .read_options()
+            .is_err());
+        assert!(!default.squashed_entries);
+        assert_eq!(
+            default.notes_ref,
+            Some(String::from("refs/notes/conventional"))
+        );
+    }
+
+    #[test]
+    fn should_load_identities() {
+        // given
+        let root = std::env::temp_dir().join("con-comm-identities");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(
+            root.join(".mailmap"),
+            "Jane Doe <jane@example.com> <jane@old.example.com>\n",
+        )
+        .unwrap();
+        let mut config = Config::parse("[contributors]\nexclude = ['\\[bot\\]$']").unwrap();
+        config.root = root.clone();
+
+        // when
+        let identities = config.identities(&root).unwrap();
+        let missing = Config::default().identities(&root.join("missing")).unwrap();
+        config.contributors.mailmap = Some(String::from("missing.mailmap"));
+        let error = config.identities(&root).unwrap_err();
+
+        // then
+        let contributor = identities.resolve(&Contributor::new("jane", "jane@old.example.com"));
+        assert_eq!(
+            contributor,
+            Contributor::new("Jane Doe", "jane@example.com")
+        );
+        assert!(identities.is_excluded(&Contributor::new("renovate[bot]", "bot@example.com")));
+        assert_eq!(
+            missing.resolve(&Contributor::new("jane", "jane@old.example.com")),
+            Contributor::new("jane", "jane@old.example.com")
+        );
+        assert!(error.reason.starts_with("Couldn't read mailmap"));
+        assert!(Config::parse("[contributors]\nexclude = ['[']")
+            .unwrap()
+            .identities(&root)
+            .is_err());
+    }
+
+    #[test]
+    fn should_return_error_for_malformed_config() {
+        // given
+        let content = "scopes = 12";
+
+        // when
+        let result = Config::parse(content);
+
+        // then
+        assert!(result.is_err(), "An Error should have been returned");
+    }
+}
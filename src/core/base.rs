@@ -1,5 +1,6 @@
 use std::fmt;
 use std::fmt::Formatter;
+use std::str::FromStr;
 
 #[derive(Debug)]
 pub struct ParseError {
@@ -13,7 +14,9 @@ impl fmt::Display for ParseError {
     }
 }
 
-#[derive(Debug, PartialEq)]
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
 pub struct Paragraph {
     pub lines: Vec<String>,
 }
@@ -23,14 +26,9 @@ impl Paragraph {
         Self { lines: vec![] }
     }
 
-    pub fn from(other: &Paragraph) -> Self {
-        return Self {
-            lines: other.lines.to_vec(),
-        };
-    }
-
+    #[allow(clippy::result_unit_err)]
     pub fn add_line(&mut self, line: &str) -> Result<(), ()> {
-        if line.len() == 0 {
+        if line.is_empty() {
             return Err(());
         }
 
@@ -42,6 +40,10 @@ impl Paragraph {
         self.lines.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
     pub fn get_line(&self, num: usize) -> Option<&String> {
         self.lines.get(num)
     }
@@ -53,7 +55,7 @@ impl Paragraph {
     pub fn folded(&self) -> Paragraph {
         let mut folded_lines: Vec<String> = vec![];
 
-        if self.lines.len() > 0 {
+        if !self.lines.is_empty() {
             let mut lines_iterator = self.lines.iter();
 
             let mut current_line = String::from(lines_iterator.next().unwrap());
@@ -65,8 +67,8 @@ impl Paragraph {
                     break;
                 }
 
-                if next_line.unwrap().starts_with(" ") {
-                    current_line.push_str(" ");
+                if next_line.unwrap().starts_with(' ') {
+                    current_line.push(' ');
                     current_line.push_str(next_line.unwrap().trim_start());
                 } else {
                     folded_lines.push(current_line);
@@ -81,9 +83,50 @@ impl Paragraph {
     }
 }
 
+/// Lines of the text, which must not be empty.
+impl FromStr for Paragraph {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut paragraph = Paragraph::new();
+        for line in text.lines() {
+            if paragraph.add_line(line).is_err() {
+                return Err(ParseError {
+                    line: String::from(text),
+                    reason: String::from("Paragraph cannot contain empty lines"),
+                });
+            }
+        }
+
+        if paragraph.is_empty() {
+            return Err(ParseError {
+                line: String::from(text),
+                reason: String::from("Paragraph has to have at least one line"),
+            });
+        }
+
+        return Ok(paragraph);
+    }
+}
+
+impl TryFrom<&str> for Paragraph {
+    type Error = ParseError;
+
+    fn try_from(text: &str) -> Result<Self, Self::Error> {
+        Self::from_str(text)
+    }
+}
+
+impl fmt::Display for Paragraph {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.lines.join("\n"))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::core::base::Paragraph;
+    use std::str::FromStr;
 
     #[test]
     fn should_fold_line_starting_with_trailing_space_into_previous_line() {
@@ -142,4 +185,17 @@ mod tests {
             }
         )
     }
+
+    #[test]
+    fn should_parse_and_display_paragraph() {
+        // when
+        let paragraph = Paragraph::from_str("first line\nsecond line").unwrap();
+
+        // then
+        assert_eq!(paragraph.lines, vec!["first line", "second line"]);
+        assert_eq!(paragraph.to_string(), "first line\nsecond line");
+        assert_eq!(Paragraph::try_from("first line").unwrap().len(), 1);
+        assert!(Paragraph::from_str("").is_err());
+        assert!(Paragraph::from_str("first\n\nsecond").is_err());
+    }
 }
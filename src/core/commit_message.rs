@@ -5,8 +5,8 @@ pub struct CommitMessage {
     pub paragraphs: Vec<Paragraph>,
 }
 
-impl CommitMessage {
-    pub fn from(file_content: &str) -> Self {
+impl From<&str> for CommitMessage {
+    fn from(file_content: &str) -> Self {
         let mut paragraphs: Vec<Paragraph> = vec![];
 
         let mut current_paragraph: Paragraph = Paragraph::new();
@@ -17,44 +17,46 @@ impl CommitMessage {
                     .add_line(trimmed_line)
                     .expect("Failed to add line to paragraph")
             } else {
-                if current_paragraph.len() > 0 {
+                if !current_paragraph.is_empty() {
                     paragraphs.push(current_paragraph);
                 }
                 current_paragraph = Paragraph::new()
             }
         }
 
-        if current_paragraph.len() > 0 {
+        if !current_paragraph.is_empty() {
             paragraphs.push(current_paragraph);
         }
 
         return CommitMessage { paragraphs };
     }
+}
 
+impl CommitMessage {
     pub fn get_paragraphs(&self) -> VecDeque<Paragraph> {
         let mut deque: VecDeque<Paragraph> = VecDeque::with_capacity(self.paragraphs.len());
         for par in self.paragraphs.iter() {
-            deque.push_back(Paragraph::from(par));
+            deque.push_back(par.clone());
         }
 
         return deque;
     }
 
-    pub fn get_paragraph(self: &Self, num: usize) -> Option<&Paragraph> {
+    pub fn get_paragraph(&self, num: usize) -> Option<&Paragraph> {
         if num >= self.paragraphs.len() {
             return None;
         }
 
-        let ref paragraph = self.paragraphs[num];
+        let paragraph = &self.paragraphs[num];
         return Some(paragraph);
     }
 
     fn is_message_line(line: &str) -> bool {
-        if line.len() == 0 {
+        if line.is_empty() {
             return false;
         }
 
-        if line.starts_with("#") {
+        if line.starts_with('#') {
             return false;
         }
 
@@ -0,0 +1,254 @@
+extern crate lazy_static;
+
+use crate::core::conventional_commit::ConventionalCommit;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref IDENTITY_REGEX: Regex = Regex::new(r"([^<>]*)<([^<>]*)>").unwrap();
+}
+
+/// Trailers naming people who contributed to a commit besides its author.
+pub const CONTRIBUTOR_TRAILERS: [&str; 2] = ["Co-authored-by", "Signed-off-by"];
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Contributor {
+    pub name: String,
+    pub email: String,
+}
+
+impl Contributor {
+    pub fn new(name: &str, email: &str) -> Self {
+        Self {
+            name: String::from(name),
+            email: String::from(email),
+        }
+    }
+
+    /// Parses `Name <email>`, as in `Co-authored-by` trailers.
+    pub fn parse(identity: &str) -> Option<Self> {
+        let captures = IDENTITY_REGEX.captures(identity.trim())?;
+        let email = captures[2].trim();
+        if email.is_empty() {
+            return None;
+        }
+
+        return Some(Self::new(captures[1].trim(), email));
+    }
+
+    /// Whether both are the same person, judging by the email address.
+    pub fn is_same(&self, other: &Contributor) -> bool {
+        self.email.eq_ignore_ascii_case(&other.email)
+    }
+}
+
+/// Contributors named in `Co-authored-by` and `Signed-off-by` trailers, in order.
+pub fn find_trailer_contributors(commit: &ConventionalCommit) -> Vec<Contributor> {
+    return match &commit.footer {
+        Some(footer) => footer
+            .elements
+            .iter()
+            .filter(|element| {
+                CONTRIBUTOR_TRAILERS
+                    .iter()
+                    .any(|key| element.key().eq_ignore_ascii_case(key))
+            })
+            .filter_map(|element| Contributor::parse(element.value()))
+            .collect(),
+        None => vec![],
+    };
+}
+
+#[derive(Debug, PartialEq, Clone)]
+struct MailmapEntry {
+    proper_name: Option<String>,
+    proper_email: Option<String>,
+    commit_name: Option<String>,
+    commit_email: String,
+}
+
+/// Canonical names and emails of contributors, read from a `.mailmap` file in the format of
+/// git, see `git help check-mailmap`.
+#[derive(Debug, Default, Clone)]
+pub struct Mailmap {
+    entries: Vec<MailmapEntry>,
+}
+
+impl Mailmap {
+    /// Parses the file content. Lines that can't be parsed are ignored, like git does.
+    pub fn from(content: &str) -> Self {
+        let entries = content
+            .lines()
+            .filter_map(|line| {
+                let line = line.split('#').next().unwrap_or_default();
+                let identities: Vec<(&str, &str)> = IDENTITY_REGEX
+                    .captures_iter(line)
+                    .map(|c| (c.get(1).unwrap().as_str(), c.get(2).unwrap().as_str()))
+                    .collect();
+
+                let (proper_name, proper_email, commit_name, commit_email) = match identities[..] {
+                    [(name, email)] => (name, "", "", email),
+                    [(proper_name, proper_email), (commit_name, commit_email), ..] => {
+                        (proper_name, proper_email, commit_name, commit_email)
+                    }
+                    _ => return None,
+                };
+
+                let optional = |value: &str| match value.trim() {
+                    "" => None,
+                    value => Some(String::from(value)),
+                };
+                return Some(MailmapEntry {
+                    proper_name: optional(proper_name),
+                    proper_email: optional(proper_email),
+                    commit_name: optional(commit_name),
+                    commit_email: optional(commit_email)?,
+                });
+            })
+            .collect();
+
+        return Self { entries };
+    }
+
+    /// Canonical identity of the contributor. Emails and names are matched ignoring case, and
+    /// entries with a commit name take precedence over those with the email only.
+    pub fn resolve(&self, contributor: &Contributor) -> Contributor {
+        let matching = |with_name: bool| {
+            self.entries.iter().rev().find(|entry| {
+                entry.commit_email.eq_ignore_ascii_case(&contributor.email)
+                    && match &entry.commit_name {
+                        Some(name) => with_name && name.eq_ignore_ascii_case(&contributor.name),
+                        None => !with_name,
+                    }
+            })
+        };
+
+        return match matching(true).or_else(|| matching(false)) {
+            Some(entry) => Contributor {
+                name: entry
+                    .proper_name
+                    .clone()
+                    .unwrap_or(contributor.name.clone()),
+                email: entry
+                    .proper_email
+                    .clone()
+                    .unwrap_or(contributor.email.clone()),
+            },
+            None => contributor.clone(),
+        };
+    }
+}
+
+/// How identities of contributors are normalised, and which of them are left out of
+/// contributor lists.
+#[derive(Debug, Default, Clone)]
+pub struct Identities {
+    pub mailmap: Mailmap,
+    /// Patterns matched against names and emails of contributors to leave out, e.g. bots.
+    pub exclude: Vec<Regex>,
+}
+
+impl Identities {
+    pub fn resolve(&self, contributor: &Contributor) -> Contributor {
+        self.mailmap.resolve(contributor)
+    }
+
+    pub fn is_excluded(&self, contributor: &Contributor) -> bool {
+        self.exclude.iter().any(|pattern| {
+            pattern.is_match(&contributor.name) || pattern.is_match(&contributor.email)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::contributors::{find_trailer_contributors, Contributor, Identities, Mailmap};
+    use crate::core::conventional_commit::ConventionalCommit;
+    use regex::Regex;
+    use std::str::FromStr;
+
+    #[test]
+    fn should_parse_identities() {
+        assert_eq!(
+            Contributor::parse("Jane Doe <jane@example.com>"),
+            Some(Contributor::new("Jane Doe", "jane@example.com"))
+        );
+        assert_eq!(
+            Contributor::parse("<jane@example.com>"),
+            Some(Contributor::new("", "jane@example.com"))
+        );
+        assert_eq!(Contributor::parse("Jane Doe"), None);
+        assert_eq!(Contributor::parse("Jane Doe <>"), None);
+    }
+
+    #[test]
+    fn should_find_co_authors_and_sign_offs() {
+        // given
+        let commit = ConventionalCommit::from_str(
+            "feat: pair\n\n\
+            Co-authored-by: John Roe <john@example.com>\n\
+            Refs: #12\n\
+            signed-off-by: Jane Doe <jane@example.com>",
+        )
+        .unwrap();
+
+        // when
+        let contributors = find_trailer_contributors(&commit);
+
+        // then
+        assert_eq!(
+            contributors,
+            vec![
+                Contributor::new("John Roe", "john@example.com"),
+                Contributor::new("Jane Doe", "jane@example.com"),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_resolve_identities_with_mailmap() {
+        // given
+        let mailmap = Mailmap::from(
+            "# names and emails\n\
+            Jane Doe <jane@example.com>\n\
+            <jane@example.com> <jane@old.example.com> # moved\n\
+            John Roe <john@example.com> johnny <JOHN@home.example.com>\n\
+            invalid line\n",
+        );
+
+        // then
+        let resolve = |name: &str, email: &str| mailmap.resolve(&Contributor::new(name, email));
+        assert_eq!(
+            resolve("jane", "Jane@Example.com"),
+            Contributor::new("Jane Doe", "Jane@Example.com")
+        );
+        assert_eq!(
+            resolve("Jane", "jane@old.example.com"),
+            Contributor::new("Jane", "jane@example.com")
+        );
+        assert_eq!(
+            resolve("Johnny", "john@home.example.com"),
+            Contributor::new("John Roe", "john@example.com")
+        );
+        assert_eq!(
+            resolve("J", "john@home.example.com"),
+            Contributor::new("J", "john@home.example.com")
+        );
+    }
+
+    #[test]
+    fn should_exclude_contributors_matching_patterns() {
+        // given
+        let identities = Identities {
+            exclude: vec![Regex::new(r"\[bot\]").unwrap()],
+            ..Identities::default()
+        };
+
+        // then
+        assert!(identities.is_excluded(&Contributor::new(
+            "dependabot[bot]",
+            "49699333+dependabot[bot]@users.noreply.github.com"
+        )));
+        assert!(!identities.is_excluded(&Contributor::new("Jane Doe", "jane@example.com")));
+    }
+}
@@ -4,6 +4,9 @@ use crate::core::base::{Paragraph, ParseError};
 use crate::core::commit_message::CommitMessage;
 use lazy_static::lazy_static;
 use regex::{Regex, RegexBuilder};
+use std::fmt;
+use std::fmt::Formatter;
+use std::str::FromStr;
 
 lazy_static! {
     static ref SUBJECT_REGEX: Regex = RegexBuilder::new(
@@ -23,12 +26,12 @@ lazy_static! {
     .build()
     .unwrap();
     static ref FOOTER_REGEX: Regex =
-        RegexBuilder::new(r"^(?:(?<breaking>BREAKING CHANGE)|(?:[-A-z]+)+?)(?::\s)|(?:\s#).+$")
+        RegexBuilder::new(r"^(?:(?<breaking>BREAKING[ -]CHANGE)|(?:[-A-z]+)+?)(?::\s)|(?:\s#).+$")
             .build()
             .unwrap();
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ConventionalCommit {
     pub commit_type: CommitType,
     pub scopes: Option<Vec<String>>,
@@ -38,15 +41,29 @@ pub struct ConventionalCommit {
     pub is_breaking_change: bool,
 }
 
-impl ConventionalCommit {
-    pub fn from_str(message: &str) -> Result<Self, ParseError> {
+impl FromStr for ConventionalCommit {
+    type Err = ParseError;
+
+    fn from_str(message: &str) -> Result<Self, Self::Err> {
         let commit = CommitMessage::from(message);
-        return ConventionalCommit::from(commit);
+        return ConventionalCommit::try_from(commit);
+    }
+}
+
+impl TryFrom<&str> for ConventionalCommit {
+    type Error = ParseError;
+
+    fn try_from(message: &str) -> Result<Self, Self::Error> {
+        Self::from_str(message)
     }
+}
+
+impl TryFrom<CommitMessage> for ConventionalCommit {
+    type Error = ParseError;
 
-    pub fn from(message: CommitMessage) -> Result<Self, ParseError> {
+    fn try_from(message: CommitMessage) -> Result<Self, Self::Error> {
         let mut paragraphs = message.get_paragraphs();
-        if paragraphs.len() == 0 {
+        if paragraphs.is_empty() {
             return Err(ParseError {
                 line: String::from(""),
                 reason: String::from("Commit message has to have at least one line"),
@@ -54,7 +71,7 @@ impl ConventionalCommit {
         }
 
         let first_paragraph = paragraphs.pop_front().unwrap();
-        let potential_header = Header::from(&first_paragraph);
+        let potential_header = Header::try_from(&first_paragraph);
         if potential_header.is_err() {
             return Err(potential_header.err().unwrap());
         }
@@ -63,17 +80,15 @@ impl ConventionalCommit {
         let mut body: Option<Body> = None;
         let mut footer: Option<Footer> = None;
 
-        if paragraphs.len() > 0 {
+        if !paragraphs.is_empty() {
             let last_paragraph = paragraphs.pop_back().unwrap();
-            let potential_footer = Footer::from(&last_paragraph);
-            if potential_footer.is_ok() {
-                footer = Some(potential_footer.unwrap())
-            } else {
-                paragraphs.push_back(last_paragraph)
+            match Footer::try_from(&last_paragraph) {
+                Ok(potential_footer) => footer = Some(potential_footer),
+                Err(_) => paragraphs.push_back(last_paragraph),
             }
         }
 
-        if paragraphs.len() > 0 {
+        if !paragraphs.is_empty() {
             body = Some(Body::from(Vec::from(paragraphs)));
         }
 
@@ -92,6 +107,181 @@ impl ConventionalCommit {
     }
 }
 
+impl ConventionalCommit {
+    /// Builder of a commit validated against the grammar, e.g.
+    /// `ConventionalCommit::builder().feat().scope("api").description("add endpoint").build()`.
+    pub fn builder() -> ConventionalCommitBuilder {
+        ConventionalCommitBuilder::default()
+    }
+
+    /// Formats the commit as a message, ending with a newline, see [fmt::Display].
+    pub fn to_message(&self) -> String {
+        format!("{}\n", self)
+    }
+}
+
+/// The commit as a message, without a trailing newline. A breaking change without a
+/// `BREAKING CHANGE` trailer is marked with `!` in the header.
+impl fmt::Display for ConventionalCommit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.commit_type)?;
+        if let Some(scopes) = &self.scopes {
+            write!(f, "({})", scopes.join(","))?;
+        }
+        let has_breaking_trailer = match &self.footer {
+            Some(footer) => footer.has_breaking_change_marker,
+            None => false,
+        };
+        if self.is_breaking_change && !has_breaking_trailer {
+            write!(f, "!")?;
+        }
+        write!(f, ": {}", self.description)?;
+
+        if let Some(body) = &self.body {
+            for paragraph in &body.paragraphs {
+                write!(f, "\n\n{}", paragraph)?;
+            }
+        }
+        if let Some(footer) = &self.footer {
+            write!(f, "\n\n{}", footer)?;
+        }
+        return Ok(());
+    }
+}
+
+/// Collects the parts of a [ConventionalCommit]; [ConventionalCommitBuilder::build] checks that
+/// the message they form parses back to the same commit.
+#[derive(Debug, Default)]
+pub struct ConventionalCommitBuilder {
+    commit_type: Option<CommitType>,
+    scopes: Vec<String>,
+    description: String,
+    body: Vec<Paragraph>,
+    breaking_change: bool,
+    trailers: Vec<String>,
+}
+
+impl ConventionalCommitBuilder {
+    pub fn feat(self) -> Self {
+        Self {
+            commit_type: Some(CommitType::Feat),
+            ..self
+        }
+    }
+
+    pub fn fix(self) -> Self {
+        Self {
+            commit_type: Some(CommitType::Fix),
+            ..self
+        }
+    }
+
+    /// Any type, e.g. `docs`; `feat` and `fix` are recognised regardless of case.
+    pub fn commit_type(self, commit_type: &str) -> Self {
+        Self {
+            commit_type: Some(parse_commit_type(commit_type)),
+            ..self
+        }
+    }
+
+    /// Adds a scope, the header lists all of them separated by commas.
+    pub fn scope(mut self, scope: &str) -> Self {
+        self.scopes.push(String::from(scope));
+        return self;
+    }
+
+    pub fn description(self, description: &str) -> Self {
+        Self {
+            description: String::from(description),
+            ..self
+        }
+    }
+
+    /// Adds paragraphs of the body, separated by empty lines in the text.
+    pub fn body(mut self, text: &str) -> Self {
+        self.body.extend(CommitMessage::from(text).paragraphs);
+        return self;
+    }
+
+    /// Marks the commit as a breaking change with a `BREAKING CHANGE` trailer describing it.
+    pub fn breaking(mut self, description: &str) -> Self {
+        self.breaking_change = true;
+        self.trailers
+            .push(format!("BREAKING CHANGE: {}", description));
+        return self;
+    }
+
+    /// Marks the commit as a breaking change with `!` in the header only.
+    pub fn breaking_marker(self) -> Self {
+        Self {
+            breaking_change: true,
+            ..self
+        }
+    }
+
+    /// Adds a trailer, e.g. `trailer("Refs", "#1")` for `Refs: #1`.
+    pub fn trailer(mut self, key: &str, value: &str) -> Self {
+        self.trailers.push(format!("{}: {}", key, value));
+        return self;
+    }
+
+    pub fn build(self) -> Result<ConventionalCommit, ParseError> {
+        let commit_type = match self.commit_type {
+            Some(commit_type) => commit_type,
+            None => {
+                return Err(ParseError {
+                    line: String::from(""),
+                    reason: String::from("Commit type is required"),
+                })
+            }
+        };
+        if self.description.trim().is_empty() {
+            return Err(ParseError {
+                line: String::from(""),
+                reason: String::from("Commit description is required"),
+            });
+        }
+
+        let mut footer: Option<Footer> = None;
+        if !self.trailers.is_empty() {
+            let elements = self
+                .trailers
+                .iter()
+                .map(|trailer| FooterElement::try_from(trailer.as_str()))
+                .collect::<Result<Vec<FooterElement>, ParseError>>()?;
+            footer = Some(Footer {
+                has_breaking_change_marker: elements.iter().any(|e| e.has_breaking_change),
+                elements,
+            });
+        }
+
+        let commit = ConventionalCommit {
+            commit_type,
+            scopes: Some(self.scopes).filter(|scopes| !scopes.is_empty()),
+            description: self.description,
+            body: Some(Body::from(self.body)).filter(|body| !body.paragraphs.is_empty()),
+            is_breaking_change: self.breaking_change
+                || match &footer {
+                    Some(footer) => footer.has_breaking_change_marker,
+                    None => false,
+                },
+            footer,
+        };
+
+        // parts that are valid on their own can still form a different commit, e.g. a type
+        // with a colon, or a body ending with a paragraph of trailers
+        let message = commit.to_string();
+        if ConventionalCommit::from_str(&message)? != commit {
+            return Err(ParseError {
+                line: String::from(message.lines().next().unwrap_or_default()),
+                reason: String::from("Commit parts do not form a valid conventional commit"),
+            });
+        }
+
+        return Ok(commit);
+    }
+}
+
 struct Header {
     commit_type: CommitType,
     scopes: Option<Vec<String>>,
@@ -99,8 +289,10 @@ struct Header {
     has_breaking_change_marker: bool,
 }
 
-impl Header {
-    pub fn from(paragraph: &Paragraph) -> Result<Header, ParseError> {
+impl TryFrom<&Paragraph> for Header {
+    type Error = ParseError;
+
+    fn try_from(paragraph: &Paragraph) -> Result<Header, Self::Error> {
         if paragraph.len() != 1 {
             return Err(ParseError {
                 line: String::from(""),
@@ -126,41 +318,39 @@ impl Header {
         return Ok(Header {
             commit_type: parse_commit_type(commit_type),
             description: String::from(description),
-            scopes: if scopes.is_some() {
-                Some(parse_scopes(scopes.unwrap().as_str()))
-            } else {
-                None
-            },
+            scopes: scopes.map(|scopes| parse_scopes(scopes.as_str())),
             has_breaking_change_marker,
         });
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Body {
     pub paragraphs: Vec<Paragraph>,
 }
 
-impl Body {
-    pub fn from(paragraphs: Vec<Paragraph>) -> Self {
+impl From<Vec<Paragraph>> for Body {
+    fn from(paragraphs: Vec<Paragraph>) -> Self {
         Self { paragraphs }
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Footer {
     pub elements: Vec<FooterElement>,
     pub has_breaking_change_marker: bool,
 }
 
-impl Footer {
-    pub fn from(paragraph: &Paragraph) -> Result<Self, ParseError> {
+impl TryFrom<&Paragraph> for Footer {
+    type Error = ParseError;
+
+    fn try_from(paragraph: &Paragraph) -> Result<Self, Self::Error> {
         let mut footer_elements: Vec<FooterElement> = vec![];
         let mut has_breaking_change = false;
 
         let folded_paragraph = paragraph.folded();
         for line in folded_paragraph.get_lines() {
-            let potential_element = FooterElement::from(line.as_str());
+            let potential_element = FooterElement::try_from(line.as_str());
             if potential_element.is_err() {
                 return Err(ParseError {
                     line: String::from(line),
@@ -183,14 +373,27 @@ impl Footer {
     }
 }
 
-#[derive(Debug, PartialEq)]
+impl fmt::Display for Footer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let lines: Vec<&str> = self
+            .elements
+            .iter()
+            .map(|element| element.content.as_str())
+            .collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct FooterElement {
     pub content: String,
     pub has_breaking_change: bool,
 }
 
-impl FooterElement {
-    pub fn from(line: &str) -> Result<Self, ParseError> {
+impl TryFrom<&str> for FooterElement {
+    type Error = ParseError;
+
+    fn try_from(line: &str) -> Result<Self, Self::Error> {
         let captures = FOOTER_REGEX.captures(line);
         if captures.is_none() {
             return Err(ParseError {
@@ -208,13 +411,88 @@ impl FooterElement {
     }
 }
 
-#[derive(Debug, PartialEq)]
+impl FooterElement {
+    /// Token of the trailer, e.g. `Refs` for `Refs: #123` or `BREAKING CHANGE`.
+    pub fn key(&self) -> &str {
+        return match self.split() {
+            Some((key, _)) => key,
+            None => &self.content,
+        };
+    }
+
+    /// Value of the trailer, e.g. `#123` for both `Refs: #123` and `Refs #123`.
+    pub fn value(&self) -> &str {
+        return match self.split() {
+            Some((_, value)) => value.trim(),
+            None => "",
+        };
+    }
+
+    fn split(&self) -> Option<(&str, &str)> {
+        let colon = self.content.find(": ");
+        let hash = self.content.find(" #");
+        return match (colon, hash) {
+            (Some(colon), Some(hash)) if hash < colon => {
+                Some((&self.content[..hash], &self.content[hash + 1..]))
+            }
+            (Some(colon), _) => Some((&self.content[..colon], &self.content[colon + 2..])),
+            (None, Some(hash)) => Some((&self.content[..hash], &self.content[hash + 1..])),
+            (None, None) => None,
+        };
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum CommitType {
     Fix,
     Feat,
     Custom(String),
 }
 
+impl CommitType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            CommitType::Fix => "fix",
+            CommitType::Feat => "feat",
+            CommitType::Custom(commit_type) => commit_type,
+        }
+    }
+}
+
+/// A single word type, `feat` and `fix` are recognised regardless of case.
+impl FromStr for CommitType {
+    type Err = ParseError;
+
+    fn from_str(commit_type: &str) -> Result<Self, Self::Err> {
+        if commit_type.is_empty()
+            || commit_type
+                .chars()
+                .any(|c| c.is_whitespace() || "()!:".contains(c))
+        {
+            return Err(ParseError {
+                line: String::from(commit_type),
+                reason: String::from("Commit type has invalid format"),
+            });
+        }
+
+        return Ok(parse_commit_type(commit_type));
+    }
+}
+
+impl TryFrom<&str> for CommitType {
+    type Error = ParseError;
+
+    fn try_from(commit_type: &str) -> Result<Self, Self::Error> {
+        Self::from_str(commit_type)
+    }
+}
+
+impl fmt::Display for CommitType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 fn parse_commit_type(commit_type: &str) -> CommitType {
     return match commit_type.to_lowercase().as_str() {
         "feat" => CommitType::Feat,
@@ -224,7 +502,7 @@ fn parse_commit_type(commit_type: &str) -> CommitType {
 }
 
 fn parse_scopes(scopes: &str) -> Vec<String> {
-    scopes.split(",").map(|s| String::from(s)).collect()
+    scopes.split(',').map(String::from).collect()
 }
 
 #[cfg(test)]
@@ -235,6 +513,8 @@ mod tests {
     use crate::core::conventional_commit::{
         Body, CommitType, ConventionalCommit, Footer, FooterElement,
     };
+    use std::collections::HashSet;
+    use std::str::FromStr;
 
     #[test]
     fn should_parse_commit_subject_line_with_feat_type_and_foo_scope() {
@@ -242,7 +522,7 @@ mod tests {
         let subject = "feat(foo): bar baz";
 
         // when
-        let result = ConventionalCommit::from_str(&subject);
+        let result = ConventionalCommit::from_str(subject);
 
         // then
         let expected: ConventionalCommit = ConventionalCommit {
@@ -263,7 +543,7 @@ mod tests {
         let subject = "fix(foo): bar baz";
 
         // when
-        let result = ConventionalCommit::from_str(&subject);
+        let result = ConventionalCommit::from_str(subject);
 
         // then
         let expected: ConventionalCommit = ConventionalCommit {
@@ -284,7 +564,7 @@ mod tests {
         let subject = "docs(foo): bar baz";
 
         // when
-        let result = ConventionalCommit::from_str(&subject);
+        let result = ConventionalCommit::from_str(subject);
 
         // then
         let expected: ConventionalCommit = ConventionalCommit {
@@ -305,7 +585,7 @@ mod tests {
         let subject = "feat(foo)!: bar baz";
 
         // when
-        let result = ConventionalCommit::from_str(&subject);
+        let result = ConventionalCommit::from_str(subject);
 
         // then
         let expected: ConventionalCommit = ConventionalCommit {
@@ -326,7 +606,7 @@ mod tests {
         let subject = "feat(foo,bax): bar baz";
 
         // when
-        let result = ConventionalCommit::from_str(&subject);
+        let result = ConventionalCommit::from_str(subject);
 
         // then
         let expected: ConventionalCommit = ConventionalCommit {
@@ -347,7 +627,7 @@ mod tests {
         let subject = "Implemented something";
 
         // when
-        let result = ConventionalCommit::from_str(&subject);
+        let result = ConventionalCommit::from_str(subject);
 
         // then
         assert!(result.is_err(), "An Error should have been returned");
@@ -363,7 +643,7 @@ mod tests {
         };
 
         // when
-        let convention_commit = ConventionalCommit::from(commit);
+        let convention_commit = ConventionalCommit::try_from(commit);
 
         // then
         assert_eq!(
@@ -389,7 +669,7 @@ mod tests {
         };
 
         // when
-        let convention_commit = ConventionalCommit::from(commit);
+        let convention_commit = ConventionalCommit::try_from(commit);
 
         // then
         assert_eq!(
@@ -415,7 +695,7 @@ mod tests {
         };
 
         // when
-        let convention_commit = ConventionalCommit::from(commit);
+        let convention_commit = ConventionalCommit::try_from(commit);
 
         // then
         assert_eq!(
@@ -441,7 +721,7 @@ mod tests {
         };
 
         // when
-        let convention_commit = ConventionalCommit::from(commit);
+        let convention_commit = ConventionalCommit::try_from(commit);
 
         // then
         assert_eq!(
@@ -476,7 +756,7 @@ mod tests {
         };
 
         // when
-        let convention_commit = ConventionalCommit::from(commit);
+        let convention_commit = ConventionalCommit::try_from(commit);
 
         // then
         assert_eq!(
@@ -526,7 +806,7 @@ mod tests {
         };
 
         // when
-        let convention_commit = ConventionalCommit::from(commit);
+        let convention_commit = ConventionalCommit::try_from(commit);
 
         // then
         assert_eq!(
@@ -577,7 +857,7 @@ mod tests {
         };
 
         // when
-        let convention_commit = ConventionalCommit::from(commit);
+        let convention_commit = ConventionalCommit::try_from(commit);
 
         // then
         assert_eq!(
@@ -623,7 +903,7 @@ mod tests {
         };
 
         // when
-        let convention_commit = ConventionalCommit::from(commit);
+        let convention_commit = ConventionalCommit::try_from(commit);
 
         // then
         assert_eq!(
@@ -671,7 +951,7 @@ mod tests {
         };
 
         // when
-        let convention_commit = ConventionalCommit::from(commit);
+        let convention_commit = ConventionalCommit::try_from(commit);
 
         // then
         assert_eq!(
@@ -726,7 +1006,7 @@ mod tests {
         };
 
         // when
-        let conventional_commit = ConventionalCommit::from(commit);
+        let conventional_commit = ConventionalCommit::try_from(commit);
 
         // then
         assert_eq!(
@@ -748,6 +1028,34 @@ mod tests {
         )
     }
 
+    #[test]
+    fn should_treat_breaking_change_token_with_hyphen_as_breaking_change() {
+        // given
+        let message = "feat: new breaking change\n\nBREAKING-CHANGE: removed an option";
+
+        // when
+        let conventional_commit = ConventionalCommit::from_str(message);
+
+        // then
+        assert!(conventional_commit.unwrap().is_breaking_change);
+    }
+
+    #[test]
+    fn should_split_footer_elements_into_key_and_value() {
+        // given
+        let colon = FooterElement::try_from("Reviewed-by: Z <z@example.com>").unwrap();
+        let hash = FooterElement::try_from("Refs #123").unwrap();
+        let breaking = FooterElement::try_from("BREAKING CHANGE: config file is gone").unwrap();
+
+        // then
+        assert_eq!(colon.key(), "Reviewed-by");
+        assert_eq!(colon.value(), "Z <z@example.com>");
+        assert_eq!(hash.key(), "Refs");
+        assert_eq!(hash.value(), "#123");
+        assert_eq!(breaking.key(), "BREAKING CHANGE");
+        assert_eq!(breaking.value(), "config file is gone");
+    }
+
     #[test]
     fn should_leave_last_paragraph_that_is_not_a_footer_as_it_is() {
         // given
@@ -768,7 +1076,7 @@ mod tests {
         };
 
         // when
-        let conventional_commit = ConventionalCommit::from(commit);
+        let conventional_commit = ConventionalCommit::try_from(commit);
 
         // then
         assert_eq!(
@@ -792,4 +1100,90 @@ mod tests {
             }
         )
     }
+
+    #[test]
+    fn should_build_commit_formatting_to_valid_message() {
+        // when
+        let commit = ConventionalCommit::builder()
+            .feat()
+            .scope("api")
+            .scope("db")
+            .description("add endpoint")
+            .body("first paragraph\nsecond line\n\nsecond paragraph")
+            .breaking("the old endpoint is removed")
+            .trailer("Refs", "#1")
+            .build()
+            .unwrap();
+
+        // then
+        let message = commit.to_message();
+        assert_eq!(
+            message,
+            "feat(api,db): add endpoint\n\n\
+            first paragraph\nsecond line\n\n\
+            second paragraph\n\n\
+            BREAKING CHANGE: the old endpoint is removed\nRefs: #1\n"
+        );
+        assert!(commit.is_breaking_change);
+        assert_eq!(ConventionalCommit::from_str(&message).unwrap(), commit);
+        assert_eq!(
+            ConventionalCommit::builder()
+                .commit_type("docs")
+                .breaking_marker()
+                .description("drop old guide")
+                .build()
+                .unwrap()
+                .to_message(),
+            "docs!: drop old guide\n"
+        );
+    }
+
+    #[test]
+    fn should_reject_parts_not_forming_valid_commit() {
+        // given
+        let valid = || ConventionalCommit::builder().fix().description("crash");
+
+        // then
+        assert!(ConventionalCommit::builder()
+            .description("crash")
+            .build()
+            .is_err());
+        assert!(ConventionalCommit::builder().fix().build().is_err());
+        assert!(valid().commit_type("fix: oops").build().is_err());
+        assert!(valid().scope("api,db").build().is_err());
+        assert!(valid().description("crash\n\nbody").build().is_err());
+        assert!(valid().trailer("Reviewed by", "Jane").build().is_err());
+        assert!(valid().body("Refs: #1").build().is_err());
+    }
+
+    #[test]
+    fn should_compose_with_standard_traits() {
+        // given
+        let message = "feat(api)!: add endpoint\n\nsome body\n\nRefs: #1";
+
+        // when
+        let commit: ConventionalCommit = message.parse().unwrap();
+        let same = ConventionalCommit::try_from(CommitMessage::from(message)).unwrap();
+
+        // then
+        assert_eq!(commit.to_string(), message);
+        assert_eq!(ConventionalCommit::try_from(message).unwrap(), commit);
+        let commits: HashSet<ConventionalCommit> = HashSet::from([commit.clone(), same]);
+        assert_eq!(commits.len(), 1);
+        assert!("not conventional".parse::<ConventionalCommit>().is_err());
+    }
+
+    #[test]
+    fn should_parse_and_display_commit_type() {
+        // then
+        assert_eq!(CommitType::from_str("FEAT").unwrap(), Feat);
+        assert_eq!(
+            CommitType::try_from("docs").unwrap(),
+            CommitType::Custom(String::from("docs"))
+        );
+        assert_eq!(Fix.to_string(), "fix");
+        assert!(CommitType::from_str("").is_err());
+        assert!(CommitType::from_str("feat(api)").is_err());
+        assert!(CommitType::from_str("two words").is_err());
+    }
 }
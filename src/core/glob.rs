@@ -0,0 +1,131 @@
+use crate::core::base::ParseError;
+use regex::Regex;
+
+/// Glob pattern matched against slash separated, repository relative paths.
+///
+/// `*` matches within a single path segment, `**` matches across segments and `?` matches
+/// a single character. A pattern without any slash matches file names at any depth, and
+/// a pattern matching a directory matches everything inside it.
+#[derive(Debug)]
+pub struct Glob {
+    pub pattern: String,
+    regex: Regex,
+}
+
+impl Glob {
+    pub fn new(pattern: &str) -> Result<Self, ParseError> {
+        let trimmed = pattern.trim_start_matches("./").trim_end_matches('/');
+        if trimmed.is_empty() {
+            return Err(ParseError {
+                line: String::from(pattern),
+                reason: String::from("Glob pattern cannot be empty"),
+            });
+        }
+
+        let mut expression = String::from("^");
+        if !trimmed.contains('/') {
+            expression.push_str("(?:.*/)?");
+        }
+
+        let mut chars = trimmed.trim_start_matches('/').chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '*' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    if chars.peek() == Some(&'/') {
+                        chars.next();
+                        expression.push_str("(?:.*/)?");
+                    } else {
+                        expression.push_str(".*");
+                    }
+                }
+                '*' => expression.push_str("[^/]*"),
+                '?' => expression.push_str("[^/]"),
+                _ => expression.push_str(&regex::escape(&c.to_string())),
+            }
+        }
+        expression.push_str("(?:/.*)?$");
+
+        let regex = Regex::new(&expression).map_err(|e| ParseError {
+            line: String::from(pattern),
+            reason: e.to_string(),
+        })?;
+
+        return Ok(Self {
+            pattern: String::from(pattern),
+            regex,
+        });
+    }
+
+    pub fn matches(&self, path: &str) -> bool {
+        self.regex.is_match(path.trim_start_matches("./"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::glob::Glob;
+
+    #[test]
+    fn should_match_files_within_directory_with_double_star() {
+        // given
+        let glob = Glob::new("src/api/**").unwrap();
+
+        // then
+        assert!(glob.matches("src/api/mod.rs"));
+        assert!(glob.matches("src/api/v1/routes.rs"));
+        assert!(!glob.matches("src/apis/mod.rs"));
+        assert!(!glob.matches("tests/src/api/mod.rs"));
+    }
+
+    #[test]
+    fn should_match_single_segment_with_single_star() {
+        // given
+        let glob = Glob::new("crates/*/Cargo.toml").unwrap();
+
+        // then
+        assert!(glob.matches("crates/foo/Cargo.toml"));
+        assert!(!glob.matches("crates/foo/bar/Cargo.toml"));
+    }
+
+    #[test]
+    fn should_match_file_name_at_any_depth_when_pattern_has_no_slash() {
+        // given
+        let glob = Glob::new("*.md").unwrap();
+
+        // then
+        assert!(glob.matches("README.md"));
+        assert!(glob.matches("docs/guide/intro.md"));
+        assert!(!glob.matches("src/main.rs"));
+    }
+
+    #[test]
+    fn should_match_everything_inside_of_matched_directory() {
+        // given
+        let glob = Glob::new("docs").unwrap();
+
+        // then
+        assert!(glob.matches("docs/index.md"));
+        assert!(glob.matches("guide/docs/index.md"));
+        assert!(!glob.matches("documentation/index.md"));
+    }
+
+    #[test]
+    fn should_match_any_directory_depth_with_leading_double_star() {
+        // given
+        let glob = Glob::new("**/tests/*.rs").unwrap();
+
+        // then
+        assert!(glob.matches("tests/lint.rs"));
+        assert!(glob.matches("crates/foo/tests/lint.rs"));
+        assert!(!glob.matches("crates/foo/src/lint.rs"));
+    }
+
+    #[test]
+    fn should_return_error_for_empty_pattern() {
+        assert!(
+            Glob::new("/").is_err(),
+            "An Error should have been returned"
+        );
+    }
+}
@@ -1,10 +1,363 @@
-pub mod history {
-    use crate::core::conventional_commit::ConventionalCommit;
-    use chrono::{DateTime, Utc};
-
-    // TODO implement me!
-    struct HistoryEntry {
-        commit: ConventionalCommit,
-        timestamp: DateTime<Utc>,
+use crate::core::base::ParseError;
+use crate::core::contributors::{find_trailer_contributors, Contributor, Identities};
+use crate::core::conventional_commit::ConventionalCommit;
+use crate::core::semantic_version::Increment;
+use crate::git::{Commit, Signature};
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::str::FromStr;
+
+lazy_static! {
+    static ref GIT_REVERT_REGEX: Regex = Regex::new(r#"^Revert "(.+)"$"#).unwrap();
+    static ref REVERTED_COMMIT_REGEX: Regex =
+        Regex::new(r"(?m)^This reverts commit ([0-9a-fA-F]{7,64})\b").unwrap();
+}
+
+/// Conventional commit read from the repository.
+#[derive(Debug, PartialEq)]
+pub struct HistoryEntry {
+    pub id: String,
+    pub author: Signature,
+    pub commit: ConventionalCommit,
+    pub timestamp: DateTime<Utc>,
+    /// Id of the commit this one reverts, possibly abbreviated, taken from a `This reverts
+    /// commit <id>` line or from the header of a reverted commit in the same history.
+    pub reverts: Option<String>,
+}
+
+impl HistoryEntry {
+    /// Entry of the commit. Reverts generated by git, `Revert "<header>"`, are read as
+    /// `revert: <header>`.
+    pub fn from(commit: &Commit) -> Result<Self, ParseError> {
+        let (subject, rest) = commit
+            .message
+            .split_once('\n')
+            .unwrap_or((&commit.message, ""));
+        let message = match GIT_REVERT_REGEX.captures(subject.trim_end()) {
+            Some(captures) => format!("revert: {}\n{}", &captures[1], rest),
+            None => commit.message.clone(),
+        };
+
+        return Ok(Self {
+            id: commit.id.clone(),
+            author: commit.author.clone(),
+            commit: ConventionalCommit::from_str(&message)?,
+            timestamp: commit.committer.timestamp.with_timezone(&Utc),
+            reverts: REVERTED_COMMIT_REGEX
+                .captures(&commit.message)
+                .map(|captures| captures[1].to_lowercase()),
+        });
+    }
+
+    pub fn is_revert(&self) -> bool {
+        self.commit
+            .commit_type
+            .as_str()
+            .eq_ignore_ascii_case("revert")
+    }
+
+    /// The author followed by co-authors and people who signed off the commit, resolved with
+    /// the mailmap, without duplicates and excluded contributors.
+    pub fn contributors(&self, identities: &Identities) -> Vec<Contributor> {
+        let author = Contributor::new(&self.author.name, &self.author.email);
+        let mut contributors: Vec<Contributor> = vec![];
+        for contributor in [author]
+            .into_iter()
+            .chain(find_trailer_contributors(&self.commit))
+        {
+            let contributor = identities.resolve(&contributor);
+            if !identities.is_excluded(&contributor)
+                && !contributors.iter().any(|c| c.is_same(&contributor))
+            {
+                contributors.push(contributor);
+            }
+        }
+
+        return contributors;
+    }
+}
+
+/// Conventional commits in a range of history, newest first.
+#[derive(Debug, Default)]
+pub struct History {
+    pub entries: Vec<HistoryEntry>,
+    /// Ids of commits that don't follow the conventional commits format.
+    pub skipped: Vec<String>,
+    /// Ids of reverts dropped along with the commits they revert, and of those commits.
+    pub reverted: Vec<String>,
+}
+
+impl History {
+    pub fn from(commits: &[Commit]) -> Self {
+        let mut history = History::default();
+        for commit in commits {
+            match HistoryEntry::from(commit) {
+                Ok(entry) => history.entries.push(entry),
+                Err(_) => history.skipped.push(commit.id.clone()),
+            }
+        }
+
+        // reverts without an id refer to an older commit by its header
+        for entry in &mut history.entries {
+            if entry.reverts.is_none() && entry.is_revert() {
+                let header = entry.commit.description.trim();
+                entry.reverts = commits
+                    .iter()
+                    .skip_while(|commit| commit.id != entry.id)
+                    .skip(1)
+                    .find(|commit| commit.message.lines().next().map(str::trim) == Some(header))
+                    .map(|commit| commit.id.clone());
+            }
+        }
+
+        return history;
+    }
+
+    /// Drops reverts along with the commits they revert, when both are in the history, so that
+    /// neither shows up in changelogs or counts towards the next version. A revert of a revert
+    /// cancels out with it, restoring the originally reverted commit.
+    pub fn cancel_reverts(mut self) -> Self {
+        let mut index = 0;
+        // newest first, so that the latest revert of a commit is matched first
+        while index < self.entries.len() {
+            let target = match &self.entries[index].reverts {
+                Some(target) => target.clone(),
+                None => {
+                    index += 1;
+                    continue;
+                }
+            };
+
+            // entries read from one squash commit share its id, and are reverted together
+            let older: Vec<usize> = (index + 1..self.entries.len())
+                .filter(|&position| self.entries[position].id.starts_with(&target))
+                .collect();
+            match older.first() {
+                Some(&reverted) => {
+                    let reverted = self.entries[reverted].id.clone();
+                    for position in older.into_iter().rev() {
+                        self.entries.remove(position);
+                    }
+                    let revert = self.entries.remove(index);
+                    self.reverted.push(revert.id);
+                    self.reverted.push(reverted);
+                }
+                None => match self.skipped.iter().position(|id| id.starts_with(&target)) {
+                    Some(reverted) => {
+                        let reverted = self.skipped.remove(reverted);
+                        let revert = self.entries.remove(index);
+                        self.reverted.push(revert.id);
+                        self.reverted.push(reverted);
+                    }
+                    None => index += 1,
+                },
+            }
+        }
+
+        return self;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Distinct contributors of all entries, in the order of their first contribution. Skipped
+    /// commits aren't taken into account, as only their ids are known.
+    pub fn contributors(&self, identities: &Identities) -> Vec<Contributor> {
+        let mut contributors: Vec<Contributor> = vec![];
+        for entry in self.entries.iter().rev() {
+            for contributor in entry.contributors(identities) {
+                if !contributors.iter().any(|c| c.is_same(&contributor)) {
+                    contributors.push(contributor);
+                }
+            }
+        }
+
+        return contributors;
+    }
+
+    /// The most significant increment required by any of the commits.
+    pub fn increment(&self) -> Increment {
+        self.entries
+            .iter()
+            .map(|entry| Increment::from(&entry.commit))
+            .max()
+            .unwrap_or(Increment::None)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::core::contributors::{Contributor, Identities, Mailmap};
+    use crate::core::history::History;
+    use crate::core::semantic_version::Increment;
+    use crate::git::{Commit, Signature};
+    use chrono::DateTime;
+    use regex::Regex;
+
+    /// Commit authored by Jane Doe on 2024-05-01, with the given id and message.
+    pub fn commit(id: &str, message: &str) -> Commit {
+        let signature = Signature {
+            name: String::from("Jane Doe"),
+            email: String::from("jane@example.com"),
+            timestamp: DateTime::parse_from_rfc3339("2024-05-01T10:00:00+02:00").unwrap(),
+        };
+
+        return Commit {
+            id: String::from(id),
+            parents: vec![],
+            author: signature.clone(),
+            committer: signature,
+            message: String::from(message),
+        };
+    }
+
+    #[test]
+    fn should_skip_commits_not_following_the_format() {
+        // given
+        let commits = vec![
+            commit("c", "feat: add api"),
+            commit("b", "Merge branch 'main'"),
+            commit("a", "fix: crash"),
+        ];
+
+        // when
+        let history = History::from(&commits);
+
+        // then
+        let ids: Vec<&str> = history.entries.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["c", "a"]);
+        assert_eq!(history.skipped, vec![String::from("b")]);
+        assert_eq!(
+            history.entries[0].timestamp.to_rfc3339(),
+            "2024-05-01T08:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn should_use_the_most_significant_increment() {
+        // given
+        let minor = vec![commit("b", "feat: add api"), commit("a", "fix: crash")];
+        let none = vec![commit("a", "docs: readme")];
+
+        // then
+        assert_eq!(History::from(&minor).increment(), Increment::Minor);
+        assert_eq!(History::from(&none).increment(), Increment::None);
+        assert_eq!(History::from(&[]).increment(), Increment::None);
+    }
+
+    #[test]
+    fn should_list_distinct_contributors_in_order_of_first_contribution() {
+        // given
+        let commits = vec![
+            commit(
+                "c",
+                "fix: crash\n\n\
+                Co-authored-by: dependabot[bot] <support@github.com>\n\
+                Signed-off-by: John Roe <john@old.example.com>",
+            ),
+            commit(
+                "b",
+                "feat: pair\n\n\
+                Co-authored-by: Ann Poe <ann@example.com>\n\
+                Co-authored-by: Jane <JANE@example.com>",
+            ),
+            commit("a", "docs: readme"),
+        ];
+        let identities = Identities {
+            mailmap: Mailmap::from("John Roe <john@example.com> <john@old.example.com>"),
+            exclude: vec![Regex::new(r"\[bot\]").unwrap()],
+        };
+
+        // when
+        let contributors = History::from(&commits).contributors(&identities);
+
+        // then
+        assert_eq!(
+            contributors,
+            vec![
+                Contributor::new("Jane Doe", "jane@example.com"),
+                Contributor::new("Ann Poe", "ann@example.com"),
+                Contributor::new("John Roe", "john@example.com"),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_find_reverted_commits() {
+        // given
+        let commits = vec![
+            commit(
+                "d",
+                "Revert \"fix: crash\"\n\nThis reverts commit A1B2C3D4E5.",
+            ),
+            commit("c", "revert: feat(api): add endpoint"),
+            commit("b", "feat(api): add endpoint"),
+            commit("a", "revert: feat: unknown"),
+        ];
+
+        // when
+        let history = History::from(&commits);
+
+        // then
+        let reverts: Vec<Option<&str>> = history
+            .entries
+            .iter()
+            .map(|entry| entry.reverts.as_deref())
+            .collect();
+        assert_eq!(reverts, vec![Some("a1b2c3d4e5"), Some("b"), None, None]);
+        assert_eq!(history.entries[0].commit.commit_type.as_str(), "revert");
+        assert_eq!(history.entries[0].commit.description, "fix: crash");
+    }
+
+    #[test]
+    fn should_cancel_reverts_with_reverted_commits() {
+        // given
+        let commits = vec![
+            commit("f", "Revert \"Revert \"feat: search\"\""),
+            commit("e", "fix: typo"),
+            commit(
+                "d",
+                "Revert \"feat: search\"\n\nThis reverts commit c1c1c1c1.",
+            ),
+            commit("c1c1c1c1", "feat: search"),
+            commit("b", "revert: WIP\n\nThis reverts commit 0a0a0a0a."),
+            commit("0a0a0a0a", "WIP"),
+            commit("a", "revert: feat: released before"),
+        ];
+
+        // when
+        let history = History::from(&commits).cancel_reverts();
+
+        // then
+        let ids: Vec<&str> = history.entries.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["e", "c1c1c1c1", "a"]);
+        assert!(history.skipped.is_empty());
+        assert_eq!(history.reverted, vec!["f", "d", "b", "0a0a0a0a"]);
+        assert_eq!(history.increment(), Increment::Minor);
+    }
+
+    #[test]
+    fn should_cancel_all_entries_of_reverted_squash_commit() {
+        // given
+        let commits = vec![
+            commit(
+                "c",
+                "Revert \"feat: search\"\n\nThis reverts commit b2b2b2b2.",
+            ),
+            commit("b2b2b2b2", "feat!: search"),
+            commit("b2b2b2b2", "fix: ranking"),
+            commit("a", "fix: typo"),
+        ];
+
+        // when
+        let history = History::from(&commits).cancel_reverts();
+
+        // then
+        let ids: Vec<&str> = history.entries.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["a"]);
+        assert_eq!(history.reverted, vec!["c", "b2b2b2b2"]);
+        assert_eq!(history.increment(), Increment::Patch);
     }
 }
@@ -1,5 +1,8 @@
 pub mod base;
 pub mod commit_message;
+pub mod contributors;
 pub mod conventional_commit;
+pub mod glob;
 pub mod history;
 pub mod semantic_version;
+pub mod version_range;
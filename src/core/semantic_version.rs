@@ -1,10 +1,21 @@
+use crate::core::base::ParseError;
 use crate::core::conventional_commit::{CommitType, ConventionalCommit};
+use lazy_static::lazy_static;
+use regex::Regex;
 use std::cmp::Ordering;
-use std::fmt::{write, Formatter};
-use std::mem::discriminant;
+use std::fmt::Formatter;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+lazy_static! {
+    static ref VERSION_REGEX: Regex = Regex::new(
+        r"^v?(?<major>0|[1-9]\d*)\.(?<minor>0|[1-9]\d*)\.(?<patch>0|[1-9]\d*)(?:-(?<pre_release>[0-9A-Za-z.-]+))?(?:\+(?<metadata>[0-9A-Za-z.-]+))?$"
+    )
+    .unwrap();
+}
 
-#[derive(Debug)]
-struct SemanticVersion {
+#[derive(Debug, Clone)]
+pub struct SemanticVersion {
     major: u32,
     minor: u32,
     patch: u32,
@@ -12,11 +23,36 @@ struct SemanticVersion {
     metadata: Option<String>,
 }
 
-#[derive(Debug)]
-enum PreReleaseType {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PreReleaseType {
     Alpha,
     Beta,
     RC,
+    /// Any other identifier, e.g. `dev` or `nightly`.
+    Custom(String),
+}
+
+/// Part of the version that a set of changes requires to increment, from the least significant.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Increment {
+    None,
+    Patch,
+    Minor,
+    Major,
+}
+
+impl Increment {
+    pub fn from(commit: &ConventionalCommit) -> Self {
+        if commit.is_breaking_change {
+            return Increment::Major;
+        }
+
+        match commit.commit_type {
+            CommitType::Feat => Increment::Minor,
+            CommitType::Fix => Increment::Patch,
+            CommitType::Custom(_) => Increment::None,
+        }
+    }
 }
 
 impl PreReleaseType {
@@ -28,18 +64,38 @@ impl PreReleaseType {
             _ => None,
         }
     }
-}
 
-impl PartialEq for PreReleaseType {
-    fn eq(&self, other: &Self) -> bool {
-        discriminant(self) == discriminant(other)
+    /// Accepts any valid SemVer pre-release identifier, not only the well known ones.
+    pub fn from_identifier(identifier: &str) -> Option<PreReleaseType> {
+        if let Some(pre_release_type) = PreReleaseType::from(identifier) {
+            return Some(pre_release_type);
+        }
+
+        let is_valid = !identifier.is_empty()
+            && !has_leading_zero(identifier)
+            && identifier
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-');
+        return match is_valid {
+            true => Some(PreReleaseType::Custom(String::from(identifier))),
+            false => None,
+        };
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            PreReleaseType::Alpha => "alpha",
+            PreReleaseType::Beta => "beta",
+            PreReleaseType::RC => "rc",
+            PreReleaseType::Custom(identifier) => identifier,
+        }
     }
 }
 
-#[derive(Debug)]
-struct PreRelease {
+#[derive(Debug, Clone)]
+pub struct PreRelease {
     pre_release_type_chain: Vec<PreReleaseType>,
-    version: Option<i32>,
+    version: Option<u64>,
 }
 
 impl std::fmt::Display for PreRelease {
@@ -51,47 +107,162 @@ impl std::fmt::Display for PreRelease {
             .collect::<Vec<_>>()
             .join(".");
 
+        match self.version {
+            Some(version) if type_chain_str.is_empty() => write!(f, "{}", version),
+            Some(version) => write!(f, "{}.{}", type_chain_str, version),
+            None => write!(f, "{}", type_chain_str),
+        }
+    }
+}
+
+impl PreRelease {
+    pub fn new(pre_release_type_chain: Vec<PreReleaseType>, version: Option<u64>) -> Self {
+        Self {
+            pre_release_type_chain,
+            version,
+        }
+    }
+
+    /// First pre-release on the given channel, e.g. `rc.1`.
+    pub fn first(channel: PreReleaseType) -> Self {
+        Self::new(vec![channel], Some(1))
+    }
+
+    /// Whether this is a pre-release on the given channel, e.g. `rc.2` for `rc`.
+    pub fn is_on_channel(&self, channel: &PreReleaseType) -> bool {
+        self.pre_release_type_chain.len() == 1 && &self.pre_release_type_chain[0] == channel
+    }
+
+    /// Following pre-release, e.g. `rc.3` after `rc.2`. Numbering starts with `.1` when the
+    /// current pre-release has no number.
+    pub fn next(&self) -> Self {
+        Self::new(
+            self.pre_release_type_chain.clone(),
+            Some(self.version.map_or(1, |version| version + 1)),
+        )
+    }
+
+    pub fn from(input: &str) -> Option<Self> {
+        let mut pre_release_type_chain: Vec<PreReleaseType> = vec![];
+        let mut version: Option<u64> = None;
+
+        let mut identifiers = input.split('.').peekable();
+        while let Some(identifier) = identifiers.next() {
+            // only the last numeric identifier is a version number, others are kept as they are
+            if identifiers.peek().is_none()
+                && is_numeric(identifier)
+                && !has_leading_zero(identifier)
+            {
+                if let Ok(number) = identifier.parse::<u64>() {
+                    version = Some(number);
+                    continue;
+                }
+            }
+
+            pre_release_type_chain.push(PreReleaseType::from_identifier(identifier)?);
+        }
+
+        return Some(Self {
+            pre_release_type_chain,
+            version,
+        });
+    }
+
+    /// Dot separated identifiers, e.g. `["rc", "1"]` for `rc.1`.
+    pub fn identifiers(&self) -> Vec<String> {
+        let mut identifiers: Vec<String> = self
+            .pre_release_type_chain
+            .iter()
+            .map(|pre_release_type| String::from(pre_release_type.as_str()))
+            .collect();
         if let Some(version) = self.version {
-            write!(f, "{}.{}", type_chain_str, version)
-        } else {
-            write!(f, "{}", type_chain_str)
+            identifiers.push(version.to_string());
         }
+
+        return identifiers;
     }
 }
 
+/// Digits only, as `parse` would accept a leading `+` as well.
+fn is_numeric(identifier: &str) -> bool {
+    !identifier.is_empty() && identifier.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Numeric identifiers must not have leading zeros.
+fn has_leading_zero(identifier: &str) -> bool {
+    identifier.len() > 1
+        && identifier.starts_with('0')
+        && identifier.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Precedence of single pre-release identifiers (SemVer 2.0.0, §11.4): numeric identifiers
+/// are compared numerically, alphanumeric ones in ASCII order, and numeric ones always have
+/// lower precedence than alphanumeric ones.
+fn compare_identifiers(first: &str, second: &str) -> Ordering {
+    return match (is_numeric(first), is_numeric(second)) {
+        // without leading zeros, a longer number is always a greater one
+        (true, true) => first
+            .len()
+            .cmp(&second.len())
+            .then_with(|| first.cmp(second)),
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        (false, false) => first.cmp(second),
+    };
+}
+
 impl PartialEq for PreRelease {
     fn eq(&self, other: &Self) -> bool {
-        self.version == other.version && self.pre_release_type_chain == other.pre_release_type_chain
+        self.cmp(other) == Ordering::Equal
     }
 }
 
-fn get_release_type_priority(pre_release: &PreReleaseType) -> u8 {
-    match pre_release {
-        PreReleaseType::Alpha => 1,
-        PreReleaseType::Beta => 2,
-        PreReleaseType::RC => 4,
+impl Eq for PreRelease {}
+
+impl Hash for PreRelease {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.identifiers().hash(state);
+    }
+}
+
+impl Ord for PreReleaseType {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // alpha, beta and rc happen to be in ASCII order as well
+        compare_identifiers(self.as_str(), other.as_str())
     }
 }
 
 impl PartialOrd for PreReleaseType {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        if self.eq(other) {
-            return Some(Ordering::Equal);
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PreRelease {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let first = self.identifiers();
+        let second = other.identifiers();
+        for (a, b) in first.iter().zip(second.iter()) {
+            let ordering = compare_identifiers(a, b);
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
         }
 
-        return get_release_type_priority(&self).partial_cmp(&get_release_type_priority(&other));
+        // when all preceding identifiers are equal, the longer list takes precedence
+        return first.len().cmp(&second.len());
+    }
+}
+
+impl PartialOrd for PreRelease {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
 impl std::fmt::Display for PreReleaseType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let str = match &self {
-            PreReleaseType::Alpha => "alpha",
-            PreReleaseType::Beta => "beta",
-            PreReleaseType::RC => "rc",
-        };
-
-        write!(f, "{}", str)
+        write!(f, "{}", self.as_str())
     }
 }
 
@@ -112,57 +283,105 @@ impl SemanticVersion {
         };
     }
 
-    pub fn from(input: String) -> Option<Self> {
-        todo!("Implement parsing string version into proper semver instance")
-    }
-
-    pub fn apply_commit(self, commit: ConventionalCommit) -> SemanticVersion {
-        if commit.is_breaking_change {
-            return Self::new(
-                self.major + 1,
-                self.minor,
-                self.patch,
-                self.pre_release,
-                self.metadata,
-            );
-        }
-
-        match commit.commit_type {
-            CommitType::Feat => Self::new(
-                self.major,
-                self.minor + 1,
-                self.patch,
-                self.pre_release,
-                self.metadata,
-            ),
-            CommitType::Fix => Self::new(
+    /// Next release version, resetting less significant parts. Pre-release and build metadata
+    /// are dropped, as they describe the current version only. `None` when the incremented
+    /// part doesn't fit anymore.
+    pub fn increment(&self, increment: Increment) -> Option<SemanticVersion> {
+        let version = match increment {
+            Increment::Major => Self::new(self.major.checked_add(1)?, 0, 0, None, None),
+            Increment::Minor => Self::new(self.major, self.minor.checked_add(1)?, 0, None, None),
+            Increment::Patch => Self::new(
                 self.major,
                 self.minor,
-                self.patch + 1,
-                self.pre_release,
-                self.metadata,
+                self.patch.checked_add(1)?,
+                None,
+                None,
             ),
-            CommitType::Custom(_) => Self::new(
-                self.major,
-                self.minor,
-                self.patch,
-                self.pre_release,
-                self.metadata,
-            ),
-        }
+            Increment::None => Self::new(self.major, self.minor, self.patch, None, None),
+        };
+
+        return Some(version);
+    }
+
+    pub fn pre_release(&self) -> Option<&PreRelease> {
+        self.pre_release.as_ref()
+    }
+
+    pub fn is_pre_release(&self) -> bool {
+        self.pre_release.is_some()
+    }
+
+    /// Same version with the given pre-release and without build metadata.
+    pub fn with_pre_release(&self, pre_release: Option<PreRelease>) -> SemanticVersion {
+        Self::new(self.major, self.minor, self.patch, pre_release, None)
+    }
+
+    /// Whether major, minor and patch are the same, regardless of pre-release and metadata.
+    pub fn has_same_core(&self, other: &SemanticVersion) -> bool {
+        self.major == other.major && self.minor == other.minor && self.patch == other.patch
+    }
+
+    /// Version after the commit. A pre-release moves on to the following pre-release instead,
+    /// as the version it leads to has already been decided. `None` when the version can't be
+    /// incremented any further.
+    pub fn apply_commit(self, commit: ConventionalCommit) -> Option<SemanticVersion> {
+        let increment = Increment::from(&commit);
+        return match &self.pre_release {
+            Some(pre_release) if increment != Increment::None => {
+                Some(self.with_pre_release(Some(pre_release.next())))
+            }
+            Some(_) => Some(self),
+            None => self.increment(increment),
+        };
+    }
+}
+
+/// Version like `1.2.3-rc.1+build.5`, optionally prefixed with `v`.
+impl FromStr for SemanticVersion {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseError {
+            line: String::from(input),
+            reason: String::from("Invalid semantic version"),
+        };
+        let captures = VERSION_REGEX.captures(input.trim()).ok_or_else(invalid)?;
+
+        let pre_release = match captures.name("pre_release") {
+            Some(pre_release) => Some(PreRelease::from(pre_release.as_str()).ok_or_else(invalid)?),
+            None => None,
+        };
+
+        return Ok(Self::new(
+            captures["major"].parse().map_err(|_| invalid())?,
+            captures["minor"].parse().map_err(|_| invalid())?,
+            captures["patch"].parse().map_err(|_| invalid())?,
+            pre_release,
+            captures
+                .name("metadata")
+                .map(|metadata| String::from(metadata.as_str())),
+        ));
+    }
+}
+
+impl TryFrom<&str> for SemanticVersion {
+    type Error = ParseError;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        Self::from_str(input)
     }
 }
 
 impl std::fmt::Display for SemanticVersion {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match &self.metadata {
-            Some(metadata) => write!(
-                f,
-                "{}.{}.{}+{}",
-                self.major, self.minor, self.patch, metadata
-            ),
-            None => write!(f, "{}.{}.{}", self.major, self.minor, self.patch),
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(pre_release) = &self.pre_release {
+            write!(f, "-{}", pre_release)?;
         }
+        if let Some(metadata) = &self.metadata {
+            write!(f, "+{}", metadata)?;
+        }
+        return Ok(());
     }
 }
 
@@ -175,21 +394,37 @@ impl PartialEq for SemanticVersion {
     }
 }
 
+impl Eq for SemanticVersion {}
+
+/// Consistent with equality, build metadata is ignored.
+impl Hash for SemanticVersion {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.major.hash(state);
+        self.minor.hash(state);
+        self.patch.hash(state);
+        self.pre_release.hash(state);
+    }
+}
+
+/// Precedence as defined by SemVer 2.0.0, §11. Build metadata is ignored.
+impl Ord for SemanticVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.major
+            .cmp(&other.major)
+            .then(self.minor.cmp(&other.minor))
+            .then(self.patch.cmp(&other.patch))
+            .then_with(|| match (&self.pre_release, &other.pre_release) {
+                // a pre-release precedes the release it leads to
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (first, second) => first.cmp(second),
+            })
+    }
+}
+
 impl PartialOrd for SemanticVersion {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        if self.eq(other) {
-            return Some(Ordering::Equal);
-        }
-
-        return if self.major == other.major {
-            if self.minor == other.minor {
-                self.patch.partial_cmp(&other.patch)
-            } else {
-                self.minor.partial_cmp(&other.minor)
-            }
-        } else {
-            self.major.partial_cmp(&other.major)
-        };
+        Some(self.cmp(other))
     }
 }
 
@@ -198,7 +433,9 @@ mod tests {
     use crate::core::conventional_commit::CommitType::{Feat, Fix};
     use crate::core::conventional_commit::{CommitType, ConventionalCommit};
     use crate::core::semantic_version::PreReleaseType::{Alpha, Beta, RC};
-    use crate::core::semantic_version::{PreRelease, PreReleaseType, SemanticVersion};
+    use crate::core::semantic_version::{Increment, PreRelease, PreReleaseType, SemanticVersion};
+    use std::collections::HashSet;
+    use std::str::FromStr;
 
     #[test]
     fn should_increase_major_version_when_introducing_breaking_change() {
@@ -214,7 +451,7 @@ mod tests {
         };
 
         // when
-        let new_version = version.apply_commit(commit);
+        let new_version = version.apply_commit(commit).unwrap();
 
         // then
         assert_eq!(new_version, SemanticVersion::new(2, 0, 0, None, None));
@@ -234,7 +471,7 @@ mod tests {
         };
 
         // when
-        let new_version = version.apply_commit(commit);
+        let new_version = version.apply_commit(commit).unwrap();
 
         // then
         assert_eq!(new_version, SemanticVersion::new(1, 1, 0, None, None));
@@ -254,7 +491,7 @@ mod tests {
         };
 
         // when
-        let new_version = version.apply_commit(commit);
+        let new_version = version.apply_commit(commit).unwrap();
 
         // then
         assert_eq!(new_version, SemanticVersion::new(1, 0, 1, None, None));
@@ -274,19 +511,76 @@ mod tests {
         };
 
         // when
-        let new_version = version.apply_commit(commit);
+        let new_version = version.apply_commit(commit).unwrap();
 
         // then
         assert_eq!(new_version, SemanticVersion::new(1, 0, 0, None, None));
     }
 
+    #[test]
+    fn should_increment_version_and_reset_less_significant_parts() {
+        // given
+        let version = SemanticVersion::new(1, 2, 3, None, Some(String::from("build")));
+
+        // then
+        assert_eq!(
+            version.increment(Increment::Major).unwrap().to_string(),
+            String::from("2.0.0")
+        );
+        assert_eq!(
+            version.increment(Increment::Minor).unwrap().to_string(),
+            String::from("1.3.0")
+        );
+        assert_eq!(
+            version.increment(Increment::Patch).unwrap().to_string(),
+            String::from("1.2.4")
+        );
+        assert_eq!(
+            version.increment(Increment::None).unwrap().to_string(),
+            String::from("1.2.3")
+        );
+    }
+
+    #[test]
+    fn should_not_increment_version_past_the_largest_number() {
+        // given
+        let version = SemanticVersion::new(1, u32::MAX, 3, None, None);
+
+        // then
+        assert_eq!(version.increment(Increment::Minor), None);
+        assert_eq!(
+            version.increment(Increment::Patch),
+            Some(SemanticVersion::new(1, u32::MAX, 4, None, None))
+        );
+    }
+
+    #[test]
+    fn should_determine_increment_from_commit() {
+        // given
+        let commit = |message: &str| ConventionalCommit::from_str(message).unwrap();
+
+        // then
+        assert_eq!(
+            Increment::from(&commit("feat!: remove api")),
+            Increment::Major
+        );
+        assert_eq!(
+            Increment::from(&commit("fix: x\n\nBREAKING CHANGE: y")),
+            Increment::Major
+        );
+        assert_eq!(Increment::from(&commit("feat: add api")), Increment::Minor);
+        assert_eq!(Increment::from(&commit("fix: crash")), Increment::Patch);
+        assert_eq!(Increment::from(&commit("docs: readme")), Increment::None);
+        assert!(Increment::Major > Increment::Minor && Increment::Patch > Increment::None);
+    }
+
     #[test]
     fn should_parse_semantic_version_string_into_semantic_version_instance_with_metadata() {
         // given
         let input = String::from("32.12.4+202105272159");
 
         // when
-        let actual = SemanticVersion::from(input);
+        let actual = SemanticVersion::from_str(&input);
 
         // then
         assert_eq!(
@@ -308,7 +602,7 @@ mod tests {
         let input = String::from("v32.12.4+202105272159");
 
         // when
-        let actual = SemanticVersion::from(input);
+        let actual = SemanticVersion::from_str(&input);
 
         // then
         assert_eq!(
@@ -426,6 +720,139 @@ mod tests {
         assert!(first > second)
     }
 
+    #[test]
+    fn should_convert_semver_with_pre_release_and_metadata_to_string() {
+        // given
+        let version = SemanticVersion::new(
+            2,
+            0,
+            0,
+            Some(PreRelease::first(RC)),
+            Some(String::from("build.5")),
+        );
+
+        // then
+        assert_eq!(version.to_string(), "2.0.0-rc.1+build.5");
+    }
+
+    #[test]
+    fn should_parse_arbitrary_pre_release_identifiers() {
+        // when
+        let nightly = SemanticVersion::from_str("1.0.0-nightly.20240501");
+        let numeric = SemanticVersion::from_str("1.0.0-1");
+        let mixed = SemanticVersion::from_str("1.0.0-x.7.z-z");
+
+        // then
+        assert_eq!(nightly.unwrap().to_string(), "1.0.0-nightly.20240501");
+        assert_eq!(numeric.unwrap().to_string(), "1.0.0-1");
+        assert_eq!(mixed.unwrap().to_string(), "1.0.0-x.7.z-z");
+        assert!(SemanticVersion::from_str("1.0.0-a_b").is_err());
+    }
+
+    #[test]
+    fn should_reject_signed_or_zero_padded_pre_release_numbers() {
+        assert_eq!(PreRelease::from("rc.+1"), None);
+        assert_eq!(PreRelease::from("rc.01"), None);
+        assert_eq!(PreRelease::from("rc.0").unwrap().to_string(), "rc.0");
+        // a hyphen makes it an alphanumeric identifier rather than a negative number
+        assert_eq!(
+            PreRelease::from("rc.-1").unwrap().identifiers(),
+            vec!["rc", "-1"]
+        );
+        assert_eq!(
+            PreRelease::from("rc.4294967296")
+                .unwrap()
+                .next()
+                .to_string(),
+            "rc.4294967297"
+        );
+    }
+
+    #[test]
+    fn should_move_on_to_next_pre_release_when_applying_commit() {
+        // given
+        let version = SemanticVersion::from_str("2.0.0-rc.1").unwrap();
+        let commit = ConventionalCommit::from_str("feat!: breaking").unwrap();
+
+        // when
+        let new_version = version.apply_commit(commit).unwrap();
+
+        // then
+        assert_eq!(new_version.to_string(), "2.0.0-rc.2");
+    }
+
+    #[test]
+    fn should_mark_pre_release_as_lesser_than_release() {
+        let rc = SemanticVersion::from_str("2.0.0-rc.1").unwrap();
+        let next_rc = SemanticVersion::from_str("2.0.0-rc.2").unwrap();
+        let beta = SemanticVersion::from_str("2.0.0-beta.3").unwrap();
+        let release = SemanticVersion::new(2, 0, 0, None, None);
+
+        assert!(rc < release);
+        assert!(rc < next_rc);
+        assert!(beta < rc);
+        assert!(SemanticVersion::new(1, 9, 9, None, None) < beta);
+    }
+
+    #[test]
+    fn should_order_versions_by_semver_precedence() {
+        // given
+        let expected = vec![
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+            "1.0.1-0.3.7",
+            "1.0.1-0.3.7.x",
+            "1.0.1-x.7.z.92",
+            "1.0.1",
+            "1.10.0",
+            "2.0.0",
+        ];
+        let mut versions: Vec<SemanticVersion> = expected
+            .iter()
+            .rev()
+            .map(|version| SemanticVersion::from_str(version).unwrap())
+            .collect();
+
+        // when
+        versions.sort();
+
+        // then
+        let actual: Vec<String> = versions.iter().map(|v| v.to_string()).collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn should_ignore_metadata_in_precedence() {
+        let first = SemanticVersion::from_str("1.0.0-rc.1+build.1").unwrap();
+        let second = SemanticVersion::from_str("1.0.0-rc.1+build.2").unwrap();
+
+        assert_eq!(first.cmp(&second), std::cmp::Ordering::Equal);
+    }
+
+    #[test]
+    fn should_reject_numeric_identifiers_with_leading_zeros() {
+        assert!(SemanticVersion::from_str("1.0.0-rc.01").is_err());
+        assert!(SemanticVersion::from_str("1.0.0-01.rc").is_err());
+        assert!(SemanticVersion::from_str("1.0.0-0.rc").is_ok());
+    }
+
+    #[test]
+    fn should_accept_custom_pre_release_channels() {
+        assert_eq!(
+            PreReleaseType::from_identifier("nightly"),
+            Some(PreReleaseType::Custom(String::from("nightly")))
+        );
+        assert_eq!(PreReleaseType::from_identifier("rc"), Some(RC));
+        assert_eq!(PreReleaseType::from_identifier("rc.1"), None);
+        assert_eq!(PreRelease::first(Beta).next().to_string(), "beta.2");
+    }
+
     #[test]
     fn should_convert_prerelease_with_alpha_beta_and_version_into_string() {
         let input = PreRelease {
@@ -483,4 +910,25 @@ mod tests {
     fn should_return_none_when_trying_to_convert_unknown_value() {
         assert_eq!(PreReleaseType::from("rcc"), None)
     }
+
+    #[test]
+    fn should_hash_versions_consistently_with_equality() {
+        // given
+        let versions = [
+            "1.0.0-rc.1+build.1",
+            "1.0.0-rc.1+build.2",
+            "v1.0.0-rc.1",
+            "1.0.0",
+        ];
+
+        // when
+        let unique: HashSet<SemanticVersion> = versions
+            .iter()
+            .map(|version| SemanticVersion::try_from(*version).unwrap())
+            .collect();
+
+        // then
+        assert_eq!(unique.len(), 2);
+        assert!("1.0".parse::<SemanticVersion>().is_err());
+    }
 }
@@ -0,0 +1,333 @@
+use crate::core::base::ParseError;
+use crate::core::semantic_version::{PreRelease, SemanticVersion};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::fmt;
+use std::fmt::Formatter;
+
+lazy_static! {
+    static ref PARTIAL_VERSION_REGEX: Regex = Regex::new(
+        r"^v?(?<major>0|[1-9]\d*|[*xX])(?:\.(?<minor>0|[1-9]\d*|[*xX]))?(?:\.(?<patch>0|[1-9]\d*|[*xX]))?(?:-(?<pre_release>[0-9A-Za-z.-]+))?(?:\+[0-9A-Za-z.-]+)?$"
+    )
+    .unwrap();
+}
+
+/// Set of versions, written as comma separated requirements, e.g. `>=1.0.0, <2`.
+///
+/// Supported requirements, with missing version parts treated as wildcards:
+/// - `^1.2.3` (or just `1.2.3`): compatible versions, `>=1.2.3, <2.0.0`; for `0.x` versions
+///   the left-most non-zero part is kept, so `^0.2.3` means `>=0.2.3, <0.3.0`,
+/// - `~1.2.3`: patch updates only, `>=1.2.3, <1.3.0`; `~1` means `>=1.0.0, <2.0.0`,
+/// - `=1.2.3`, `>1.2.3`, `>=1.2.3`, `<1.2.3`, `<=1.2.3`: comparisons,
+/// - `1.2.*`, `1.x`, `*`: wildcards.
+///
+/// Pre-releases are only matched by ranges mentioning a pre-release of the same version,
+/// so `>=1.0.0-rc.1` matches `1.0.0-rc.2`, but `>=0.9.0` doesn't.
+#[derive(Debug)]
+pub struct VersionRange {
+    pub range: String,
+    bounds: Vec<Bound>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Bound {
+    Exactly(SemanticVersion),
+    Above(SemanticVersion),
+    AtLeast(SemanticVersion),
+    Below(SemanticVersion),
+    AtMost(SemanticVersion),
+}
+
+impl Bound {
+    fn version(&self) -> &SemanticVersion {
+        match self {
+            Bound::Exactly(version)
+            | Bound::Above(version)
+            | Bound::AtLeast(version)
+            | Bound::Below(version)
+            | Bound::AtMost(version) => version,
+        }
+    }
+
+    fn matches(&self, version: &SemanticVersion) -> bool {
+        match self {
+            Bound::Exactly(bound) => version == bound,
+            Bound::Above(bound) => version > bound,
+            Bound::AtLeast(bound) => version >= bound,
+            Bound::Below(bound) => version < bound,
+            Bound::AtMost(bound) => version <= bound,
+        }
+    }
+}
+
+/// Version with possibly missing parts, as written in a requirement.
+struct PartialVersion {
+    major: Option<u32>,
+    minor: Option<u32>,
+    patch: Option<u32>,
+    pre_release: Option<PreRelease>,
+    /// Whether a part was written as `*` or `x`, rather than left out.
+    wildcard: bool,
+}
+
+impl PartialVersion {
+    fn from(input: &str) -> Option<Self> {
+        let captures = PARTIAL_VERSION_REGEX.captures(input)?;
+        let part = |name: &str| -> Option<Option<u32>> {
+            match captures.name(name).map(|part| part.as_str()) {
+                None | Some("*" | "x" | "X") => Some(None),
+                // numbers too large for a version are rejected, rather than taken for wildcards
+                Some(number) => number.parse().ok().map(Some),
+            }
+        };
+
+        let major: Option<u32> = part("major")?;
+        let minor: Option<u32> = major.and(part("minor")?);
+        let patch: Option<u32> = minor.and(part("patch")?);
+        let pre_release = match captures.name("pre_release") {
+            // a pre-release only makes sense for a complete version
+            Some(pre_release) if patch.is_some() => Some(PreRelease::from(pre_release.as_str())?),
+            Some(_) => return None,
+            None => None,
+        };
+
+        let wildcard = ["major", "minor", "patch"].iter().any(|name| {
+            captures
+                .name(name)
+                .is_some_and(|part| matches!(part.as_str(), "*" | "x" | "X"))
+        });
+
+        return Some(Self {
+            major,
+            minor,
+            patch,
+            pre_release,
+            wildcard,
+        });
+    }
+
+    /// Lowest version matching the partial version.
+    fn lowest(&self) -> SemanticVersion {
+        SemanticVersion::new(
+            self.major.unwrap_or(0),
+            self.minor.unwrap_or(0),
+            self.patch.unwrap_or(0),
+            self.pre_release.clone(),
+            None,
+        )
+    }
+
+    fn is_complete(&self) -> bool {
+        self.patch.is_some()
+    }
+
+    /// Lowest version above all versions matching an incomplete partial version, or `None`
+    /// when it doesn't fit into a version.
+    fn next(&self) -> Option<SemanticVersion> {
+        match (self.major, self.minor) {
+            (Some(major), None) => Some(SemanticVersion::new(
+                major.checked_add(1)?,
+                0,
+                0,
+                None,
+                None,
+            )),
+            (Some(major), Some(minor)) => Some(SemanticVersion::new(
+                major,
+                minor.checked_add(1)?,
+                0,
+                None,
+                None,
+            )),
+            (None, _) => None,
+        }
+    }
+}
+
+impl VersionRange {
+    pub fn from(range: &str) -> Result<Self, ParseError> {
+        let mut bounds: Vec<Bound> = vec![];
+        for requirement in range.split(',').map(str::trim) {
+            bounds.extend(parse_requirement(requirement).ok_or_else(|| ParseError {
+                line: String::from(requirement),
+                reason: String::from("Invalid version requirement"),
+            })?);
+        }
+
+        return Ok(Self {
+            range: String::from(range.trim()),
+            bounds,
+        });
+    }
+
+    pub fn matches(&self, version: &SemanticVersion) -> bool {
+        if !self.bounds.iter().all(|bound| bound.matches(version)) {
+            return false;
+        }
+
+        return !version.is_pre_release()
+            || self.bounds.iter().any(|bound| {
+                bound.version().is_pre_release() && bound.version().has_same_core(version)
+            });
+    }
+}
+
+impl fmt::Display for VersionRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.range)
+    }
+}
+
+fn parse_requirement(requirement: &str) -> Option<Vec<Bound>> {
+    let operator_length = requirement
+        .find(|c: char| !matches!(c, '>' | '<' | '=' | '^' | '~'))
+        .unwrap_or(requirement.len());
+    let (operator, version) = requirement.split_at(operator_length);
+    let version = PartialVersion::from(version.trim())?;
+
+    // like in Cargo, `1.2` means `^1.2`, but `1.2.*` only matches `1.2.x` versions
+    let operator = match operator {
+        "" if version.wildcard => "=",
+        operator => operator,
+    };
+
+    let lowest = version.lowest();
+    let bounds = match (operator, version.major, version.minor) {
+        // wildcards matching everything
+        ("" | "^" | "~" | "=" | ">=", None, _) => vec![],
+        // exact versions and wildcards
+        ("=", _, _) => match version.is_complete() {
+            true => vec![Bound::Exactly(lowest)],
+            false => vec![Bound::AtLeast(lowest), Bound::Below(version.next()?)],
+        },
+        // upper bounds that don't fit into a version reject the requirement
+        ("" | "^", Some(major), minor) => {
+            let upper = match (major, minor, version.patch) {
+                (0, Some(0), Some(patch)) => {
+                    SemanticVersion::new(0, 0, patch.checked_add(1)?, None, None)
+                }
+                (0, Some(minor), _) => {
+                    SemanticVersion::new(0, minor.checked_add(1)?, 0, None, None)
+                }
+                _ => SemanticVersion::new(major.checked_add(1)?, 0, 0, None, None),
+            };
+            vec![Bound::AtLeast(lowest), Bound::Below(upper)]
+        }
+        ("~", Some(major), minor) => {
+            let upper = match minor {
+                Some(minor) => SemanticVersion::new(major, minor.checked_add(1)?, 0, None, None),
+                None => SemanticVersion::new(major.checked_add(1)?, 0, 0, None, None),
+            };
+            vec![Bound::AtLeast(lowest), Bound::Below(upper)]
+        }
+        (">=", _, _) => vec![Bound::AtLeast(lowest)],
+        (">", Some(_), _) => match version.is_complete() {
+            true => vec![Bound::Above(lowest)],
+            false => vec![Bound::AtLeast(version.next()?)],
+        },
+        ("<", Some(_), _) => vec![Bound::Below(lowest)],
+        ("<=", Some(_), _) => match version.is_complete() {
+            true => vec![Bound::AtMost(lowest)],
+            false => vec![Bound::Below(version.next()?)],
+        },
+        _ => return None,
+    };
+
+    return Some(bounds);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::semantic_version::SemanticVersion;
+    use crate::core::version_range::VersionRange;
+    use std::str::FromStr;
+
+    fn matches(range: &str, version: &str) -> bool {
+        let range = VersionRange::from(range).unwrap();
+        return range.matches(&SemanticVersion::from_str(version).unwrap());
+    }
+
+    #[test]
+    fn should_match_caret_requirements() {
+        assert!(matches("^1.2", "1.2.0"));
+        assert!(matches("^1.2", "1.9.3"));
+        assert!(!matches("^1.2", "1.1.9"));
+        assert!(!matches("^1.2", "2.0.0"));
+        assert!(matches("1.2.3", "1.4.0"));
+        assert!(matches("^0.2.3", "0.2.9"));
+        assert!(!matches("^0.2.3", "0.3.0"));
+        assert!(matches("^0.0.3", "0.0.3"));
+        assert!(!matches("^0.0.3", "0.0.4"));
+        assert!(matches("^0", "0.9.9"));
+    }
+
+    #[test]
+    fn should_match_tilde_requirements() {
+        assert!(matches("~1.2.3", "1.2.3"));
+        assert!(matches("~1.2.3", "1.2.9"));
+        assert!(!matches("~1.2.3", "1.3.0"));
+        assert!(!matches("~1.2.3", "1.2.2"));
+        assert!(matches("~1", "1.9.0"));
+        assert!(!matches("~1", "2.0.0"));
+    }
+
+    #[test]
+    fn should_match_comparisons_joined_with_comma() {
+        assert!(matches(">=1.0.0, <2", "1.0.0"));
+        assert!(matches(">=1.0.0, <2", "1.99.0"));
+        assert!(!matches(">=1.0.0, <2", "2.0.0"));
+        assert!(!matches(">=1.0.0, <2", "0.9.0"));
+        assert!(matches(">1.2", "1.3.0"));
+        assert!(!matches(">1.2", "1.2.5"));
+        assert!(matches("<=1.2", "1.2.5"));
+        assert!(!matches("<=1.2", "1.3.0"));
+        assert!(matches("=1.2.3", "1.2.3+build"));
+        assert!(!matches("=1.2.3", "1.2.4"));
+        assert!(matches(">= 1.0.0", "1.0.0"));
+    }
+
+    #[test]
+    fn should_match_wildcards() {
+        assert!(matches("*", "3.2.1"));
+        assert!(matches("1.2.*", "1.2.7"));
+        assert!(!matches("1.2.*", "1.3.0"));
+        assert!(matches("1.x", "1.5.0"));
+        assert!(!matches("1.x", "2.0.0"));
+    }
+
+    #[test]
+    fn should_match_pre_releases_only_when_range_mentions_them() {
+        assert!(!matches(">=0.9.0", "1.0.0-rc.1"));
+        assert!(!matches("<2", "2.0.0-rc.1"));
+        assert!(!matches("*", "1.0.0-rc.1"));
+        assert!(matches(">=1.0.0-rc.1", "1.0.0-rc.2"));
+        assert!(matches(">=1.0.0-rc.1", "1.0.0"));
+        assert!(!matches(">=1.0.0-rc.2", "1.0.0-rc.1"));
+        assert!(!matches(">=1.0.0-rc.1", "1.1.0-rc.1"));
+    }
+
+    #[test]
+    fn should_reject_invalid_requirements() {
+        assert!(VersionRange::from("").is_err());
+        assert!(VersionRange::from(">=1.0.0,").is_err());
+        assert!(VersionRange::from("^1.2-rc.1").is_err());
+        assert!(VersionRange::from("=>1.0.0").is_err());
+        assert!(VersionRange::from("<*").is_err());
+        assert!(VersionRange::from("1.2.3.4").is_err());
+    }
+
+    #[test]
+    fn should_reject_requirements_out_of_version_bounds() {
+        assert!(VersionRange::from("^4294967296").is_err());
+        assert!(VersionRange::from("1.99999999999.0").is_err());
+        assert!(VersionRange::from("^4294967295").is_err());
+        assert!(VersionRange::from("~1.4294967295").is_err());
+        assert!(VersionRange::from("^0.0.4294967295").is_err());
+        assert!(VersionRange::from("<=4294967295.x").is_err());
+        assert!(matches(">=4294967295.0.0", "4294967295.1.0"));
+        assert!(matches(
+            "=4294967295.4294967295.4294967295",
+            "4294967295.4294967295.4294967295"
+        ));
+    }
+}
@@ -0,0 +1,97 @@
+use con_comm::changelog::links::Links;
+use con_comm::changelog::{
+    prepend_changelog, process_args, ChangelogArgs, ChangelogRenderer, Format, ReleaseContext,
+};
+use con_comm::config::Config;
+use con_comm::git;
+use con_comm::git::RevisionRange;
+use con_comm::monorepo::Monorepo;
+use con_comm::release::{find_filtered_releases, find_releases};
+use std::env;
+use std::fs;
+
+/*
+Renders a changelog of all releases tagged in the history of HEAD, newest first. By default,
+the template from the `[changelog]` configuration is used. `--format` selects a preset instead,
+e.g. `angular` or `asciidoc`, a standalone `html` page or `json`. With `--output <file>`, the
+changelog is written to the file instead of the standard output.
+
+In a monorepo, a package's tag prefix, e.g. `--tag-prefix core@`, renders the changelog of
+that package, made of the commits attributed to it only.
+*/
+fn main() {
+    let args: ChangelogArgs = process_args(&env::args().collect::<Vec<String>>());
+
+    let current_dir = env::current_dir()
+        .unwrap_or_else(|e| panic!("Couldn't determine current directory: {}", e));
+    let config = Config::discover(&current_dir)
+        .unwrap_or_else(|e| panic!("Couldn't load configuration: {}", e));
+    let options = config
+        .read_options()
+        .unwrap_or_else(|e| panic!("Couldn't load configuration: {}", e));
+    let repository = git::open_history(&current_dir, options)
+        .unwrap_or_else(|e| panic!("Couldn't open repository: {}", e));
+
+    let remote_url = repository
+        .remote_url(&config.changelog.links.remote)
+        .unwrap_or_else(|e| panic!("Couldn't read remote URL: {}", e));
+    let links = Links::from(&config.changelog.links, remote_url.as_deref())
+        .unwrap_or_else(|e| panic!("Couldn't configure changelog links: {}", e));
+    let identities = config
+        .identities(repository.root())
+        .unwrap_or_else(|e| panic!("Couldn't load contributor identities: {}", e));
+    let renderer = match args.format {
+        Format::Preset(preset) => ChangelogRenderer::preset(preset),
+        _ => ChangelogRenderer::from(&config)
+            .unwrap_or_else(|e| panic!("Couldn't load changelog template: {}", e)),
+    }
+    .with_links(links)
+    .with_identities(identities);
+
+    let prefix = args
+        .tag_prefix
+        .as_ref()
+        .unwrap_or(&config.release.tag_prefix);
+    let monorepo = Monorepo::from(&config, repository.root())
+        .unwrap_or_else(|e| panic!("Couldn't read monorepo packages: {}", e));
+    let package = monorepo
+        .as_ref()
+        .and_then(|monorepo| Some((monorepo, monorepo.find_package(prefix)?)));
+    let releases = match package {
+        Some((monorepo, package)) => {
+            let mut include = monorepo
+                .package_filter(repository.as_ref(), package, &RevisionRange::default())
+                .unwrap_or_else(|e| panic!("Couldn't read changed paths: {}", e));
+            find_filtered_releases(repository.as_ref(), prefix, &mut include)
+        }
+        None => find_releases(repository.as_ref(), prefix),
+    };
+    let releases: Vec<ReleaseContext> = releases
+        .unwrap_or_else(|e| panic!("Couldn't read releases: {}", e))
+        .iter()
+        .map(|(release, date)| renderer.release_context(release, *date))
+        .collect();
+    if releases.is_empty() {
+        eprintln!("No release tags with prefix {}", prefix);
+    }
+
+    let changelog = match args.format {
+        Format::Json => renderer.render_json(&releases),
+        Format::Html => renderer
+            .render_html(releases)
+            .unwrap_or_else(|e| panic!("Couldn't render changelog: {}", e)),
+        Format::Template | Format::Preset(_) => {
+            let content = renderer
+                .render(releases)
+                .unwrap_or_else(|e| panic!("Couldn't render changelog: {}", e));
+            prepend_changelog("", &renderer.header, &content)
+        }
+    };
+
+    match &args.output {
+        Some(path) => {
+            fs::write(path, changelog).unwrap_or_else(|e| panic!("Couldn't write {}: {}", path, e))
+        }
+        None => print!("{}", changelog),
+    }
+}
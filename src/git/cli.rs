@@ -0,0 +1,826 @@
+use crate::git::{decode, Signature};
+use crate::git::{Commit, GitError, Reference, Repository, RevisionRange, Tag};
+use chrono::DateTime;
+use std::collections::HashMap;
+use std::env;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::thread;
+
+/// Records are separated with NUL (`-z`), which can't be a part of a commit message, and
+/// fields with the unit separator. The message goes last, so it may contain anything else.
+const LOG_FORMAT: &str = "--format=%H%x1f%P%x1f%an%x1f%ae%x1f%aI%x1f%cn%x1f%ce%x1f%cI%x1f%e%x1f%B";
+const LOG_FIELDS: usize = 10;
+const FIELD_SEPARATOR: u8 = 0x1f;
+
+/// Repository backend running the `git` binary.
+#[derive(Debug)]
+pub struct CliRepository {
+    git: PathBuf,
+    root: PathBuf,
+}
+
+impl CliRepository {
+    pub fn new(git: PathBuf, root: PathBuf) -> Self {
+        Self { git, root }
+    }
+
+    /// Opens the repository containing `path`, using `git` found on `PATH`.
+    pub fn discover(path: &Path) -> Result<Self, GitError> {
+        let git = find_git().ok_or_else(|| GitError {
+            reason: String::from("Couldn't find git executable on PATH"),
+        })?;
+
+        let output = run(&git, path, &["rev-parse", "--show-toplevel"], None)?;
+        let root = String::from_utf8_lossy(&output).trim().to_string();
+
+        return Ok(Self::new(git, PathBuf::from(root)));
+    }
+
+    fn git(&self, args: &[&str]) -> Result<Vec<u8>, GitError> {
+        run(&self.git, &self.root, args, None)
+    }
+
+    /// Reads commits with `git log`. Messages are requested in UTF-8, regardless of the
+    /// `i18n.logOutputEncoding` setting.
+    fn log(&self, options: &[&str], revisions: &[&str]) -> Result<Vec<Commit>, GitError> {
+        let mut args: Vec<&str> = vec!["log", "-z", "--encoding=UTF-8", LOG_FORMAT];
+        args.extend(options);
+        args.push("--end-of-options");
+        args.extend(revisions);
+        args.push("--");
+
+        return parse_log(&self.git(&args)?);
+    }
+
+    fn git_string(&self, args: &[&str]) -> Result<String, GitError> {
+        let output = self.git(args)?;
+        return Ok(String::from_utf8_lossy(&output).to_string());
+    }
+}
+
+impl Repository for CliRepository {
+    fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn current_branch(&self) -> Result<Option<String>, GitError> {
+        // symbolic-ref fails for a detached HEAD, which is not an error for us
+        let branch = match self.git_string(&["symbolic-ref", "--short", "-q", "HEAD"]) {
+            Ok(branch) => branch,
+            Err(_) => return Ok(None),
+        };
+
+        return Ok(Some(String::from(branch.trim())).filter(|branch| !branch.is_empty()));
+    }
+
+    fn staged_paths(&self) -> Result<Vec<String>, GitError> {
+        let output = self.git(&["diff", "--cached", "--name-only", "-z"])?;
+        return Ok(split_nul(&output));
+    }
+
+    fn resolve(&self, revision: &str) -> Result<String, GitError> {
+        let id = self.git_string(&[
+            "rev-parse",
+            "--verify",
+            "--end-of-options",
+            &format!("{}^{{commit}}", revision),
+        ])?;
+        return Ok(String::from(id.trim()));
+    }
+
+    fn commits(&self, range: &RevisionRange) -> Result<Vec<Commit>, GitError> {
+        let excluded = range.from.as_ref().map(|from| format!("^{}", from));
+        let mut revisions: Vec<&str> = vec![&range.to];
+        revisions.extend(excluded.as_deref());
+
+        return self.log(&[], &revisions);
+    }
+
+    fn message(&self, revision: &str) -> Result<String, GitError> {
+        let commit = self
+            .log(&["-1"], &[revision])?
+            .pop()
+            .ok_or_else(|| GitError {
+                reason: format!("Couldn't find commit {}", revision),
+            })?;
+        return Ok(commit.message);
+    }
+
+    fn changed_paths(&self, revision: &str) -> Result<Vec<String>, GitError> {
+        let output = self.git(&[
+            "show",
+            "--diff-merges=first-parent",
+            "--no-renames",
+            "--format=",
+            "--name-only",
+            "-z",
+            "--end-of-options",
+            revision,
+            "--",
+        ])?;
+        return Ok(split_nul(&output));
+    }
+
+    fn changed_paths_in_range(
+        &self,
+        range: &RevisionRange,
+    ) -> Result<HashMap<String, Vec<String>>, GitError> {
+        let excluded = range.from.as_ref().map(|from| format!("^{}", from));
+        let mut args: Vec<&str> = vec![
+            "log",
+            "-z",
+            // every commit starts with an empty record, as paths can't be empty
+            "--format=%x00%H",
+            "--diff-merges=first-parent",
+            "--no-renames",
+            "--name-only",
+            "--end-of-options",
+            &range.to,
+        ];
+        args.extend(excluded.as_deref());
+        args.push("--");
+
+        return Ok(parse_changed_paths(&self.git(&args)?));
+    }
+
+    fn tags(&self) -> Result<Vec<Tag>, GitError> {
+        let references = self.references()?;
+
+        return Ok(references
+            .into_iter()
+            .filter_map(|reference| {
+                reference.name.strip_prefix("refs/tags/").map(|name| Tag {
+                    name: String::from(name),
+                    target: reference.target.clone(),
+                })
+            })
+            .collect());
+    }
+
+    fn references(&self) -> Result<Vec<Reference>, GitError> {
+        // %(*objectname) is only set for annotated tags, and points to the tagged object
+        let output = self.git_string(&[
+            "for-each-ref",
+            "--format=%(refname)%00%(objectname)%00%(*objectname)",
+            "refs/heads",
+            "refs/remotes",
+            "refs/tags",
+        ])?;
+
+        let mut references: Vec<Reference> = vec![];
+        for line in output.lines() {
+            let fields: Vec<&str> = line.split('\0').collect();
+            if fields.len() != 3 {
+                continue;
+            }
+
+            let target = match fields[2].is_empty() {
+                true => fields[1],
+                false => fields[2],
+            };
+            references.push(Reference {
+                name: String::from(fields[0]),
+                target: String::from(target),
+            });
+        }
+
+        return Ok(references);
+    }
+
+    fn remote_url(&self, name: &str) -> Result<Option<String>, GitError> {
+        // git config exits with 1 for a missing key, which is not an error for us
+        let url = match self.git_string(&["config", "--get", &format!("remote.{}.url", name)]) {
+            Ok(url) => url,
+            Err(_) => return Ok(None),
+        };
+
+        return Ok(Some(String::from(url.trim())).filter(|url| !url.is_empty()));
+    }
+
+    fn create_tag(&self, name: &str, target: &str, message: &str) -> Result<(), GitError> {
+        self.git(&[
+            "tag",
+            "--annotate",
+            "--cleanup=verbatim",
+            "--message",
+            message,
+            "--end-of-options",
+            name,
+            target,
+        ])?;
+        return Ok(());
+    }
+
+    fn notes(&self, notes_ref: &str) -> Result<HashMap<String, String>, GitError> {
+        let listed = self.git_string(&["notes", "--ref", notes_ref, "list"])?;
+        let pairs: Vec<(&str, &str)> = listed
+            .lines()
+            .filter_map(|line| line.split_once(' '))
+            .collect();
+        if pairs.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        // all note blobs are read by a single process
+        let input: String = pairs
+            .iter()
+            .map(|(note, _)| format!("{}\n", note))
+            .collect();
+        let output = run(
+            &self.git,
+            &self.root,
+            &["cat-file", "--batch"],
+            Some(input.as_bytes()),
+        )?;
+        let mut contents = parse_batch(&output)?;
+
+        let mut notes: HashMap<String, String> = HashMap::new();
+        for (note, commit) in pairs {
+            if let Some(content) = contents.remove(note) {
+                notes.insert(String::from(commit), decode(&content, None));
+            }
+        }
+
+        return Ok(notes);
+    }
+
+    fn add_note(&self, notes_ref: &str, revision: &str, note: &str) -> Result<(), GitError> {
+        self.git(&[
+            "notes",
+            "--ref",
+            notes_ref,
+            "add",
+            "--force",
+            "--message",
+            note,
+            "--end-of-options",
+            revision,
+        ])?;
+        return Ok(());
+    }
+}
+
+/// Looks for the `git` executable in directories listed in `PATH`.
+pub fn find_git() -> Option<PathBuf> {
+    let name = format!("git{}", env::consts::EXE_SUFFIX);
+    let path = env::var_os("PATH")?;
+
+    return env::split_paths(&path)
+        .map(|directory| directory.join(&name))
+        .find(|candidate| candidate.is_file());
+}
+
+/// Runs `git commit` with the given message, passing extra arguments through.
+/// Unlike the rest of the repository access, this always goes through the git binary,
+/// so that hooks are run as usual.
+pub fn commit(message: &str, extra_args: &[String]) -> Result<ExitStatus, GitError> {
+    let git = find_git().ok_or_else(|| GitError {
+        reason: String::from("Couldn't find git executable on PATH"),
+    })?;
+
+    let mut child = Command::new(git)
+        .args(["commit", "-F", "-"])
+        .args(extra_args)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| GitError {
+            reason: format!("Failed to start git commit: {}", e),
+        })?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(message.as_bytes()).map_err(|e| GitError {
+            reason: format!("Failed to pass message to git commit: {}", e),
+        })?;
+    }
+
+    return child.wait().map_err(|e| GitError {
+        reason: format!("Failed to run git commit: {}", e),
+    });
+}
+
+fn run(
+    git: &Path,
+    directory: &Path,
+    args: &[&str],
+    input: Option<&[u8]>,
+) -> Result<Vec<u8>, GitError> {
+    let mut child = Command::new(git)
+        .args(args)
+        .current_dir(directory)
+        .env("LANG", "C")
+        .stdin(match input {
+            Some(_) => Stdio::piped(),
+            None => Stdio::null(),
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| GitError {
+            reason: format!("Failed to start git {}: {}", args[0], e),
+        })?;
+
+    // written from another thread, so that git doesn't block on a full output pipe meanwhile
+    let writer = match (input, child.stdin.take()) {
+        (Some(input), Some(mut stdin)) => {
+            let input = input.to_vec();
+            Some(thread::spawn(move || stdin.write_all(&input)))
+        }
+        _ => None,
+    };
+
+    let output = child.wait_with_output().map_err(|e| GitError {
+        reason: format!("Failed to run git {}: {}", args[0], e),
+    })?;
+    if let Some(writer) = writer {
+        writer
+            .join()
+            .expect("writing to git shouldn't panic")
+            .map_err(|e| GitError {
+                reason: format!("Failed to pass input to git {}: {}", args[0], e),
+            })?;
+    }
+
+    if !output.status.success() {
+        return Err(GitError {
+            reason: format!(
+                "git {} failed: {}",
+                args[0],
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        });
+    }
+
+    return Ok(output.stdout);
+}
+
+fn parse_log(output: &[u8]) -> Result<Vec<Commit>, GitError> {
+    output
+        .split(|byte| *byte == 0)
+        .filter(|record| !record.is_empty())
+        .map(parse_record)
+        .collect()
+}
+
+fn parse_record(record: &[u8]) -> Result<Commit, GitError> {
+    let fields: Vec<&[u8]> = record
+        .splitn(LOG_FIELDS, |byte| *byte == FIELD_SEPARATOR)
+        .collect();
+    if fields.len() != LOG_FIELDS {
+        return Err(GitError {
+            reason: format!(
+                "Malformed git log record: {}",
+                String::from_utf8_lossy(record)
+            ),
+        });
+    }
+
+    let encoding = String::from_utf8_lossy(fields[8]);
+    let encoding = Some(encoding.as_ref()).filter(|encoding| !encoding.is_empty());
+    let id = String::from_utf8_lossy(fields[0]).to_string();
+
+    let signature = |name: &[u8], email: &[u8], date: &[u8]| -> Result<Signature, GitError> {
+        let date = String::from_utf8_lossy(date);
+        let timestamp = DateTime::parse_from_rfc3339(&date).map_err(|e| GitError {
+            reason: format!("Couldn't parse date of commit {}: {}", id, e),
+        })?;
+
+        return Ok(Signature {
+            name: decode(name, encoding),
+            email: decode(email, encoding),
+            timestamp,
+        });
+    };
+
+    return Ok(Commit {
+        id: id.clone(),
+        parents: String::from_utf8_lossy(fields[1])
+            .split_whitespace()
+            .map(String::from)
+            .collect(),
+        author: signature(fields[2], fields[3], fields[4])?,
+        committer: signature(fields[5], fields[6], fields[7])?,
+        message: decode(fields[9], encoding),
+    });
+}
+
+/// Parses `git log -z --name-only` output, where every commit starts with an empty record,
+/// followed by its id and the changed paths. The first path is preceded by a newline.
+fn parse_changed_paths(output: &[u8]) -> HashMap<String, Vec<String>> {
+    let mut changed_paths: HashMap<String, Vec<String>> = HashMap::new();
+    let mut records = output.split(|byte| *byte == 0).peekable();
+
+    while let Some(record) = records.next() {
+        if !record.is_empty() {
+            continue;
+        }
+        let id = match records.next() {
+            Some(id) if !id.is_empty() => String::from_utf8_lossy(id).to_string(),
+            _ => continue,
+        };
+
+        let mut paths: Vec<String> = vec![];
+        while let Some(path) = records.next_if(|record| !record.is_empty()) {
+            let path = match paths.is_empty() {
+                true => path.strip_prefix(b"\n").unwrap_or(path),
+                false => path,
+            };
+            paths.push(String::from_utf8_lossy(path).to_string());
+        }
+        changed_paths.insert(id, paths);
+    }
+
+    return changed_paths;
+}
+
+/// Parses `git cat-file --batch` output into contents by object id. Missing objects are
+/// left out.
+fn parse_batch(output: &[u8]) -> Result<HashMap<String, Vec<u8>>, GitError> {
+    let malformed = || GitError {
+        reason: String::from("Malformed git cat-file output"),
+    };
+
+    let mut contents: HashMap<String, Vec<u8>> = HashMap::new();
+    let mut rest = output;
+    while !rest.is_empty() {
+        let end = rest
+            .iter()
+            .position(|byte| *byte == b'\n')
+            .ok_or_else(malformed)?;
+        let header = String::from_utf8_lossy(&rest[..end]).to_string();
+        rest = &rest[end + 1..];
+
+        let fields: Vec<&str> = header.split(' ').collect();
+        if fields.len() != 3 {
+            // e.g. `<id> missing`
+            continue;
+        }
+        let size: usize = fields[2].parse().map_err(|_| malformed())?;
+        if rest.len() < size + 1 {
+            return Err(malformed());
+        }
+        contents.insert(String::from(fields[0]), rest[..size].to_vec());
+        rest = &rest[size + 1..];
+    }
+
+    return Ok(contents);
+}
+
+fn split_nul(output: &[u8]) -> Vec<String> {
+    output
+        .split(|byte| *byte == 0)
+        .filter(|item| !item.is_empty())
+        .map(|item| String::from_utf8_lossy(item).to_string())
+        .collect()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::git::cli::{find_git, parse_batch, parse_changed_paths, parse_log, CliRepository};
+    use crate::git::{Repository, RevisionRange, Tag};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::process::{self, Command};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static REPOSITORIES: AtomicUsize = AtomicUsize::new(0);
+
+    /// Creates an empty repository in a fresh temporary directory, unique to the test run.
+    pub fn create_repository(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!(
+            "con-comm-git-{}-{}-{}",
+            name,
+            process::id(),
+            REPOSITORIES.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        git(&root, &["init", "-q", "-b", "main"]);
+        // for commands run by the backends themselves
+        git(&root, &["config", "user.name", "Jane Doe"]);
+        git(&root, &["config", "user.email", "jane@example.com"]);
+        git(&root, &["config", "tag.gpgSign", "false"]);
+        return root;
+    }
+
+    pub fn git(root: &Path, args: &[&str]) -> String {
+        let output = Command::new(find_git().unwrap())
+            .args([
+                "-c",
+                "user.name=Jane Doe",
+                "-c",
+                "user.email=jane@example.com",
+            ])
+            .args(["-c", "commit.gpgsign=false", "-c", "tag.gpgsign=false"])
+            .args(args)
+            .current_dir(root)
+            .env("GIT_CONFIG_NOSYSTEM", "1")
+            .env("GIT_CONFIG_GLOBAL", "/dev/null")
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        return String::from_utf8(output.stdout).unwrap().trim().to_string();
+    }
+
+    /// Commits a change of `file` with the given message, returning the commit id.
+    pub fn commit_file(root: &Path, file: &str, message: &str) -> String {
+        let path = root.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let content = fs::read_to_string(&path).unwrap_or_default();
+        fs::write(&path, format!("{}{}\n", content, message.len())).unwrap();
+        git(root, &["add", file]);
+        git(root, &["commit", "-q", "--cleanup=verbatim", "-m", message]);
+        return git(root, &["rev-parse", "HEAD"]);
+    }
+
+    /// Commits a change with a Latin-1 message, declared with `i18n.commitEncoding`.
+    pub fn commit_latin1(root: &Path) {
+        fs::write(root.join("latin1.txt"), "latin1").unwrap();
+        fs::write(
+            root.join(".git").join("LATIN1_MSG"),
+            b"feat: caf\xe9\n\nJ\xe9r\xf4me was here\n",
+        )
+        .unwrap();
+        git(root, &["add", "latin1.txt"]);
+        git(
+            root,
+            &[
+                "-c",
+                "i18n.commitEncoding=ISO-8859-1",
+                "commit",
+                "-q",
+                "-F",
+                ".git/LATIN1_MSG",
+            ],
+        );
+        // output in the declared encoding would be the default otherwise
+        git(root, &["config", "i18n.logOutputEncoding", "ISO-8859-1"]);
+    }
+
+    fn open(root: &Path) -> CliRepository {
+        CliRepository::new(find_git().unwrap(), root.to_path_buf())
+    }
+
+    #[test]
+    fn should_read_commits_in_range_newest_first() {
+        // given
+        let root = create_repository("cli-commits");
+        let first = commit_file(&root, "a.txt", "feat: first\n\n1234567890\n");
+        let second = commit_file(&root, "a.txt", "fix: second");
+        let third = commit_file(&root, "b.txt", "docs: third\n\nwith body");
+
+        // when
+        let all = open(&root).commits(&RevisionRange::default()).unwrap();
+        let range = open(&root)
+            .commits(&RevisionRange::new(Some(&first), "HEAD"))
+            .unwrap();
+
+        // then
+        let ids: Vec<&str> = all.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec![third.as_str(), second.as_str(), first.as_str()]);
+        assert_eq!(all[2].message, "feat: first\n\n1234567890\n");
+        assert_eq!(range.len(), 2);
+        assert_eq!(range[0].message, "docs: third\n\nwith body\n");
+    }
+
+    #[test]
+    fn should_parse_log_records_with_arbitrary_messages() {
+        // given
+        let output = b"aaa\x1fbbb ccc\x1fJane Doe\x1fjane@example.com\x1f2024-05-01T10:00:00+02:00\x1fJohn Doe\x1fjohn@example.com\x1f2024-05-02T10:00:00Z\x1f\x1ffix: body with digits\n\n1714550400\nweird \x1f field\n\0ddd\x1f\x1fJane Doe\x1fjane@example.com\x1f2024-04-01T10:00:00Z\x1fJane Doe\x1fjane@example.com\x1f2024-04-01T10:00:00Z\x1fISO-8859-1\x1ffeat: caf\xe9\n";
+
+        // when
+        let commits = parse_log(output).unwrap();
+
+        // then
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].id, "aaa");
+        assert_eq!(commits[0].parents, vec!["bbb", "ccc"]);
+        assert_eq!(commits[0].author.name, "Jane Doe");
+        assert_eq!(
+            commits[0].author.timestamp.to_rfc3339(),
+            "2024-05-01T10:00:00+02:00"
+        );
+        assert_eq!(commits[0].committer.email, "john@example.com");
+        assert_eq!(
+            commits[0].message,
+            "fix: body with digits\n\n1714550400\nweird \x1f field\n"
+        );
+        assert!(commits[1].parents.is_empty());
+        assert_eq!(commits[1].message, "feat: caf\u{e9}\n");
+    }
+
+    #[test]
+    fn should_reject_truncated_log_record() {
+        assert!(parse_log(b"aaa\x1fbbb\x1fJane Doe").is_err());
+    }
+
+    #[test]
+    fn should_read_messages_in_declared_encoding() {
+        // given
+        let root = create_repository("cli-encoding");
+        commit_latin1(&root);
+
+        // when
+        let commits = open(&root).commits(&RevisionRange::default()).unwrap();
+
+        // then
+        assert_eq!(
+            commits[0].message,
+            "feat: caf\u{e9}\n\nJ\u{e9}r\u{f4}me was here\n"
+        );
+    }
+
+    #[test]
+    fn should_read_tags_branches_and_messages() {
+        // given
+        let root = create_repository("cli-refs");
+        let first = commit_file(&root, "a.txt", "feat: first");
+        git(&root, &["tag", "v1.0.0"]);
+        let second = commit_file(&root, "a.txt", "fix: second");
+        git(&root, &["tag", "-a", "v1.0.1", "-m", "release 1.0.1"]);
+        git(&root, &["checkout", "-q", "-b", "feat/foo"]);
+        git(&root, &["add", "."]);
+
+        // when
+        let repository = open(&root);
+        let tags = repository.tags().unwrap();
+
+        // then
+        assert_eq!(
+            tags,
+            vec![
+                Tag {
+                    name: String::from("v1.0.0"),
+                    target: first.clone(),
+                },
+                Tag {
+                    name: String::from("v1.0.1"),
+                    target: second.clone(),
+                },
+            ]
+        );
+        assert_eq!(
+            repository.current_branch().unwrap(),
+            Some(String::from("feat/foo"))
+        );
+        assert_eq!(repository.resolve("v1.0.1").unwrap(), second);
+        assert_eq!(repository.message(&first).unwrap(), "feat: first\n");
+        repository
+            .create_tag("v1.1.0", &first, "release notes\n\n# not a comment\n")
+            .unwrap();
+        assert_eq!(repository.resolve("v1.1.0").unwrap(), first);
+        assert_eq!(
+            git(&root, &["tag", "-l", "--format=%(contents)", "v1.1.0"]),
+            "release notes\n\n# not a comment"
+        );
+        assert!(repository
+            .references()
+            .unwrap()
+            .iter()
+            .any(|reference| reference.name == "refs/heads/main"));
+        assert_eq!(repository.remote_url("origin").unwrap(), None);
+        git(
+            &root,
+            &["remote", "add", "origin", "git@github.com:owner/repo.git"],
+        );
+        assert_eq!(
+            repository.remote_url("origin").unwrap(),
+            Some(String::from("git@github.com:owner/repo.git"))
+        );
+        assert!(repository.notes("refs/notes/test").unwrap().is_empty());
+        repository
+            .add_note("refs/notes/test", "v1.0.0", "feat: better first")
+            .unwrap();
+        repository
+            .add_note("refs/notes/test", &first, "feat: best first")
+            .unwrap();
+        let notes = repository.notes("refs/notes/test").unwrap();
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[&first], "feat: best first\n");
+    }
+
+    #[test]
+    fn should_list_staged_paths() {
+        // given
+        let root = create_repository("cli-staged");
+        commit_file(&root, "a.txt", "feat: first");
+        fs::write(root.join("b c.txt"), "new").unwrap();
+        git(&root, &["add", "b c.txt"]);
+
+        // when
+        let paths = open(&root).staged_paths().unwrap();
+
+        // then
+        assert_eq!(paths, vec![String::from("b c.txt")]);
+    }
+
+    #[test]
+    fn should_list_paths_changed_by_commit() {
+        // given
+        let root = create_repository("cli-changed");
+        let first = commit_file(&root, "a.txt", "feat: first");
+        git(&root, &["checkout", "-q", "-b", "topic"]);
+        commit_file(&root, "dir/b.txt", "feat: second");
+        git(&root, &["checkout", "-q", "main"]);
+        commit_file(&root, "c.txt", "feat: third");
+        git(
+            &root,
+            &["merge", "-q", "--no-ff", "-m", "Merge topic", "topic"],
+        );
+        git(&root, &["mv", "a.txt", "dir/a.txt"]);
+        git(&root, &["commit", "-q", "-m", "refactor: move"]);
+        let repository = open(&root);
+
+        // then
+        assert_eq!(repository.changed_paths(&first).unwrap(), vec!["a.txt"]);
+        assert_eq!(
+            repository.changed_paths("HEAD^").unwrap(),
+            vec!["dir/b.txt"]
+        );
+        assert_eq!(
+            repository.changed_paths("HEAD").unwrap(),
+            vec!["a.txt", "dir/a.txt"]
+        );
+    }
+
+    #[test]
+    fn should_list_paths_changed_by_commits_in_range() {
+        // given
+        let root = create_repository("cli-changed-range");
+        let first = commit_file(&root, "a.txt", "feat: first");
+        git(
+            &root,
+            &["commit", "-q", "--allow-empty", "-m", "chore: empty"],
+        );
+        git(&root, &["checkout", "-q", "-b", "topic"]);
+        commit_file(&root, "dir/b c.txt", "feat: second");
+        git(&root, &["checkout", "-q", "main"]);
+        commit_file(&root, "c.txt", "feat: third");
+        git(
+            &root,
+            &["merge", "-q", "--no-ff", "-m", "Merge topic", "topic"],
+        );
+        let repository = open(&root);
+
+        // when
+        let all = repository
+            .changed_paths_in_range(&RevisionRange::default())
+            .unwrap();
+        let range = repository
+            .changed_paths_in_range(&RevisionRange::new(Some(&first), "HEAD"))
+            .unwrap();
+
+        // then
+        assert_eq!(all.len(), 5);
+        for (id, paths) in &all {
+            assert_eq!(paths, &repository.changed_paths(id).unwrap(), "{}", id);
+        }
+        assert_eq!(all[&first], vec!["a.txt"]);
+        assert!(all[&repository.resolve("HEAD~2").unwrap()].is_empty());
+        assert_eq!(range.len(), 4);
+        assert!(!range.contains_key(&first));
+    }
+
+    #[test]
+    fn should_parse_changed_paths_and_batch_output() {
+        // given
+        let log = b"\0aaa\0\nfirst\0second\0\0bbb\0\0ccc\0\nthird\0";
+        let batch = b"n1 blob 5\nhello\nn2 missing\nn3 blob 3\na\nb\n";
+
+        // when
+        let changed_paths = parse_changed_paths(log);
+        let contents = parse_batch(batch).unwrap();
+
+        // then
+        assert_eq!(changed_paths.len(), 3);
+        assert_eq!(changed_paths["aaa"], vec!["first", "second"]);
+        assert!(changed_paths["bbb"].is_empty());
+        assert_eq!(changed_paths["ccc"], vec!["third"]);
+        assert_eq!(contents.len(), 2);
+        assert_eq!(contents["n1"], b"hello");
+        assert_eq!(contents["n3"], b"a\nb");
+        assert!(parse_batch(b"n1 blob 10\nshort\n").is_err());
+    }
+
+    #[test]
+    fn should_discover_repository_root_from_subdirectory() {
+        // given
+        let root = create_repository("cli-discover");
+        commit_file(&root, "nested/a.txt", "feat: first");
+
+        // when
+        let repository = CliRepository::discover(&root.join("nested")).unwrap();
+
+        // then
+        assert_eq!(
+            repository.root().canonicalize().unwrap(),
+            root.canonicalize().unwrap()
+        );
+    }
+}
@@ -0,0 +1,345 @@
+use crate::git::{decode, Commit, GitError, Reference, Repository, RevisionRange, Signature, Tag};
+use chrono::{DateTime, FixedOffset};
+use git2::{ErrorCode, Oid, Sort};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Repository backend using libgit2, which needs no `git` binary.
+pub struct Git2Repository {
+    repository: git2::Repository,
+}
+
+impl Git2Repository {
+    /// Opens the repository containing `path`. Bare repositories are not supported.
+    pub fn discover(path: &Path) -> Result<Self, GitError> {
+        let repository = git2::Repository::discover(path).map_err(to_git_error)?;
+        if repository.workdir().is_none() {
+            return Err(GitError {
+                reason: String::from("Bare repositories are not supported"),
+            });
+        }
+
+        return Ok(Self { repository });
+    }
+
+    fn resolve_oid(&self, revision: &str) -> Result<Oid, GitError> {
+        let commit = self
+            .repository
+            .revparse_single(revision)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(to_git_error)?;
+        return Ok(commit.id());
+    }
+}
+
+impl Repository for Git2Repository {
+    fn root(&self) -> &Path {
+        self.repository.workdir().unwrap_or(self.repository.path())
+    }
+
+    fn current_branch(&self) -> Result<Option<String>, GitError> {
+        // HEAD is read directly, as it may point to a branch without commits yet
+        let head = self
+            .repository
+            .find_reference("HEAD")
+            .map_err(to_git_error)?;
+
+        return Ok(head
+            .symbolic_target()
+            .and_then(|target| target.strip_prefix("refs/heads/"))
+            .map(String::from));
+    }
+
+    fn staged_paths(&self) -> Result<Vec<String>, GitError> {
+        let head_tree = match self.repository.head() {
+            Ok(head) => Some(head.peel_to_tree().map_err(to_git_error)?),
+            Err(_) => None,
+        };
+
+        let diff = self
+            .repository
+            .diff_tree_to_index(head_tree.as_ref(), None, None)
+            .map_err(to_git_error)?;
+
+        return Ok(diff
+            .deltas()
+            .filter_map(|delta| delta.new_file().path().or(delta.old_file().path()))
+            .map(|path| path.to_string_lossy().replace('\\', "/"))
+            .collect());
+    }
+
+    fn resolve(&self, revision: &str) -> Result<String, GitError> {
+        return Ok(self.resolve_oid(revision)?.to_string());
+    }
+
+    fn commits(&self, range: &RevisionRange) -> Result<Vec<Commit>, GitError> {
+        let mut walk = self.repository.revwalk().map_err(to_git_error)?;
+        // children before parents, even when commit dates are equal, just like git log
+        walk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)
+            .map_err(to_git_error)?;
+        walk.push(self.resolve_oid(&range.to)?)
+            .map_err(to_git_error)?;
+        if let Some(from) = &range.from {
+            walk.hide(self.resolve_oid(from)?).map_err(to_git_error)?;
+        }
+
+        let mut commits: Vec<Commit> = vec![];
+        for oid in walk {
+            let oid = oid.map_err(to_git_error)?;
+            let commit = self.repository.find_commit(oid).map_err(to_git_error)?;
+
+            let encoding = commit.message_encoding();
+            commits.push(Commit {
+                id: oid.to_string(),
+                parents: commit.parent_ids().map(|id| id.to_string()).collect(),
+                author: to_signature(&commit.author(), encoding)?,
+                committer: to_signature(&commit.committer(), encoding)?,
+                message: decode(commit.message_raw_bytes(), encoding),
+            });
+        }
+
+        return Ok(commits);
+    }
+
+    fn message(&self, revision: &str) -> Result<String, GitError> {
+        let commit = self
+            .repository
+            .find_commit(self.resolve_oid(revision)?)
+            .map_err(to_git_error)?;
+        return Ok(decode(
+            commit.message_raw_bytes(),
+            commit.message_encoding(),
+        ));
+    }
+
+    fn changed_paths(&self, revision: &str) -> Result<Vec<String>, GitError> {
+        let commit = self
+            .repository
+            .find_commit(self.resolve_oid(revision)?)
+            .map_err(to_git_error)?;
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree().map_err(to_git_error)?),
+            Err(_) => None,
+        };
+        let tree = commit.tree().map_err(to_git_error)?;
+
+        let diff = self
+            .repository
+            .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)
+            .map_err(to_git_error)?;
+
+        return Ok(diff
+            .deltas()
+            .filter_map(|delta| delta.new_file().path().or(delta.old_file().path()))
+            .map(|path| path.to_string_lossy().replace('\\', "/"))
+            .collect());
+    }
+
+    fn tags(&self) -> Result<Vec<Tag>, GitError> {
+        let references = self.references()?;
+
+        return Ok(references
+            .into_iter()
+            .filter_map(|reference| {
+                reference.name.strip_prefix("refs/tags/").map(|name| Tag {
+                    name: String::from(name),
+                    target: reference.target.clone(),
+                })
+            })
+            .collect());
+    }
+
+    fn references(&self) -> Result<Vec<Reference>, GitError> {
+        let mut references: Vec<Reference> = vec![];
+        for reference in self.repository.references().map_err(to_git_error)? {
+            let reference = reference.map_err(to_git_error)?;
+            let name = match reference.name() {
+                Some(name) => String::from(name),
+                None => continue,
+            };
+            if !["refs/heads/", "refs/remotes/", "refs/tags/"]
+                .iter()
+                .any(|prefix| name.starts_with(prefix))
+            {
+                continue;
+            }
+
+            // same as for-each-ref: annotated tags are peeled, anything else is kept as is
+            let target = match reference.peel_to_commit() {
+                Ok(commit) => commit.id(),
+                Err(_) => match reference.resolve().ok().and_then(|r| r.target()) {
+                    Some(target) => target,
+                    None => continue,
+                },
+            };
+            references.push(Reference {
+                name,
+                target: target.to_string(),
+            });
+        }
+        references.sort_by(|a, b| a.name.cmp(&b.name));
+
+        return Ok(references);
+    }
+
+    fn remote_url(&self, name: &str) -> Result<Option<String>, GitError> {
+        return match self.repository.find_remote(name) {
+            Ok(remote) => Ok(remote.url().map(String::from)),
+            Err(e) if e.code() == ErrorCode::NotFound => Ok(None),
+            Err(e) => Err(to_git_error(e)),
+        };
+    }
+
+    fn create_tag(&self, name: &str, target: &str, message: &str) -> Result<(), GitError> {
+        let target = self
+            .repository
+            .revparse_single(target)
+            .map_err(to_git_error)?;
+        let tagger = self.repository.signature().map_err(to_git_error)?;
+        self.repository
+            .tag(name, &target, &tagger, message, false)
+            .map_err(to_git_error)?;
+        return Ok(());
+    }
+
+    fn notes(&self, notes_ref: &str) -> Result<HashMap<String, String>, GitError> {
+        let iterator = match self.repository.notes(Some(notes_ref)) {
+            Ok(iterator) => iterator,
+            Err(e) if e.code() == ErrorCode::NotFound => return Ok(HashMap::new()),
+            Err(e) => return Err(to_git_error(e)),
+        };
+
+        let mut notes: HashMap<String, String> = HashMap::new();
+        for item in iterator {
+            let (_, commit) = item.map_err(to_git_error)?;
+            let note = self
+                .repository
+                .find_note(Some(notes_ref), commit)
+                .map_err(to_git_error)?;
+            notes.insert(commit.to_string(), decode(note.message_bytes(), None));
+        }
+
+        return Ok(notes);
+    }
+
+    fn add_note(&self, notes_ref: &str, revision: &str, note: &str) -> Result<(), GitError> {
+        let commit = self.resolve_oid(revision)?;
+        let signature = self.repository.signature().map_err(to_git_error)?;
+        self.repository
+            .note(&signature, &signature, Some(notes_ref), commit, note, true)
+            .map_err(to_git_error)?;
+        return Ok(());
+    }
+}
+
+fn to_signature(
+    signature: &git2::Signature,
+    encoding: Option<&str>,
+) -> Result<Signature, GitError> {
+    let time = signature.when();
+    let offset = FixedOffset::east_opt(time.offset_minutes() * 60).ok_or_else(|| GitError {
+        reason: format!("Invalid time zone offset: {}", time.offset_minutes()),
+    })?;
+    let timestamp = DateTime::from_timestamp(time.seconds(), 0)
+        .ok_or_else(|| GitError {
+            reason: format!("Invalid timestamp: {}", time.seconds()),
+        })?
+        .with_timezone(&offset);
+
+    return Ok(Signature {
+        name: decode(signature.name_bytes(), encoding),
+        email: decode(signature.email_bytes(), encoding),
+        timestamp,
+    });
+}
+
+fn to_git_error(error: git2::Error) -> GitError {
+    GitError {
+        reason: String::from(error.message()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::git::cli::tests::{commit_file, commit_latin1, create_repository, git};
+    use crate::git::cli::CliRepository;
+    use crate::git::libgit2::Git2Repository;
+    use crate::git::{Repository, RevisionRange};
+    use std::fs;
+
+    #[test]
+    fn should_read_the_same_data_as_cli_backend() {
+        // given
+        let root = create_repository("libgit2-compare");
+        let first = commit_file(&root, "a.txt", "feat: first\n\nbody\n");
+        git(&root, &["tag", "-a", "v1.0.0", "-m", "release"]);
+        commit_file(&root, "b/c.txt", "fix: second");
+        commit_latin1(&root);
+        git(&root, &["checkout", "-q", "-b", "fix/bar"]);
+        fs::write(root.join("d.txt"), "new").unwrap();
+        git(&root, &["add", "d.txt"]);
+
+        // when
+        let cli = CliRepository::discover(&root).unwrap();
+        let native = Git2Repository::discover(&root).unwrap();
+        let range = RevisionRange::new(Some(&first), "HEAD");
+
+        // then
+        assert_eq!(
+            native.commits(&range).unwrap(),
+            cli.commits(&range).unwrap()
+        );
+        assert_eq!(
+            native.commits(&RevisionRange::default()).unwrap(),
+            cli.commits(&RevisionRange::default()).unwrap()
+        );
+        assert_eq!(native.tags().unwrap(), cli.tags().unwrap());
+        assert_eq!(native.references().unwrap(), cli.references().unwrap());
+        assert_eq!(
+            native.current_branch().unwrap(),
+            cli.current_branch().unwrap()
+        );
+        assert_eq!(native.staged_paths().unwrap(), cli.staged_paths().unwrap());
+        for revision in [first.as_str(), "HEAD~1", "HEAD"] {
+            assert_eq!(
+                native.changed_paths(revision).unwrap(),
+                cli.changed_paths(revision).unwrap()
+            );
+        }
+        assert_eq!(native.resolve("v1.0.0").unwrap(), first);
+        native.create_tag("v2.0.0", "HEAD", "notes\n").unwrap();
+        assert_eq!(
+            native.resolve("v2.0.0").unwrap(),
+            cli.resolve("HEAD").unwrap()
+        );
+        assert_eq!(
+            native.message("v1.0.0").unwrap(),
+            cli.message("v1.0.0").unwrap()
+        );
+        assert_eq!(native.remote_url("origin").unwrap(), None);
+        git(
+            &root,
+            &[
+                "remote",
+                "add",
+                "origin",
+                "https://gitlab.com/group/repo.git",
+            ],
+        );
+        assert_eq!(
+            native.remote_url("origin").unwrap(),
+            cli.remote_url("origin").unwrap()
+        );
+        assert!(native.notes("refs/notes/test").unwrap().is_empty());
+        native
+            .add_note("refs/notes/test", "HEAD", "fix: better second\n")
+            .unwrap();
+        cli.add_note("refs/notes/test", &first, "feat: better first")
+            .unwrap();
+        assert_eq!(
+            native.notes("refs/notes/test").unwrap(),
+            cli.notes("refs/notes/test").unwrap()
+        );
+        assert_eq!(native.notes("refs/notes/test").unwrap().len(), 2);
+    }
+}
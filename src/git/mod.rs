@@ -0,0 +1,225 @@
+pub mod cli;
+#[cfg(feature = "libgit2")]
+pub mod libgit2;
+pub mod reader;
+
+use crate::git::reader::{HistoryReader, ReadOptions};
+use chrono::{DateTime, FixedOffset};
+use encoding_rs::{Encoding, UTF_8};
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Formatter;
+use std::path::Path;
+
+#[derive(Debug)]
+pub struct GitError {
+    pub reason: String,
+}
+
+impl fmt::Display for GitError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.reason)
+    }
+}
+
+/// Author or committer of a commit.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Signature {
+    pub name: String,
+    pub email: String,
+    pub timestamp: DateTime<FixedOffset>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Commit {
+    pub id: String,
+    /// Ids of parent commits, empty for a root commit.
+    pub parents: Vec<String>,
+    pub author: Signature,
+    pub committer: Signature,
+    pub message: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Tag {
+    /// Short name, e.g. `v1.2.3`.
+    pub name: String,
+    /// Id of the tagged commit; annotated tags are peeled.
+    pub target: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Reference {
+    /// Full name, e.g. `refs/heads/main`.
+    pub name: String,
+    pub target: String,
+}
+
+/// Commits reachable from `to`, but not from `from`, just like `git log from..to`.
+#[derive(Debug, PartialEq)]
+pub struct RevisionRange {
+    pub from: Option<String>,
+    pub to: String,
+}
+
+impl RevisionRange {
+    pub fn new(from: Option<&str>, to: &str) -> Self {
+        Self {
+            from: from.map(String::from),
+            to: String::from(to),
+        }
+    }
+
+    /// Parses `from..to`, `..to`, `from..` or a single revision.
+    pub fn from(range: &str) -> Self {
+        match range.split_once("..") {
+            Some((from, to)) => Self::new(
+                Some(from).filter(|from| !from.is_empty()),
+                Some(to).filter(|to| !to.is_empty()).unwrap_or("HEAD"),
+            ),
+            None => Self::new(None, range),
+        }
+    }
+}
+
+impl Default for RevisionRange {
+    fn default() -> Self {
+        Self::new(None, "HEAD")
+    }
+}
+
+/// Access to a git repository, independent of the backend.
+pub trait Repository {
+    /// Root of the working tree.
+    fn root(&self) -> &Path;
+
+    /// Short name of the checked out branch, or `None` for a detached HEAD.
+    fn current_branch(&self) -> Result<Option<String>, GitError>;
+
+    /// Paths of files staged for the next commit, relative to the repository root.
+    fn staged_paths(&self) -> Result<Vec<String>, GitError>;
+
+    /// Resolves a revision to the id of a commit.
+    fn resolve(&self, revision: &str) -> Result<String, GitError>;
+
+    /// Commits in the range, newest first.
+    fn commits(&self, range: &RevisionRange) -> Result<Vec<Commit>, GitError>;
+
+    /// Commits in the range read into histories of conventional commits, newest first. Same as
+    /// [Repository::commits], unless read with a [HistoryReader].
+    fn history_commits(&self, range: &RevisionRange) -> Result<Vec<Commit>, GitError> {
+        self.commits(range)
+    }
+
+    /// Raw message of a single commit.
+    fn message(&self, revision: &str) -> Result<String, GitError>;
+
+    /// Paths of files changed by a commit, compared with its first parent, relative to the
+    /// repository root.
+    fn changed_paths(&self, revision: &str) -> Result<Vec<String>, GitError>;
+
+    /// Paths changed by every commit in the range, like [Repository::changed_paths], by commit
+    /// id. Backends override it when reading the whole range at once is cheaper.
+    fn changed_paths_in_range(
+        &self,
+        range: &RevisionRange,
+    ) -> Result<HashMap<String, Vec<String>>, GitError> {
+        let mut changed_paths: HashMap<String, Vec<String>> = HashMap::new();
+        for commit in self.commits(range)? {
+            let paths = self.changed_paths(&commit.id)?;
+            changed_paths.insert(commit.id, paths);
+        }
+
+        return Ok(changed_paths);
+    }
+
+    fn tags(&self) -> Result<Vec<Tag>, GitError>;
+
+    /// Branches, remote tracking branches and tags.
+    fn references(&self) -> Result<Vec<Reference>, GitError>;
+
+    /// URL of a remote, e.g. `origin`, or `None` when there is no such remote.
+    fn remote_url(&self, name: &str) -> Result<Option<String>, GitError>;
+
+    /// Creates an annotated tag pointing at `target`, keeping the message verbatim.
+    fn create_tag(&self, name: &str, target: &str, message: &str) -> Result<(), GitError>;
+
+    /// Notes under a notes reference, e.g. `refs/notes/commits`, by the id of the annotated
+    /// commit. Empty when the reference doesn't exist.
+    fn notes(&self, notes_ref: &str) -> Result<HashMap<String, String>, GitError>;
+
+    /// Attaches a note to the commit under a notes reference, replacing an existing one.
+    fn add_note(&self, notes_ref: &str, revision: &str, note: &str) -> Result<(), GitError>;
+}
+
+/// Decodes text stored in a commit object. Valid UTF-8 is taken as is, as git stores messages
+/// in UTF-8 unless `i18n.commitEncoding` says otherwise. Anything else is decoded with the
+/// encoding declared in the commit, falling back to replacement characters.
+pub fn decode(bytes: &[u8], declared_encoding: Option<&str>) -> String {
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return String::from(text);
+    }
+
+    let encoding = declared_encoding
+        .and_then(|label| Encoding::for_label(label.trim().as_bytes()))
+        .unwrap_or(UTF_8);
+    let (text, _, _) = encoding.decode(bytes);
+
+    return text.to_string();
+}
+
+/// Opens the repository containing `path`. The libgit2 backend is used when compiled in,
+/// with the `git` binary found on `PATH` as a fallback.
+pub fn open(path: &Path) -> Result<Box<dyn Repository>, GitError> {
+    #[cfg(feature = "libgit2")]
+    if let Ok(repository) = libgit2::Git2Repository::discover(path) {
+        return Ok(Box::new(repository));
+    }
+
+    return Ok(Box::new(cli::CliRepository::discover(path)?));
+}
+
+/// Opens the repository containing `path` like [open], with commits read according to
+/// `options`.
+pub fn open_history(path: &Path, options: ReadOptions) -> Result<Box<dyn Repository>, GitError> {
+    return Ok(Box::new(HistoryReader::new(open(path)?, options)));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::git::{decode, RevisionRange};
+
+    #[test]
+    fn should_decode_messages_in_declared_encoding() {
+        assert_eq!(decode(b"feat: caf\xe9", Some("ISO-8859-1")), "feat: café");
+        assert_eq!(
+            decode(b"feat: caf\xc3\xa9", Some("ISO-8859-1")),
+            "feat: café"
+        );
+        assert_eq!(
+            decode(b"fix: \x93\xfa\x96\x7b", Some("Shift_JIS")),
+            "fix: 日本"
+        );
+        assert_eq!(decode(b"feat: caf\xe9", None), "feat: caf\u{fffd}");
+    }
+
+    #[test]
+    fn should_parse_revision_ranges() {
+        assert_eq!(
+            RevisionRange::from("v1.0.0..main"),
+            RevisionRange::new(Some("v1.0.0"), "main")
+        );
+        assert_eq!(
+            RevisionRange::from("v1.0.0.."),
+            RevisionRange::new(Some("v1.0.0"), "HEAD")
+        );
+        assert_eq!(
+            RevisionRange::from("..main"),
+            RevisionRange::new(None, "main")
+        );
+        assert_eq!(
+            RevisionRange::from("main"),
+            RevisionRange::new(None, "main")
+        );
+    }
+}
@@ -0,0 +1,375 @@
+use crate::core::conventional_commit::ConventionalCommit;
+use crate::core::glob::Glob;
+use crate::git::{Commit, GitError, Reference, Repository, RevisionRange, Tag};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::str::FromStr;
+
+/// How commits are read into histories of releases, see `[history]` in the configuration.
+#[derive(Debug, Default)]
+pub struct ReadOptions {
+    /// Read conventional entries listed in the body of a squash commit, e.g. `* feat: ...`,
+    /// as commits of their own.
+    pub squashed_entries: bool,
+    /// Notes reference with replacement messages of commits, e.g. `refs/notes/conventional`.
+    pub notes_ref: Option<String>,
+    pub ignore: IgnoreRules,
+}
+
+/// Commits left out of histories, e.g. those made before adopting conventional commits.
+#[derive(Debug, Default)]
+pub struct IgnoreRules {
+    /// Revision that is ignored along with all of its ancestors.
+    pub before: Option<String>,
+    /// Ids of ignored commits, possibly abbreviated.
+    pub commits: Vec<String>,
+    /// Patterns of ignored messages, matched after message overrides and without trailing
+    /// whitespace.
+    pub messages: Vec<Regex>,
+    /// Commits changing only paths matching any of these are ignored.
+    pub paths: Vec<Glob>,
+}
+
+/// Repository with histories and messages read according to [ReadOptions]. Everything else
+/// is passed through to the wrapped repository, so that e.g. tags on ignored commits are
+/// still found.
+pub struct HistoryReader {
+    repository: Box<dyn Repository>,
+    options: ReadOptions,
+}
+
+impl HistoryReader {
+    pub fn new(repository: Box<dyn Repository>, options: ReadOptions) -> Self {
+        Self {
+            repository,
+            options,
+        }
+    }
+
+    /// Replacement messages by commit id, from notes.
+    fn overrides(&self) -> Result<HashMap<String, String>, GitError> {
+        return match &self.options.notes_ref {
+            Some(notes_ref) => self.repository.notes(notes_ref),
+            None => Ok(HashMap::new()),
+        };
+    }
+
+    /// Whether the commit is ignored, with paths changed by commits of the range read up front.
+    fn is_ignored(&self, commit: &Commit, changed_paths: &HashMap<String, Vec<String>>) -> bool {
+        let ignore = &self.options.ignore;
+        if ignore
+            .commits
+            .iter()
+            .any(|id| commit.id.starts_with(&id.to_lowercase()))
+            || ignore
+                .messages
+                .iter()
+                .any(|pattern| pattern.is_match(commit.message.trim_end()))
+        {
+            return true;
+        }
+
+        return match changed_paths.get(&commit.id) {
+            Some(paths) => {
+                !paths.is_empty()
+                    && paths
+                        .iter()
+                        .all(|path| ignore.paths.iter().any(|glob| glob.matches(path)))
+            }
+            None => false,
+        };
+    }
+
+    /// The commit, or conventional entries of a squash commit as commits of their own.
+    fn read(&self, commit: Commit) -> Vec<Commit> {
+        if !self.options.squashed_entries {
+            return vec![commit];
+        }
+
+        let messages = find_squashed_messages(&commit.message);
+        if messages.is_empty() {
+            return vec![commit];
+        }
+
+        // squash bodies list the original commits oldest first
+        return messages
+            .into_iter()
+            .rev()
+            .map(|message| Commit {
+                id: commit.id.clone(),
+                parents: commit.parents.clone(),
+                author: commit.author.clone(),
+                committer: commit.committer.clone(),
+                message,
+            })
+            .collect();
+    }
+}
+
+impl Repository for HistoryReader {
+    fn root(&self) -> &Path {
+        self.repository.root()
+    }
+
+    fn current_branch(&self) -> Result<Option<String>, GitError> {
+        self.repository.current_branch()
+    }
+
+    fn staged_paths(&self) -> Result<Vec<String>, GitError> {
+        self.repository.staged_paths()
+    }
+
+    fn resolve(&self, revision: &str) -> Result<String, GitError> {
+        self.repository.resolve(revision)
+    }
+
+    fn commits(&self, range: &RevisionRange) -> Result<Vec<Commit>, GitError> {
+        self.repository.commits(range)
+    }
+
+    fn history_commits(&self, range: &RevisionRange) -> Result<Vec<Commit>, GitError> {
+        let overrides = self.overrides()?;
+        let ignore = &self.options.ignore;
+        let ignored: HashSet<String> = match &ignore.before {
+            Some(before) => self
+                .repository
+                .commits(&RevisionRange::new(None, before))?
+                .into_iter()
+                .map(|commit| commit.id)
+                .collect(),
+            None => HashSet::new(),
+        };
+        let changed_paths = match ignore.paths.is_empty() {
+            true => HashMap::new(),
+            false => self.repository.changed_paths_in_range(range)?,
+        };
+
+        let mut commits: Vec<Commit> = vec![];
+        for mut commit in self.repository.commits(range)? {
+            if let Some(message) = overrides.get(&commit.id) {
+                commit.message = message.clone();
+            }
+            if ignored.contains(&commit.id) || self.is_ignored(&commit, &changed_paths) {
+                continue;
+            }
+            commits.extend(self.read(commit));
+        }
+
+        return Ok(commits);
+    }
+
+    fn message(&self, revision: &str) -> Result<String, GitError> {
+        let overrides = self.overrides()?;
+        if !overrides.is_empty() {
+            if let Some(message) = overrides.get(&self.repository.resolve(revision)?) {
+                return Ok(message.clone());
+            }
+        }
+
+        return self.repository.message(revision);
+    }
+
+    fn changed_paths(&self, revision: &str) -> Result<Vec<String>, GitError> {
+        self.repository.changed_paths(revision)
+    }
+
+    fn changed_paths_in_range(
+        &self,
+        range: &RevisionRange,
+    ) -> Result<HashMap<String, Vec<String>>, GitError> {
+        self.repository.changed_paths_in_range(range)
+    }
+
+    fn tags(&self) -> Result<Vec<Tag>, GitError> {
+        self.repository.tags()
+    }
+
+    fn references(&self) -> Result<Vec<Reference>, GitError> {
+        self.repository.references()
+    }
+
+    fn remote_url(&self, name: &str) -> Result<Option<String>, GitError> {
+        self.repository.remote_url(name)
+    }
+
+    fn create_tag(&self, name: &str, target: &str, message: &str) -> Result<(), GitError> {
+        self.repository.create_tag(name, target, message)
+    }
+
+    fn notes(&self, notes_ref: &str) -> Result<HashMap<String, String>, GitError> {
+        self.repository.notes(notes_ref)
+    }
+
+    fn add_note(&self, notes_ref: &str, revision: &str, note: &str) -> Result<(), GitError> {
+        self.repository.add_note(notes_ref, revision, note)
+    }
+}
+
+/// Messages of conventional commits listed in the body of a squash commit, in order. Every
+/// `* ` or `- ` line starts an original commit, followed by its lines up to the next one, and
+/// those with a conventional header are kept. Lines before the first of them are left out.
+pub fn find_squashed_messages(message: &str) -> Vec<String> {
+    let mut messages: Vec<Vec<&str>> = vec![];
+    let mut current: Option<Vec<&str>> = None;
+    for line in message.lines().skip(1) {
+        match line.strip_prefix("* ").or_else(|| line.strip_prefix("- ")) {
+            Some(header) => {
+                messages.extend(current.take());
+                if ConventionalCommit::from_str(header).is_ok() {
+                    current = Some(vec![header]);
+                }
+            }
+            None => {
+                if let Some(lines) = current.as_mut() {
+                    lines.push(line.strip_prefix("  ").unwrap_or(line));
+                }
+            }
+        }
+    }
+    messages.extend(current);
+
+    return messages
+        .into_iter()
+        .map(|lines| String::from(lines.join("\n").trim_end()))
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::glob::Glob;
+    use crate::core::history::History;
+    use crate::git::cli::tests::{commit_file, create_repository, git};
+    use crate::git::cli::CliRepository;
+    use crate::git::reader::{find_squashed_messages, HistoryReader, IgnoreRules, ReadOptions};
+    use crate::git::{Repository, RevisionRange};
+    use regex::Regex;
+
+    #[test]
+    fn should_find_conventional_messages_in_squash_body() {
+        // given
+        let message = "Add search (#42)\n\n\
+            * feat(search): add index\n\n\
+            BREAKING CHANGE: the index is required\n\n\
+            * WIP\n\
+            - fix(search): handle empty query\n  \n  Refs: #41\n\n\
+            Co-authored-by: John Roe <john@example.com>\n";
+
+        // when
+        let messages = find_squashed_messages(message);
+
+        // then
+        assert_eq!(
+            messages,
+            vec![
+                "feat(search): add index\n\nBREAKING CHANGE: the index is required",
+                "fix(search): handle empty query\n\nRefs: #41\n\n\
+                Co-authored-by: John Roe <john@example.com>",
+            ]
+        );
+        assert!(find_squashed_messages("feat: add search\n\n* not conventional").is_empty());
+        assert!(find_squashed_messages("* feat: only a header").is_empty());
+    }
+
+    #[test]
+    fn should_read_squashed_entries_when_enabled() {
+        // given
+        let root = create_repository("reader-squash");
+        commit_file(&root, "a.txt", "fix: first");
+        let squash = commit_file(
+            &root,
+            "a.txt",
+            "Add search (#42)\n\n* feat(search): add index\n\n* fix(search): handle empty query",
+        );
+        let read = |squashed_entries: bool| {
+            let repository = CliRepository::discover(&root).unwrap();
+            let options = ReadOptions {
+                squashed_entries,
+                ..ReadOptions::default()
+            };
+            let reader = HistoryReader::new(Box::new(repository), options);
+            return History::from(&reader.history_commits(&RevisionRange::default()).unwrap());
+        };
+
+        // when
+        let enabled = read(true);
+        let disabled = read(false);
+
+        // then
+        let descriptions: Vec<&str> = enabled
+            .entries
+            .iter()
+            .map(|entry| entry.commit.description.as_str())
+            .collect();
+        assert_eq!(
+            descriptions,
+            vec!["handle empty query", "add index", "first"]
+        );
+        assert_eq!(enabled.entries[0].id, squash);
+        assert_eq!(disabled.entries.len(), 1);
+        assert_eq!(disabled.skipped, vec![squash]);
+    }
+
+    #[test]
+    fn should_read_messages_overridden_with_notes() {
+        // given
+        let root = create_repository("reader-notes");
+        let first = commit_file(&root, "a.txt", "added stuff");
+        commit_file(&root, "a.txt", "fix: second");
+        let repository = CliRepository::discover(&root).unwrap();
+        repository
+            .add_note("refs/notes/conventional", &first, "feat: add stuff\n")
+            .unwrap();
+        let options = ReadOptions {
+            notes_ref: Some(String::from("refs/notes/conventional")),
+            ..ReadOptions::default()
+        };
+        let reader = HistoryReader::new(Box::new(repository), options);
+
+        // when
+        let commits = reader.history_commits(&RevisionRange::default()).unwrap();
+
+        // then
+        assert_eq!(commits[0].message, "fix: second\n");
+        assert_eq!(commits[1].message, "feat: add stuff\n");
+        assert_eq!(reader.message("HEAD~1").unwrap(), "feat: add stuff\n");
+        assert_eq!(reader.message("HEAD").unwrap(), "fix: second\n");
+    }
+
+    #[test]
+    fn should_skip_ignored_commits_in_history_only() {
+        // given
+        let root = create_repository("reader-ignore");
+        commit_file(&root, "a.txt", "Initial import");
+        git(&root, &["tag", "legacy"]);
+        commit_file(&root, "a.txt", "fixed things");
+        let hash = commit_file(&root, "a.txt", "feat: broken feature");
+        commit_file(&root, "a.txt", "Merge branch 'main'");
+        commit_file(&root, "docs/guide.md", "docs: describe setup");
+        let fix = commit_file(&root, "a.txt", "fix: crash on start");
+        let options = ReadOptions {
+            ignore: IgnoreRules {
+                before: Some(String::from("legacy~0")),
+                commits: vec![hash[..7].to_uppercase()],
+                messages: vec![Regex::new("^Merge ").unwrap()],
+                paths: vec![Glob::new("docs/**").unwrap()],
+            },
+            ..ReadOptions::default()
+        };
+        let repository = CliRepository::discover(&root).unwrap();
+        let reader = HistoryReader::new(Box::new(repository), options);
+
+        // when
+        let history = reader.history_commits(&RevisionRange::default()).unwrap();
+        let all = reader.commits(&RevisionRange::default()).unwrap();
+
+        // then
+        let messages: Vec<&str> = history
+            .iter()
+            .map(|commit| commit.message.as_str())
+            .collect();
+        assert_eq!(messages, vec!["fix: crash on start\n", "fixed things\n"]);
+        assert_eq!(history[0].id, fix);
+        assert_eq!(all.len(), 6);
+    }
+}
@@ -0,0 +1,172 @@
+use crate::config::{BranchConfig, ConfigError, CONFIG_FILE_NAME};
+use regex::Regex;
+
+/// Information extracted from a branch name with one of configured patterns.
+#[derive(Debug, Default, PartialEq)]
+pub struct BranchInfo {
+    pub commit_type: Option<String>,
+    pub scope: Option<String>,
+    pub ticket: Option<String>,
+}
+
+impl BranchInfo {
+    /// Matches the branch name against patterns in order; the first one that matches wins.
+    pub fn from(branch: &str, config: &BranchConfig) -> Result<Self, ConfigError> {
+        for pattern in &config.patterns {
+            let regex = Regex::new(pattern).map_err(|e| ConfigError {
+                path: String::from(CONFIG_FILE_NAME),
+                reason: format!("Invalid branch pattern {}: {}", pattern, e),
+            })?;
+
+            if let Some(captures) = regex.captures(branch) {
+                let group = |name: &str| {
+                    captures
+                        .name(name)
+                        .map(|m| String::from(m.as_str()))
+                        .filter(|value| !value.is_empty())
+                };
+
+                return Ok(Self {
+                    commit_type: group("type"),
+                    scope: group("scope"),
+                    ticket: group("ticket"),
+                });
+            }
+        }
+
+        return Ok(Self::default());
+    }
+
+    /// Header to prefill, e.g. `feat(payments-api): `. Without a type there is no header, as
+    /// a scope alone wouldn't pass the lint.
+    pub fn header(&self) -> Option<String> {
+        let commit_type = self.commit_type.as_ref()?;
+        return match &self.scope {
+            Some(scope) => Some(format!("{}({}): ", commit_type, scope)),
+            None => Some(format!("{}: ", commit_type)),
+        };
+    }
+
+    pub fn trailer(&self, key: &str) -> Option<String> {
+        self.ticket
+            .as_ref()
+            .map(|ticket| format!("{}: {}", key, ticket))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::BranchConfig;
+    use crate::hooks::branch::BranchInfo;
+
+    #[test]
+    fn should_extract_type_and_scope_from_branch_name() {
+        // given
+        let config = BranchConfig::default();
+
+        // when
+        let actual = BranchInfo::from("feat/payments-api", &config).unwrap();
+
+        // then
+        assert_eq!(
+            actual,
+            BranchInfo {
+                commit_type: Some(String::from("feat")),
+                scope: Some(String::from("payments-api")),
+                ticket: None,
+            }
+        );
+        assert_eq!(actual.header(), Some(String::from("feat(payments-api): ")));
+        assert_eq!(actual.trailer("Refs"), None);
+    }
+
+    #[test]
+    fn should_extract_type_and_ticket_from_branch_name() {
+        // given
+        let config = BranchConfig::default();
+
+        // when
+        let actual = BranchInfo::from("fix/JIRA-123-null-pointer", &config).unwrap();
+
+        // then
+        assert_eq!(
+            actual,
+            BranchInfo {
+                commit_type: Some(String::from("fix")),
+                scope: None,
+                ticket: Some(String::from("JIRA-123")),
+            }
+        );
+        assert_eq!(actual.header(), Some(String::from("fix: ")));
+        assert_eq!(actual.trailer("Refs"), Some(String::from("Refs: JIRA-123")));
+    }
+
+    #[test]
+    fn should_extract_ticket_from_branch_name_without_type() {
+        // given
+        let config = BranchConfig::default();
+
+        // when
+        let actual = BranchInfo::from("ABC-42-some-work", &config).unwrap();
+
+        // then
+        assert_eq!(actual.header(), None);
+        assert_eq!(actual.ticket, Some(String::from("ABC-42")));
+    }
+
+    #[test]
+    fn should_not_prefill_scope_without_type() {
+        // given
+        let info = BranchInfo {
+            scope: Some(String::from("api")),
+            ..BranchInfo::default()
+        };
+
+        // then
+        assert_eq!(info.header(), None);
+    }
+
+    #[test]
+    fn should_return_empty_info_when_no_pattern_matches() {
+        // given
+        let config = BranchConfig::default();
+
+        // when
+        let actual = BranchInfo::from("main", &config).unwrap();
+
+        // then
+        assert_eq!(actual, BranchInfo::default());
+    }
+
+    #[test]
+    fn should_use_custom_patterns() {
+        // given
+        let config = BranchConfig {
+            patterns: vec![String::from(
+                r"^users/[^/]+/(?<scope>[^/]+)/(?<type>[^/]+)$",
+            )],
+            ..BranchConfig::default()
+        };
+
+        // when
+        let actual = BranchInfo::from("users/jdoe/cli/chore", &config).unwrap();
+
+        // then
+        assert_eq!(actual.header(), Some(String::from("chore(cli): ")));
+    }
+
+    #[test]
+    fn should_return_error_for_invalid_pattern() {
+        // given
+        let config = BranchConfig {
+            patterns: vec![String::from("(?<type>")],
+            ..BranchConfig::default()
+        };
+
+        // when
+        let actual = BranchInfo::from("feat/foo", &config);
+
+        // then
+        assert!(actual.is_err(), "An Error should have been returned");
+    }
+}
@@ -1,14 +1,67 @@
 #[derive(Debug)]
 pub struct CommitMsgArgs {
     pub filename: String,
+    /// Rewrite the message file to fix mechanically fixable problems.
+    pub fix: bool,
 }
 
-pub fn process_args(args: &Vec<String>) -> CommitMsgArgs {
-    if args.len() < 2 {
-        panic!("Missing commit-msg arguments");
-    }
+pub fn process_args(args: &[String]) -> CommitMsgArgs {
+    let fix = args.iter().skip(1).any(|arg| arg == "--fix");
+    let filename = args.iter().skip(1).find(|arg| *arg != "--fix");
 
-    return CommitMsgArgs {
-        filename: String::from(args.get(1).unwrap()),
+    return match filename {
+        Some(filename) => CommitMsgArgs {
+            filename: String::from(filename),
+            fix,
+        },
+        None => panic!("Missing commit-msg arguments"),
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::hooks::commit_msg::process_args;
+
+    #[test]
+    fn should_process_args_with_only_path_to_commit_file() {
+        // given
+        let args = vec![
+            String::from("DontCare"),
+            String::from(".git/COMMIT_EDITMSG"),
+        ];
+
+        // when
+        let actual = process_args(&args);
+
+        // then
+        assert_eq!(actual.filename, ".git/COMMIT_EDITMSG");
+        assert!(!actual.fix);
+    }
+
+    #[test]
+    fn should_process_fix_flag() {
+        // given
+        let args = vec![
+            String::from("DontCare"),
+            String::from("--fix"),
+            String::from(".git/COMMIT_EDITMSG"),
+        ];
+
+        // when
+        let actual = process_args(&args);
+
+        // then
+        assert_eq!(actual.filename, ".git/COMMIT_EDITMSG");
+        assert!(actual.fix);
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_panic_when_path_is_missing() {
+        // given
+        let args = vec![String::from("DontCare"), String::from("--fix")];
+
+        // when
+        process_args(&args);
+    }
+}
@@ -1,2 +1,3 @@
+pub mod branch;
 pub mod commit_msg;
 pub mod prepare_msg;
@@ -1,60 +1,642 @@
+use crate::config::{Config, ConfigError, TypeDefinition};
+use crate::hooks::branch::BranchInfo;
+use std::collections::HashMap;
+use std::fs;
+
+const DEFAULT_TEMPLATE: &str = r#"#<type>[optional scope]: <description>
+
+# [optional body]
+
+# [optional footer(s)]
+
+# Branch: {{branch}}
+#
+# type can be one of:
+#   - {{types}}
+#
+# scope suggestions:
+#   - {{scope_suggestions}}
+#
+# Note: if you add ! after type/scope, or write BREAKING CHANGE
+# in the footer, then it is represents a commit that introduces
+# some kind of breaking API change (correlates with MAJOR in the
+# semver).
+"#;
+
+/// Source of the commit message, as passed by git in the second hook argument.
+#[derive(Debug, PartialEq)]
+pub enum CommitSource {
+    /// `-m` or `-F` option was given
+    Message,
+    /// `-t` option was given or `commit.template` is set
+    Template,
+    /// commit is a merge or `.git/MERGE_MSG` exists
+    Merge,
+    /// `.git/SQUASH_MSG` exists
+    Squash,
+    /// `-c`, `-C` or `--amend` option was given
+    Commit,
+    Other(String),
+}
+
+impl CommitSource {
+    pub fn from(source: &str) -> Self {
+        match source {
+            "message" => CommitSource::Message,
+            "template" => CommitSource::Template,
+            "merge" => CommitSource::Merge,
+            "squash" => CommitSource::Squash,
+            "commit" => CommitSource::Commit,
+            _ => CommitSource::Other(String::from(source)),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct PrepareMessageArgs {
     pub filename: String,
-    pub commit_type: Option<String>, // TODO replace with an Enum in future
+    pub source: Option<CommitSource>,
     pub id: Option<String>,
 }
 
-pub fn can_use_template(args: &PrepareMessageArgs) -> bool {
-    if args.id.is_none() {
-        // we naively expect that if we have a commit ID,
-        // then we are amending and already have commit message
-        return true;
+#[derive(Debug, PartialEq)]
+pub enum TemplateAction {
+    /// Write our own template in front of whatever git has prepared.
+    Write,
+    /// Keep the user's template, but fill in the placeholders.
+    Expand,
+    /// Leave the message file untouched.
+    Skip,
+}
+
+#[derive(Debug, Default)]
+pub struct TemplateContext {
+    pub branch: Option<String>,
+    pub branch_info: BranchInfo,
+    /// Scopes inferred from staged files.
+    pub inferred_scopes: Vec<String>,
+}
+
+impl TemplateContext {
+    pub fn from(
+        branch: Option<String>,
+        inferred_scopes: Vec<String>,
+        config: &Config,
+    ) -> Result<Self, ConfigError> {
+        let mut branch_info = match &branch {
+            Some(branch) => BranchInfo::from(branch, &config.branches)?,
+            None => BranchInfo::default(),
+        };
+
+        // scope from the branch name is more explicit than the one guessed from staged files
+        if branch_info.scope.is_none() && inferred_scopes.len() == 1 {
+            branch_info.scope = inferred_scopes.first().cloned();
+        }
+
+        return Ok(Self {
+            branch,
+            branch_info,
+            inferred_scopes,
+        });
     }
+}
 
-    return false;
+pub fn get_template_action(args: &PrepareMessageArgs) -> TemplateAction {
+    match &args.source {
+        None => TemplateAction::Write,
+        Some(CommitSource::Template) => TemplateAction::Expand,
+        // messages coming from -m, merges, squashes and amends are never clobbered
+        Some(_) => TemplateAction::Skip,
+    }
 }
 
-pub fn process_args(args: &Vec<String>) -> PrepareMessageArgs {
+pub fn process_args(args: &[String]) -> PrepareMessageArgs {
     if args.len() < 2 {
         panic!("Missing prepare-commit-msg arguments");
     }
 
     return PrepareMessageArgs {
         filename: String::from(args.get(1).unwrap()),
-        commit_type: match args.get(2) {
-            Some(commit_type) => Some(String::from(commit_type)),
-            None => None,
-        },
-        id: match args.get(3) {
-            Some(id) => Some(String::from(id)),
-            None => None,
-        },
+        source: args.get(2).map(|source| CommitSource::from(source)),
+        id: args.get(3).map(String::from),
     };
 }
 
-pub const fn get_template() -> &'static str {
-    return r#"#<type>[optional scope]: <description>
+pub fn get_template(config: &Config) -> Result<String, ConfigError> {
+    if let Some(template) = &config.prepare_msg.template {
+        return Ok(template.clone());
+    }
 
-# [optional body]
+    if let Some(template_file) = &config.prepare_msg.template_file {
+        let path = config.resolve_path(template_file);
+        return fs::read_to_string(&path).map_err(|e| ConfigError {
+            path: path.display().to_string(),
+            reason: format!("Couldn't read commit message template: {}", e),
+        });
+    }
 
-# [optional footer(s)]
+    return Ok(String::from(DEFAULT_TEMPLATE));
+}
 
-# type can be one of:
-#   - fix (correlates with PATCH in semver)
-#   - feat (cerrlates with MINOR in semver)
-#   - build
-#   - chore
-#   - ci
-#   - docs
-#   - style
-#   - refactor
-#   - perf
-#   - test
-#     and many, many others
-#
-# Note: if you add ! after type/scope, or write BREAKING CHANGE
-# in the footer, then it is represents a commit that introduces
-# some kind of breaking API change (correlates with MAJOR in the
-# semver). "#;
+/// Builds the final content of the commit message file, or `None` when it should stay as it is.
+pub fn prepare_message(
+    action: &TemplateAction,
+    current_content: &str,
+    template: &str,
+    config: &Config,
+    context: &TemplateContext,
+) -> Option<String> {
+    let values = get_template_values(config, context);
+
+    match action {
+        TemplateAction::Skip => None,
+        TemplateAction::Expand => Some(render_template(current_content, &values)),
+        TemplateAction::Write => {
+            if has_message(current_content) {
+                return None;
+            }
+
+            let mut message = get_prefill(config, context);
+            message.push_str(&render_template(template, &values));
+            // the trailer goes below the body the user is about to write, so that it stays the
+            // last paragraph, but above the comments and the scissors line prepared by git
+            if let Some(trailer) = get_trailer(config, context) {
+                if !message.ends_with('\n') {
+                    message.push('\n');
+                }
+                message.push('\n');
+                message.push_str(&trailer);
+                message.push('\n');
+            }
+            if !current_content.is_empty() {
+                if !message.ends_with('\n') {
+                    message.push('\n');
+                }
+                message.push_str(current_content);
+            }
+
+            Some(message)
+        }
+    }
+}
+
+pub fn get_template_values(
+    config: &Config,
+    context: &TemplateContext,
+) -> HashMap<&'static str, String> {
+    let mut values: HashMap<&'static str, String> = HashMap::new();
+
+    values.insert(
+        "branch",
+        context
+            .branch
+            .clone()
+            .unwrap_or_else(|| String::from("HEAD")),
+    );
+    let branch_info = &context.branch_info;
+    values.insert("type", branch_info.commit_type.clone().unwrap_or_default());
+    values.insert("scope", branch_info.scope.clone().unwrap_or_default());
+    values.insert("ticket", branch_info.ticket.clone().unwrap_or_default());
+    values.insert("types", describe_types(&config.types));
+    values.insert(
+        "scope_suggestions",
+        describe_scopes(&context.inferred_scopes, &config.scopes),
+    );
+    values.insert("inferred_scopes", describe_list(&context.inferred_scopes));
+
+    return values;
+}
+
+/// Replaces `{{name}}` placeholders with their values. When a value spans over multiple lines,
+/// every following line is prefixed with whatever preceded the placeholder, so that a list
+/// rendered inside a comment stays commented out.
+pub fn render_template(template: &str, values: &HashMap<&str, String>) -> String {
+    let mut rendered_lines: Vec<String> = vec![];
+
+    for line in template.split('\n') {
+        let mut rendered = String::new();
+        let mut rest = line;
+
+        while let Some(start) = rest.find("{{") {
+            let Some(length) = rest[start..].find("}}") else {
+                break;
+            };
+
+            let name = rest[start + 2..start + length].trim();
+            rendered.push_str(&rest[..start]);
+            match values.get(name) {
+                Some(value) => {
+                    let prefix = String::from(&line[..line.len() - rest.len() + start]);
+                    let value = value.replace('\n', &format!("\n{}", prefix));
+                    rendered.push_str(&value);
+                }
+                None => rendered.push_str(&rest[start..start + length + 2]),
+            }
+
+            rest = &rest[start + length + 2..];
+        }
+
+        rendered.push_str(rest);
+        rendered_lines.push(rendered);
+    }
+
+    return rendered_lines.join("\n");
+}
+
+/// Header line derived from the branch name, put in front of the template. The line is left
+/// empty for the header to be written there when only a ticket trailer is prefilled.
+fn get_prefill(config: &Config, context: &TemplateContext) -> String {
+    let branch_info = &context.branch_info;
+    // a type that is not in the registry is more likely a part of an unrelated naming scheme
+    let header = branch_info
+        .header()
+        .filter(|_| is_known_type(config, branch_info.commit_type.as_deref()));
+
+    if header.is_none() && get_trailer(config, context).is_none() {
+        return String::new();
+    }
+
+    let mut prefill = header.unwrap_or_default();
+    prefill.push('\n');
+    return prefill;
+}
+
+fn get_trailer(config: &Config, context: &TemplateContext) -> Option<String> {
+    context.branch_info.trailer(&config.branches.ticket_trailer)
+}
+
+fn is_known_type(config: &Config, commit_type: Option<&str>) -> bool {
+    match commit_type {
+        Some(commit_type) => config.find_type(commit_type).is_some(),
+        None => false,
+    }
+}
+
+fn describe_types(types: &[TypeDefinition]) -> String {
+    let descriptions: Vec<String> = types
+        .iter()
+        .map(|t| match &t.description {
+            Some(description) => format!("{} ({})", t.name, description),
+            None => t.name.clone(),
+        })
+        .collect();
+
+    return describe_list(&descriptions);
+}
+
+fn describe_scopes(inferred: &[String], configured: &[String]) -> String {
+    let mut descriptions: Vec<String> = inferred
+        .iter()
+        .map(|scope| format!("{} (inferred from staged files)", scope))
+        .collect();
+    descriptions.extend(
+        configured
+            .iter()
+            .filter(|scope| !inferred.contains(scope))
+            .cloned(),
+    );
+
+    return describe_list(&descriptions);
+}
+
+fn describe_list(items: &[String]) -> String {
+    if items.is_empty() {
+        return String::from("none");
+    }
+
+    return items.join("\n");
+}
+
+/// Whether the message file already contains something else than comments and whitespace.
+fn has_message(content: &str) -> bool {
+    content
+        .lines()
+        .map(|line| line.trim())
+        .any(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{Config, TypeDefinition};
+    use crate::core::conventional_commit::ConventionalCommit;
+    use crate::hooks::branch::BranchInfo;
+    use crate::hooks::prepare_msg::{
+        prepare_message, render_template, TemplateAction, TemplateContext, DEFAULT_TEMPLATE,
+    };
+    use std::collections::HashMap;
+    use std::str::FromStr;
+
+    #[test]
+    fn should_replace_placeholders_with_values() {
+        // given
+        let mut values = HashMap::new();
+        values.insert("branch", String::from("feat/foo"));
+
+        // when
+        let actual = render_template("# On {{branch}}, {{ branch }}!", &values);
+
+        // then
+        assert_eq!(actual, "# On feat/foo, feat/foo!");
+    }
+
+    #[test]
+    fn should_prefix_every_line_of_multiline_value_with_text_preceding_placeholder() {
+        // given
+        let mut values = HashMap::new();
+        values.insert("types", String::from("feat\nfix"));
+
+        // when
+        let actual = render_template("# types:\n#   - {{types}}\n#", &values);
+
+        // then
+        assert_eq!(actual, "# types:\n#   - feat\n#   - fix\n#");
+    }
+
+    #[test]
+    fn should_leave_unknown_placeholders_untouched() {
+        // given
+        let values = HashMap::new();
+
+        // when
+        let actual = render_template("{{unknown}} and {{", &values);
+
+        // then
+        assert_eq!(actual, "{{unknown}} and {{");
+    }
+
+    #[test]
+    fn should_render_types_and_scopes_from_config() {
+        // given
+        let config = Config {
+            types: vec![
+                TypeDefinition::new("feat", Some("new feature")),
+                TypeDefinition::new("wip", None),
+            ],
+            scopes: vec![String::from("api")],
+            ..Config::default()
+        };
+        let context = TemplateContext {
+            branch: Some(String::from("main")),
+            ..TemplateContext::default()
+        };
+
+        // when
+        let actual = prepare_message(
+            &TemplateAction::Write,
+            "",
+            "# {{branch}}\n# - {{types}}\n# - {{scope_suggestions}}",
+            &config,
+            &context,
+        );
+
+        // then
+        assert_eq!(
+            actual,
+            Some(String::from(
+                "# main\n# - feat (new feature)\n# - wip\n# - api"
+            ))
+        );
+    }
+
+    #[test]
+    fn should_put_template_in_front_of_comments_prepared_by_git() {
+        // given
+        let config = Config::default();
+        let current = "# Please enter the commit message for your changes.\n";
+
+        // when
+        let actual = prepare_message(
+            &TemplateAction::Write,
+            current,
+            "#<type>: <description>",
+            &config,
+            &TemplateContext::default(),
+        );
+
+        // then
+        assert_eq!(
+            actual,
+            Some(String::from(
+                "#<type>: <description>\n# Please enter the commit message for your changes.\n"
+            ))
+        );
+    }
+
+    #[test]
+    fn should_not_clobber_existing_message() {
+        // given
+        let config = Config::default();
+        let current = "fix: something\n# Please enter the commit message for your changes.\n";
+
+        // when
+        let actual = prepare_message(
+            &TemplateAction::Write,
+            current,
+            "#<type>: <description>",
+            &config,
+            &TemplateContext::default(),
+        );
+
+        // then
+        assert_eq!(actual, None);
+    }
+
+    #[test]
+    fn should_expand_placeholders_in_user_template() {
+        // given
+        let config = Config::default();
+        let context =
+            TemplateContext::from(Some(String::from("feat/foo")), vec![], &config).unwrap();
+
+        // when
+        let actual = prepare_message(
+            &TemplateAction::Expand,
+            "feat: \n\n# working on {{branch}}\n",
+            "not used",
+            &config,
+            &context,
+        );
+
+        // then
+        assert_eq!(
+            actual,
+            Some(String::from("feat: \n\n# working on feat/foo\n"))
+        );
+    }
+
+    #[test]
+    fn should_prefill_header_and_ticket_trailer_from_branch_name() {
+        // given
+        let config = Config::default();
+        let context = TemplateContext {
+            branch: Some(String::from("feat/JIRA-123-payments")),
+            branch_info: BranchInfo {
+                commit_type: Some(String::from("feat")),
+                scope: Some(String::from("payments-api")),
+                ticket: Some(String::from("JIRA-123")),
+            },
+            ..TemplateContext::default()
+        };
+
+        // when
+        let actual = prepare_message(
+            &TemplateAction::Write,
+            "",
+            "#<type>: <description>",
+            &config,
+            &context,
+        );
+
+        // then
+        assert_eq!(
+            actual,
+            Some(String::from(
+                "feat(payments-api): \n#<type>: <description>\n\nRefs: JIRA-123\n"
+            ))
+        );
+    }
+
+    #[test]
+    fn should_keep_ticket_trailer_in_footer_below_body() {
+        // given
+        let config = Config::default();
+        let context =
+            TemplateContext::from(Some(String::from("JIRA-123-payments")), vec![], &config)
+                .unwrap();
+        let prepared = prepare_message(
+            &TemplateAction::Write,
+            "# Please enter the commit message for your changes.\n",
+            DEFAULT_TEMPLATE,
+            &config,
+            &context,
+        )
+        .unwrap();
+
+        // when
+        let written = prepared.replacen('\n', "fix: handle refunds\n\nSome body\n", 1);
+        let commit = ConventionalCommit::from_str(&written).unwrap();
+
+        // then
+        assert!(prepared.starts_with("\n#<type>"));
+        assert_eq!(commit.body.unwrap().paragraphs[0].lines, vec!["Some body"]);
+        assert_eq!(commit.footer.unwrap().elements[0].content, "Refs: JIRA-123");
+    }
+
+    #[test]
+    fn should_prefill_scope_when_exactly_one_scope_was_inferred() {
+        // given
+        let config = Config::default();
+        let context = TemplateContext::from(
+            Some(String::from("feat/JIRA-1")),
+            vec![String::from("api")],
+            &config,
+        )
+        .unwrap();
+
+        // when
+        let actual = prepare_message(&TemplateAction::Write, "", "#", &config, &context);
+
+        // then
+        assert_eq!(
+            actual,
+            Some(String::from("feat(api): \n#\n\nRefs: JIRA-1\n"))
+        );
+    }
+
+    #[test]
+    fn should_only_suggest_inferred_scope_on_branch_without_type() {
+        // given
+        let config = Config::default();
+        let context = TemplateContext::from(
+            Some(String::from("main")),
+            vec![String::from("api")],
+            &config,
+        )
+        .unwrap();
+
+        // when
+        let actual = prepare_message(
+            &TemplateAction::Write,
+            "",
+            "# - {{scope_suggestions}}",
+            &config,
+            &context,
+        );
+
+        // then
+        assert_eq!(
+            actual,
+            Some(String::from("# - api (inferred from staged files)"))
+        );
+    }
+
+    #[test]
+    fn should_list_inferred_scopes_before_configured_ones() {
+        // given
+        let config = Config {
+            scopes: vec![String::from("api"), String::from("ui")],
+            ..Config::default()
+        };
+        let context = TemplateContext::from(
+            Some(String::from("feat/foo")),
+            vec![String::from("api"), String::from("cli")],
+            &config,
+        )
+        .unwrap();
+
+        // when
+        let actual = prepare_message(
+            &TemplateAction::Write,
+            "",
+            "# - {{scope_suggestions}}",
+            &config,
+            &context,
+        );
+
+        // then
+        assert_eq!(
+            actual,
+            Some(String::from(
+                "feat(foo): \n# - api (inferred from staged files)\n# - cli (inferred from staged files)\n# - ui"
+            ))
+        );
+    }
+
+    #[test]
+    fn should_not_prefill_header_with_unknown_type() {
+        // given
+        let config = Config::default();
+        let context =
+            TemplateContext::from(Some(String::from("users/foo")), vec![], &config).unwrap();
+
+        // when
+        let actual = prepare_message(
+            &TemplateAction::Write,
+            "",
+            "#<type>: <description>",
+            &config,
+            &context,
+        );
+
+        // then
+        assert_eq!(actual, Some(String::from("#<type>: <description>")));
+    }
+
+    #[test]
+    fn should_skip_preparing_message_when_asked_to() {
+        // given
+        let config = Config::default();
+
+        // when
+        let actual = prepare_message(
+            &TemplateAction::Skip,
+            "Merge branch 'foo'",
+            "#<type>: <description>",
+            &config,
+            &TemplateContext::default(),
+        );
+
+        // then
+        assert_eq!(actual, None);
+    }
 }
@@ -1,2 +1,15 @@
+pub mod changelog;
+pub mod config;
 pub mod core;
+pub mod git;
 pub mod hooks;
+pub mod lint;
+pub mod manifest;
+pub mod monorepo;
+pub mod overrides;
+pub mod query;
+pub mod release;
+pub mod scope;
+pub mod stats;
+pub mod wizard;
+pub mod workspace;
@@ -0,0 +1,38 @@
+use con_comm::config::Config;
+use con_comm::git;
+use con_comm::lint::range::{lint_range, process_args, LintRangeArgs};
+use std::env;
+
+/*
+Lints messages of already made commits, e.g. `lint-range origin/main..HEAD` in a CI pipeline,
+HEAD and all of its ancestors by default. Commits ignored in `[history.ignore]` are skipped,
+and message overrides are linted instead of the original messages. Every commit with
+violations is reported on its own line, and the run fails if there are any.
+*/
+fn main() {
+    let args: LintRangeArgs = process_args(&env::args().collect::<Vec<String>>());
+
+    let current_dir = env::current_dir()
+        .unwrap_or_else(|e| panic!("Couldn't determine current directory: {}", e));
+    let config = Config::discover(&current_dir)
+        .unwrap_or_else(|e| panic!("Couldn't load configuration: {}", e));
+    let options = config
+        .read_options()
+        .unwrap_or_else(|e| panic!("Couldn't load configuration: {}", e));
+    let repository = git::open_history(&current_dir, options)
+        .unwrap_or_else(|e| panic!("Couldn't open repository: {}", e));
+
+    let result = lint_range(repository.as_ref(), &args.range, &config)
+        .unwrap_or_else(|e| panic!("Couldn't read commits: {}", e));
+    for commit in &result {
+        let reasons: Vec<String> = commit.violations.iter().map(|v| v.to_string()).collect();
+        let short_id: String = commit.id.chars().take(7).collect();
+        eprintln!("{} {}: {}", short_id, commit.header, reasons.join("; "));
+    }
+    if !result.is_empty() {
+        panic!(
+            "{} commit messages do not match proper format",
+            result.len()
+        );
+    }
+}
@@ -0,0 +1,336 @@
+use crate::config::Config;
+use crate::lint::{
+    find_breaking_change_tokens, has_trailing_period, normalise_breaking_change, Layout, Rule,
+    HEADER_REGEX,
+};
+use std::fmt;
+use std::fmt::Formatter;
+
+/// Description of a change applied to the message.
+#[derive(Debug, PartialEq)]
+pub struct Fix {
+    pub rule: Rule,
+    pub description: String,
+}
+
+impl fmt::Display for Fix {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.description)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct FixResult {
+    pub message: String,
+    pub fixes: Vec<Fix>,
+}
+
+/// Width body lines are wrapped at, unless `lint.max_body_line_length` is configured.
+const DEFAULT_BODY_LINE_LENGTH: usize = 72;
+
+/// Rewrites mechanically fixable problems, leaving comments and the part below the scissors
+/// line untouched. Problems that need a human decision are left for the linter to report.
+pub fn fix(message: &str, config: &Config) -> FixResult {
+    let mut lines: Vec<String> = message.lines().map(String::from).collect();
+
+    let layout = Layout::from(&lines);
+    let header_line = match layout.header {
+        Some(header_line) => header_line,
+        None => {
+            return FixResult {
+                message: String::from(message),
+                fixes: vec![],
+            }
+        }
+    };
+
+    // tokens are normalised in place, before wrapping shifts the lines; `BREAKING-CHANGE:` is
+    // valid, but rewritten as well, so that every message uses the same token
+    let mut breaking_change_fixes: Vec<Fix> = vec![];
+    for number in find_breaking_change_tokens(&lines, &layout, true) {
+        lines[number] = normalise_breaking_change(&lines[number]);
+        breaking_change_fixes.push(Fix {
+            rule: Rule::BreakingChangeToken,
+            description: format!("Normalised breaking change token: {}", lines[number]),
+        });
+    }
+
+    // body lines are wrapped next, as it shifts only lines below the header; the layout is
+    // computed again, so that a footer with a fixed token is not mistaken for the body
+    let mut wrap_fixes: Vec<Fix> = vec![];
+    let max_body_line_length = config
+        .lint
+        .max_body_line_length
+        .unwrap_or(DEFAULT_BODY_LINE_LENGTH);
+    for number in Layout::from(&lines).body_lines().into_iter().rev() {
+        let wrapped = wrap_line(&lines[number], max_body_line_length);
+        if wrapped.len() > 1 {
+            wrap_fixes.insert(
+                0,
+                Fix {
+                    rule: Rule::BodyLineLength,
+                    description: format!(
+                        "Wrapped body line at {} characters: {}",
+                        max_body_line_length, lines[number]
+                    ),
+                },
+            );
+            lines.splice(number..number + 1, wrapped);
+        }
+    }
+
+    let (header, mut fixes) = fix_header(&lines[header_line]);
+    lines[header_line] = header;
+
+    let next_line = lines.get(header_line + 1).map(|line| line.trim());
+    if matches!(next_line, Some(line) if !line.is_empty() && !line.starts_with('#')) {
+        lines.insert(header_line + 1, String::new());
+        fixes.push(Fix {
+            rule: Rule::BlankLineAfterHeader,
+            description: String::from("Inserted empty line after the header"),
+        });
+    }
+
+    fixes.extend(breaking_change_fixes);
+    fixes.extend(wrap_fixes);
+
+    let mut fixed_message = lines.join("\n");
+    if message.ends_with('\n') {
+        fixed_message.push('\n');
+    }
+
+    return FixResult {
+        message: fixed_message,
+        fixes,
+    };
+}
+
+fn fix_header(header: &str) -> (String, Vec<Fix>) {
+    let mut fixes: Vec<Fix> = vec![];
+    let captures = match HEADER_REGEX.captures(header.trim()) {
+        Some(captures) => captures,
+        None => return (String::from(header), fixes),
+    };
+
+    let mut commit_type = String::from(&captures["type"]);
+    if commit_type != commit_type.to_lowercase() {
+        commit_type = commit_type.to_lowercase();
+        fixes.push(Fix {
+            rule: Rule::TypeCase,
+            description: format!("Lower-cased commit type: {}", commit_type),
+        });
+    }
+
+    let mut description = String::from(&captures["description"]);
+    if captures["space"].is_empty() && !description.is_empty() {
+        fixes.push(Fix {
+            rule: Rule::SpaceAfterColon,
+            description: String::from("Added space after the colon"),
+        });
+    }
+
+    if has_trailing_period(&description) {
+        description.pop();
+        fixes.push(Fix {
+            rule: Rule::TrailingPeriod,
+            description: String::from("Removed trailing period from the description"),
+        });
+    }
+
+    if fixes.is_empty() {
+        return (String::from(header), fixes);
+    }
+
+    let header = format!(
+        "{}{}{}: {}",
+        commit_type,
+        captures.name("scope").map_or("", |m| m.as_str()),
+        captures.name("breaking").map_or("", |m| m.as_str()),
+        description.trim_end()
+    );
+
+    return (header, fixes);
+}
+
+/// Splits a line into lines no longer than `width`, breaking at spaces. Words longer than
+/// the limit (e.g. URLs) are kept whole. Continuation lines keep the indentation of the
+/// original line, extended past a list marker when the line starts with one.
+fn wrap_line(line: &str, width: usize) -> Vec<String> {
+    if line.chars().count() <= width {
+        return vec![String::from(line)];
+    }
+
+    let content = line.trim_start();
+    let indentation = &line[..line.len() - content.len()];
+    let marker_width = match content.split_once(' ') {
+        Some((marker, _)) if is_list_marker(marker) => marker.chars().count() + 1,
+        _ => 0,
+    };
+    let continuation = format!("{}{}", indentation, " ".repeat(marker_width));
+
+    let mut wrapped: Vec<String> = vec![];
+    let mut current = String::from(indentation);
+    let mut current_has_words = false;
+    for word in content.split(' ').filter(|word| !word.is_empty()) {
+        let length = current.chars().count() + word.chars().count() + 1;
+        if current_has_words && length > width {
+            wrapped.push(current);
+            current = continuation.clone();
+            current_has_words = false;
+        }
+
+        if current_has_words {
+            current.push(' ');
+        }
+        current.push_str(word);
+        current_has_words = true;
+    }
+    wrapped.push(current);
+
+    return wrapped;
+}
+
+fn is_list_marker(marker: &str) -> bool {
+    match marker {
+        "-" | "*" | "+" => true,
+        _ => {
+            marker.ends_with(['.', ')'])
+                && marker.len() > 1
+                && marker[..marker.len() - 1]
+                    .chars()
+                    .all(|c| c.is_ascii_digit())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+    use crate::lint::fix::{fix, wrap_line};
+    use crate::lint::{lint, Rule};
+
+    fn fixed_rules(message: &str, config: &Config) -> (String, Vec<Rule>) {
+        let result = fix(message, config);
+        return (
+            result.message,
+            result.fixes.iter().map(|fix| fix.rule).collect(),
+        );
+    }
+
+    #[test]
+    fn should_fix_header_style_problems() {
+        // when
+        let (message, rules) = fixed_rules("FEAT(api)!:add endpoint.\n", &Config::default());
+
+        // then
+        assert_eq!(message, "feat(api)!: add endpoint\n");
+        assert_eq!(
+            rules,
+            vec![Rule::TypeCase, Rule::SpaceAfterColon, Rule::TrailingPeriod]
+        );
+    }
+
+    #[test]
+    fn should_insert_blank_line_after_header_and_normalise_breaking_change() {
+        // given
+        let message = "fix: something\nbody line\n\nbreaking-change: removed option\n# comment\n";
+
+        // when
+        let (message, rules) = fixed_rules(message, &Config::default());
+
+        // then
+        assert_eq!(
+            message,
+            "fix: something\n\nbody line\n\nBREAKING CHANGE: removed option\n# comment\n"
+        );
+        assert_eq!(
+            rules,
+            vec![Rule::BlankLineAfterHeader, Rule::BreakingChangeToken]
+        );
+    }
+
+    #[test]
+    fn should_wrap_long_body_lines_but_not_trailers() {
+        // given
+        let mut config = Config::default();
+        config.lint.max_body_line_length = Some(20);
+        let message = "fix: something\n\nthis line is much longer than allowed\nshort\n\nReviewed-by: someone with a long name";
+
+        // when
+        let (message, rules) = fixed_rules(message, &config);
+
+        // then
+        assert_eq!(
+            message,
+            "fix: something\n\nthis line is much\nlonger than allowed\nshort\n\nReviewed-by: someone with a long name"
+        );
+        assert_eq!(rules, vec![Rule::BodyLineLength]);
+    }
+
+    #[test]
+    fn should_wrap_at_default_width_and_normalise_synonym_token_by_default() {
+        // given
+        let long_line = "word ".repeat(20);
+        let message = format!(
+            "fix: something\n\n{}\n\nBREAKING-CHANGE: removed option\n",
+            long_line.trim()
+        );
+
+        // when
+        let (message, rules) = fixed_rules(&message, &Config::default());
+
+        // then
+        assert!(
+            message.lines().all(|line| line.chars().count() <= 72),
+            "{}",
+            message
+        );
+        assert!(message.ends_with("\n\nBREAKING CHANGE: removed option\n"));
+        assert_eq!(rules, vec![Rule::BreakingChangeToken, Rule::BodyLineLength]);
+    }
+
+    #[test]
+    fn should_produce_message_without_fixable_violations() {
+        // given
+        let mut config = Config::default();
+        config.lint.max_body_line_length = Some(30);
+        config.lint.enforce_style = true;
+        let message = "Fix(cli):handle empty input.\nInput can be empty when the user presses enter right away.\n\nbreaking change: empty input is an error now\n";
+
+        // when
+        let result = fix(message, &config);
+
+        // then
+        assert_eq!(result.fixes.len(), 6);
+        assert!(
+            lint(&result.message, &config).is_empty(),
+            "{:?}",
+            lint(&result.message, &config)
+        );
+    }
+
+    #[test]
+    fn should_not_change_anything_in_valid_message() {
+        // given
+        let message = "feat(api): add endpoint\n\nSome body\n\nRefs: #12\n";
+
+        // when
+        let result = fix(message, &Config::default());
+
+        // then
+        assert_eq!(result.message, message);
+        assert!(result.fixes.is_empty());
+    }
+
+    #[test]
+    fn should_keep_list_indentation_when_wrapping() {
+        assert_eq!(
+            wrap_line("- first item that is too long", 15),
+            vec!["- first item", "  that is too", "  long"]
+        );
+        assert_eq!(
+            wrap_line("see https://example.com/a/very/long/url", 10),
+            vec!["see", "https://example.com/a/very/long/url"]
+        );
+    }
+}
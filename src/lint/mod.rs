@@ -0,0 +1,468 @@
+pub mod fix;
+pub mod range;
+
+use crate::config::Config;
+use crate::core::base::Paragraph;
+use crate::core::commit_message::CommitMessage;
+use crate::core::conventional_commit::{ConventionalCommit, Footer};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::fmt;
+use std::fmt::Formatter;
+use std::str::FromStr;
+
+/// Everything below this line is removed by git (`git commit --verbose`).
+pub const SCISSORS_LINE: &str = "# ------------------------ >8 ------------------------";
+
+lazy_static! {
+    static ref HEADER_REGEX: Regex = Regex::new(
+        r"^(?<type>[^():!\s]+)(?<scope>\([^)]*\))?(?<breaking>!)?:(?<space>\s*)(?<description>.*)$"
+    )
+    .unwrap();
+    static ref BREAKING_CHANGE_REGEX: Regex =
+        Regex::new(r"(?i)^breaking[ -]change\s*:\s*").unwrap();
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Rule {
+    /// Message does not follow the conventional commits grammar.
+    Syntax,
+    /// Type is not present in the type registry.
+    UnknownType,
+    /// Header is longer than allowed.
+    HeaderLength,
+    /// Type is not written in lower case.
+    TypeCase,
+    /// Description ends with a period.
+    TrailingPeriod,
+    /// Description is not separated from the colon with a space.
+    SpaceAfterColon,
+    /// Header is not followed by an empty line.
+    BlankLineAfterHeader,
+    /// Breaking change trailer is not spelled as `BREAKING CHANGE: `.
+    BreakingChangeToken,
+    /// Body line is longer than allowed.
+    BodyLineLength,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Violation {
+    pub rule: Rule,
+    pub line: String,
+    pub reason: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.line.is_empty() {
+            write!(f, "{}", self.reason)
+        } else {
+            write!(f, "{}: {}", self.reason, self.line)
+        }
+    }
+}
+
+/// Positions of the parts of a raw commit message, comment lines excluded.
+#[derive(Debug, Default)]
+struct Layout {
+    header: Option<usize>,
+    /// Paragraphs following the header, as lists of line numbers.
+    paragraphs: Vec<Vec<usize>>,
+    /// Whether the last paragraph consists of git trailers.
+    has_footer: bool,
+}
+
+impl Layout {
+    fn from(lines: &[String]) -> Self {
+        let mut layout = Layout::default();
+        let end = lines
+            .iter()
+            .position(|line| line == SCISSORS_LINE)
+            .unwrap_or(lines.len());
+
+        let mut current: Vec<usize> = vec![];
+        for (number, line) in lines.iter().enumerate().take(end) {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                if !current.is_empty() {
+                    layout.paragraphs.push(current);
+                    current = vec![];
+                }
+                continue;
+            }
+
+            if layout.header.is_none() {
+                layout.header = Some(number);
+                continue;
+            }
+
+            current.push(number);
+        }
+        if !current.is_empty() {
+            layout.paragraphs.push(current);
+        }
+
+        if let Some(last) = layout.paragraphs.last() {
+            let paragraph = Paragraph {
+                lines: last
+                    .iter()
+                    .map(|n| String::from(lines[*n].trim()))
+                    .collect(),
+            };
+            layout.has_footer = Footer::try_from(&paragraph).is_ok();
+        }
+
+        return layout;
+    }
+
+    fn body_lines(&self) -> Vec<usize> {
+        let body_paragraphs = match self.has_footer {
+            true => &self.paragraphs[..self.paragraphs.len() - 1],
+            false => &self.paragraphs[..],
+        };
+
+        return body_paragraphs.iter().flatten().copied().collect();
+    }
+}
+
+/// Checks the message against the grammar and rules enabled in the configuration.
+pub fn lint(message: &str, config: &Config) -> Vec<Violation> {
+    let mut violations: Vec<Violation> = vec![];
+
+    let lines: Vec<String> = message.lines().map(String::from).collect();
+    let layout = Layout::from(&lines);
+
+    let header = get_header(message);
+    if let Some(header) = &header {
+        if let Some(max_header_length) = config.lint.max_header_length {
+            if header.chars().count() > max_header_length {
+                violations.push(Violation {
+                    rule: Rule::HeaderLength,
+                    line: header.clone(),
+                    reason: format!(
+                        "Commit header is longer than {} characters",
+                        max_header_length
+                    ),
+                });
+            }
+        }
+
+        if config.lint.enforce_style {
+            violations.extend(check_header_style(header));
+        }
+    }
+
+    if config.lint.enforce_style {
+        violations.extend(check_message_style(&lines, &layout));
+    }
+
+    if let Some(max_body_line_length) = config.lint.max_body_line_length {
+        for number in layout.body_lines() {
+            let line = &lines[number];
+            if line.chars().count() > max_body_line_length {
+                violations.push(Violation {
+                    rule: Rule::BodyLineLength,
+                    line: line.clone(),
+                    reason: format!(
+                        "Body line is longer than {} characters",
+                        max_body_line_length
+                    ),
+                });
+            }
+        }
+    }
+
+    match ConventionalCommit::from_str(message) {
+        Err(e) => violations.push(Violation {
+            rule: Rule::Syntax,
+            line: e.line,
+            reason: e.reason,
+        }),
+        Ok(commit) => {
+            let commit_type = commit.commit_type.as_str().to_lowercase();
+            if config.lint.enforce_types && config.find_type(&commit_type).is_none() {
+                violations.push(Violation {
+                    rule: Rule::UnknownType,
+                    line: header.unwrap_or_default(),
+                    reason: format!("Commit type {} is not allowed", commit_type),
+                });
+            }
+        }
+    }
+
+    return violations;
+}
+
+fn check_header_style(header: &str) -> Vec<Violation> {
+    let mut violations: Vec<Violation> = vec![];
+    let captures = match HEADER_REGEX.captures(header) {
+        Some(captures) => captures,
+        None => return violations,
+    };
+
+    let commit_type = &captures["type"];
+    if commit_type != commit_type.to_lowercase() {
+        violations.push(Violation {
+            rule: Rule::TypeCase,
+            line: String::from(header),
+            reason: String::from("Commit type should be written in lower case"),
+        });
+    }
+
+    let description = &captures["description"];
+    if captures["space"].is_empty() && !description.is_empty() {
+        violations.push(Violation {
+            rule: Rule::SpaceAfterColon,
+            line: String::from(header),
+            reason: String::from("Description should be separated from the colon with a space"),
+        });
+    }
+
+    if has_trailing_period(description) {
+        violations.push(Violation {
+            rule: Rule::TrailingPeriod,
+            line: String::from(header),
+            reason: String::from("Description should not end with a period"),
+        });
+    }
+
+    return violations;
+}
+
+fn check_message_style(lines: &[String], layout: &Layout) -> Vec<Violation> {
+    let mut violations: Vec<Violation> = vec![];
+
+    if let Some(header_line) = layout.header {
+        let next_line = lines.get(header_line + 1).map(|line| line.trim());
+        if matches!(next_line, Some(line) if !line.is_empty() && !line.starts_with('#')) {
+            violations.push(Violation {
+                rule: Rule::BlankLineAfterHeader,
+                line: lines[header_line].clone(),
+                reason: String::from("Commit header should be followed by an empty line"),
+            });
+        }
+    }
+
+    for number in find_breaking_change_tokens(lines, layout, false) {
+        violations.push(Violation {
+            rule: Rule::BreakingChangeToken,
+            line: lines[number].clone(),
+            reason: String::from("Breaking change should be introduced with BREAKING CHANGE:"),
+        });
+    }
+
+    return violations;
+}
+
+fn has_trailing_period(description: &str) -> bool {
+    description.ends_with('.') && !description.ends_with("..")
+}
+
+/// Numbers of lines with a misspelled breaking change token, e.g. `Breaking change:`. Only the
+/// last paragraph is checked, when it is a footer once the tokens are spelled correctly, so
+/// that body lines starting with the same words are left alone. `BREAKING-CHANGE: ` is a
+/// synonym allowed by the specification, and only reported with `include_synonym`.
+fn find_breaking_change_tokens(
+    lines: &[String],
+    layout: &Layout,
+    include_synonym: bool,
+) -> Vec<usize> {
+    let is_misspelled = |line: &str| {
+        BREAKING_CHANGE_REGEX.is_match(line)
+            && !line.starts_with("BREAKING CHANGE: ")
+            && (include_synonym || !line.starts_with("BREAKING-CHANGE: "))
+    };
+
+    let last = match layout.paragraphs.last() {
+        Some(last) => last,
+        None => return vec![],
+    };
+    let footer = Paragraph {
+        lines: last
+            .iter()
+            .map(|number| lines[*number].trim())
+            .map(|line| match is_misspelled(line) {
+                true => normalise_breaking_change(line),
+                false => String::from(line),
+            })
+            .collect(),
+    };
+    if Footer::try_from(&footer).is_err() {
+        return vec![];
+    }
+
+    return last
+        .iter()
+        .filter(|number| is_misspelled(lines[**number].trim()))
+        .copied()
+        .collect();
+}
+
+fn normalise_breaking_change(line: &str) -> String {
+    BREAKING_CHANGE_REGEX
+        .replace(line.trim(), "BREAKING CHANGE: ")
+        .to_string()
+}
+
+fn get_header(message: &str) -> Option<String> {
+    let commit_message = CommitMessage::from(message);
+    return commit_message
+        .get_paragraph(0)
+        .and_then(|paragraph| paragraph.get_line(0))
+        .cloned();
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+    use crate::lint::{lint, Rule};
+
+    fn rules(message: &str) -> Vec<Rule> {
+        let mut config = Config::default();
+        config.lint.enforce_style = true;
+        lint(message, &config)
+            .iter()
+            .map(|violation| violation.rule)
+            .collect()
+    }
+
+    #[test]
+    fn should_not_report_anything_for_valid_message() {
+        // given
+        let message = "feat(api): add endpoint\n\nSome body\n\nRefs: #12\n";
+
+        // when
+        let violations = lint(message, &Config::default());
+
+        // then
+        assert!(violations.is_empty(), "{:?}", violations);
+    }
+
+    #[test]
+    fn should_report_syntax_error() {
+        // when
+        let violations = lint("Implemented something", &Config::default());
+
+        // then
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].rule, Rule::Syntax);
+    }
+
+    #[test]
+    fn should_report_unknown_type_only_when_types_are_enforced() {
+        // given
+        let mut config = Config::default();
+        let message = "wip: something";
+
+        // when
+        let lenient = lint(message, &config);
+        config.lint.enforce_types = true;
+        let strict = lint(message, &config);
+
+        // then
+        assert!(lenient.is_empty());
+        assert_eq!(strict.len(), 1);
+        assert_eq!(strict[0].rule, Rule::UnknownType);
+    }
+
+    #[test]
+    fn should_report_too_long_header() {
+        // given
+        let mut config = Config::default();
+        let long_header = format!("fix: {}", "long ".repeat(40));
+
+        // when
+        let unlimited = lint(&long_header, &config);
+        config.lint.max_header_length = Some(10);
+        let violations = lint("fix: a rather long description", &config);
+
+        // then
+        assert!(unlimited.is_empty(), "{:?}", unlimited);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].rule, Rule::HeaderLength);
+    }
+
+    #[test]
+    fn should_accept_style_problems_unless_enforced() {
+        // given
+        let messages = [
+            "Feat(api):add endpoint",
+            "fix: handle errors.",
+            "feat: remove option\n\nbreaking change: option is gone",
+            "docs: link the spec\n\nhttps://www.conventionalcommits.org/en/v1.0.0/#specification-of-a-very-long-url",
+        ];
+
+        for message in messages {
+            // when
+            let violations = lint(message, &Config::default());
+
+            // then
+            assert!(violations.is_empty(), "{}: {:?}", message, violations);
+        }
+    }
+
+    #[test]
+    fn should_report_header_style_problems() {
+        assert_eq!(
+            rules("Feat(api):add endpoint"),
+            vec![Rule::TypeCase, Rule::SpaceAfterColon]
+        );
+        assert_eq!(rules("fix: handle errors."), vec![Rule::TrailingPeriod]);
+        assert!(rules("fix: handle errors...").is_empty());
+    }
+
+    #[test]
+    fn should_report_missing_blank_line_after_header() {
+        assert_eq!(
+            rules("fix: handle errors\nbody right below"),
+            vec![Rule::BlankLineAfterHeader, Rule::Syntax]
+        );
+        assert!(rules("fix: handle errors\n# comment\nbody").is_empty());
+    }
+
+    #[test]
+    fn should_report_misspelled_breaking_change_token() {
+        assert_eq!(
+            rules("feat: remove option\n\nBreaking change: option is gone"),
+            vec![Rule::BreakingChangeToken]
+        );
+        assert!(rules("feat: remove option\n\nBREAKING-CHANGE: option is gone").is_empty());
+        assert!(
+            rules("feat: remove option\n\nBreaking change: the option is gone.\n\nRefs: #1")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn should_report_long_body_lines_but_not_long_trailers() {
+        // given
+        let mut config = Config::default();
+        config.lint.max_body_line_length = Some(20);
+        let message =
+            "fix: something\n\nthis line is much longer than allowed\n\nReviewed-by: someone with a long name";
+
+        // when
+        let violations = lint(message, &config);
+
+        // then
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].rule, Rule::BodyLineLength);
+        assert_eq!(violations[0].line, "this line is much longer than allowed");
+    }
+
+    #[test]
+    fn should_ignore_everything_below_scissors_line() {
+        // given
+        let message = "fix: something\n\n# ------------------------ >8 ------------------------\ndiff --git a/foo b/foo\nBREAKING-CHANGE: part of a diff";
+
+        // when
+        let mut config = Config::default();
+        config.lint.enforce_style = true;
+        let violations = lint(message, &config);
+
+        // then
+        assert!(violations
+            .iter()
+            .all(|v| v.rule != Rule::BreakingChangeToken));
+    }
+}
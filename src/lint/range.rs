@@ -0,0 +1,108 @@
+use crate::config::Config;
+use crate::git::{GitError, Repository, RevisionRange};
+use crate::lint::{lint, Violation};
+
+#[derive(Debug, PartialEq)]
+pub struct LintRangeArgs {
+    pub range: RevisionRange,
+}
+
+pub fn process_args(args: &[String]) -> LintRangeArgs {
+    let mut range = RevisionRange::default();
+
+    for arg in args.iter().skip(1) {
+        match arg.as_str() {
+            _ if arg.starts_with("--") => panic!("Unknown argument: {}", arg),
+            _ => range = RevisionRange::from(arg),
+        }
+    }
+
+    return LintRangeArgs { range };
+}
+
+/// Violations found in the message of a commit.
+#[derive(Debug, PartialEq)]
+pub struct CommitViolations {
+    pub id: String,
+    pub header: String,
+    pub violations: Vec<Violation>,
+}
+
+/// Lints messages of the commits in the range, newest first, and returns those with
+/// violations. Commits are read as for releases, so ignored commits are not linted and
+/// message overrides are linted instead of the original messages.
+pub fn lint_range(
+    repository: &dyn Repository,
+    range: &RevisionRange,
+    config: &Config,
+) -> Result<Vec<CommitViolations>, GitError> {
+    let mut result: Vec<CommitViolations> = vec![];
+    for commit in repository.history_commits(range)? {
+        let violations = lint(&commit.message, config);
+        if violations.is_empty() {
+            continue;
+        }
+
+        result.push(CommitViolations {
+            id: commit.id,
+            header: String::from(commit.message.lines().next().unwrap_or_default()),
+            violations,
+        });
+    }
+
+    return Ok(result);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+    use crate::git::cli::tests::{commit_file, create_repository};
+    use crate::git::cli::CliRepository;
+    use crate::git::reader::HistoryReader;
+    use crate::git::RevisionRange;
+    use crate::lint::range::{lint_range, process_args, LintRangeArgs};
+
+    #[test]
+    fn should_process_args() {
+        // given
+        let args: Vec<String> = ["lint-range", "v1.0.0..HEAD"]
+            .iter()
+            .map(|arg| String::from(*arg))
+            .collect();
+
+        // when
+        let args = process_args(&args);
+
+        // then
+        assert_eq!(
+            args,
+            LintRangeArgs {
+                range: RevisionRange::from("v1.0.0..HEAD"),
+            }
+        );
+    }
+
+    #[test]
+    fn should_report_commits_with_violations_only() {
+        // given
+        let root = create_repository("lint-range");
+        commit_file(&root, "a.txt", "Initial import");
+        let invalid = commit_file(&root, "a.txt", "fixed things");
+        commit_file(&root, "a.txt", "fix: crash on start");
+        let options = Config::parse("[history.ignore]\nmessages = ['^Initial import$']")
+            .unwrap()
+            .read_options()
+            .unwrap();
+        let repository = CliRepository::discover(&root).unwrap();
+        let reader = HistoryReader::new(Box::new(repository), options);
+
+        // when
+        let result = lint_range(&reader, &RevisionRange::default(), &Config::default()).unwrap();
+
+        // then
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].id, invalid);
+        assert_eq!(result[0].header, "fixed things");
+        assert!(!result[0].violations.is_empty());
+    }
+}
@@ -1,51 +1,61 @@
-use std::process::Command;
-
-fn main() -> () {
+use con_comm::config::Config;
+use con_comm::core::history::History;
+use con_comm::git;
+use con_comm::query::{process_args, LogArgs};
+use std::collections::HashSet;
+use std::env;
+
+fn main() {
     /*
     This binary should:
     1. Parse git log into a history of git commits for given branch
     2. Generate a changelog (doesn't have to be pretty markdown just yet)
     3. Store a reference to last read commit to speed up changelog generation in future
 
-    What we would probably like to do first is to decide on git log format, that is consistent
-    among various languages and can be easily parsed.
-
-    git log should provide:
-    - timestamp of commit
-    - full commit message, in a same format as every other thing parsed so far
-
-    LANG="en_US.UTF-8" git log --format=%ct%n%B should do the trick for now
+    For now it only lists commits in the given range (`from..to`, HEAD by default), read through
+    the repository abstraction, so that it works the same with or without libgit2. Conventional
+    commits can be queried, e.g. with `--type feat --scope api --since v2.0.0`. Commits are read
+    like for changelogs, with message overrides, ignored commits and squashed entries applied.
 
     TIL: git provides built-in support for parsing trailers with git interpret-trailers.
     It might be worth looking into that.
      */
-
-    run_git();
-
-    todo!("Implement log parsing routine");
-}
-
-fn run_git() -> () {
-    // TODO need to fina a good way to provide reliable path to git binary
-    let command = Command::new("/usr/bin/git")
-        /*
-        LANG sets the env variable for git to use
-        %ct adds committer date in unix timestamp
-        %n adds newline
-        %B adds raw, unprocessed git commit message
-         */
-        .args(["log", "--format=%ct%n%B"])
-        .env("LANG", "en_US.UTF-8")
-        .output();
-
-    if command.is_err() {
-        panic!(
-            "Failed to start git log command: {}",
-            command.err().unwrap()
-        )
+    let args: LogArgs = process_args(&env::args().collect::<Vec<String>>());
+
+    let current_dir = env::current_dir()
+        .unwrap_or_else(|e| panic!("Couldn't determine current directory: {}", e));
+    let config = Config::discover(&current_dir)
+        .unwrap_or_else(|e| panic!("Couldn't load configuration: {}", e));
+    let options = config
+        .read_options()
+        .unwrap_or_else(|e| panic!("Couldn't load configuration: {}", e));
+    let repository = git::open_history(&current_dir, options)
+        .unwrap_or_else(|e| panic!("Couldn't open repository: {}", e));
+    let commits = repository
+        .history_commits(&args.range)
+        .unwrap_or_else(|e| panic!("Couldn't read commits: {}", e));
+
+    let history = History::from(&commits);
+    let matching: HashSet<&str> = args
+        .query
+        .apply(&history)
+        .iter()
+        .map(|entry| entry.id.as_str())
+        .collect();
+
+    for commit in &commits {
+        // without conditions, commits that aren't conventional are listed as well
+        if !args.query.is_empty() && !matching.contains(commit.id.as_str()) {
+            continue;
+        }
+
+        println!(
+            "{} {} <{}> {}",
+            commit.id,
+            commit.author.name,
+            commit.author.email,
+            commit.committer.timestamp.to_rfc3339()
+        );
+        println!("{}", commit.message);
     }
-
-    let stdout_u8 = command.unwrap().stdout;
-    let stdout = std::str::from_utf8(stdout_u8.as_slice()).unwrap();
-    println!("{}", stdout);
 }
@@ -0,0 +1,691 @@
+use crate::core::semantic_version::SemanticVersion;
+use crate::workspace::cargo_packages;
+use std::fmt;
+use std::fmt::Formatter;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, TableLike, Value};
+
+/// Manifests looked for in the repository root and package directories.
+const MANIFEST_FILE_NAMES: [&str; 4] = ["Cargo.toml", "package.json", "pyproject.toml", "VERSION"];
+
+/// Tables holding the version in TOML manifests.
+const CARGO_VERSION_TABLES: [&[&str]; 2] = [&["package"], &["workspace", "package"]];
+const PYPROJECT_VERSION_TABLES: [&[&str]; 2] = [&["project"], &["tool", "poetry"]];
+
+#[derive(Debug)]
+pub struct ManifestError {
+    pub path: String,
+    pub reason: String,
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.reason)
+    }
+}
+
+/// Kind of a project manifest, recognized by its file name.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ManifestKind {
+    /// `package.version` and `workspace.package.version` in `Cargo.toml`.
+    Cargo,
+    /// Top level `version` in `package.json`.
+    PackageJson,
+    /// `project.version` and `tool.poetry.version` in `pyproject.toml`.
+    Pyproject,
+    /// Plain `VERSION` file holding nothing but the version.
+    VersionFile,
+}
+
+impl ManifestKind {
+    pub fn from(path: &Path) -> Option<Self> {
+        match path.file_name()?.to_str()? {
+            "Cargo.toml" => Some(ManifestKind::Cargo),
+            "package.json" => Some(ManifestKind::PackageJson),
+            "pyproject.toml" => Some(ManifestKind::Pyproject),
+            "VERSION" => Some(ManifestKind::VersionFile),
+            _ => None,
+        }
+    }
+}
+
+/// Content of a manifest, or another file updated for a release, before and after the update.
+#[derive(Debug)]
+pub struct ManifestUpdate {
+    pub path: PathBuf,
+    pub before: String,
+    pub after: String,
+}
+
+impl ManifestUpdate {
+    /// Reads the file at `path`, without any changes yet.
+    pub fn read(path: &Path) -> Result<Self, ManifestError> {
+        let content = fs::read_to_string(path).map_err(|e| ManifestError {
+            path: path.display().to_string(),
+            reason: e.to_string(),
+        })?;
+
+        return Ok(Self {
+            path: path.to_path_buf(),
+            before: content.clone(),
+            after: content,
+        });
+    }
+
+    /// Replaces the version of the manifest.
+    ///
+    /// Only the version values are touched, so formatting and comments are preserved.
+    /// Manifests without a version, e.g. a virtual workspace or a package inheriting
+    /// the workspace version, are left as they are.
+    pub fn set_version(&mut self, version: &SemanticVersion) -> Result<(), ManifestError> {
+        let kind = ManifestKind::from(&self.path)
+            .ok_or_else(|| self.error(String::from("Unknown manifest")))?;
+        let version = version.to_string();
+
+        self.after = match kind {
+            ManifestKind::Cargo => update_toml(&self.after, &CARGO_VERSION_TABLES, &version),
+            ManifestKind::PackageJson => update_package_json(&self.after, &version),
+            ManifestKind::Pyproject => {
+                update_toml(&self.after, &PYPROJECT_VERSION_TABLES, &version)
+            }
+            ManifestKind::VersionFile => Ok(update_version_file(&self.after, &version)),
+        }
+        .map_err(|reason| self.error(reason))?;
+
+        return Ok(());
+    }
+
+    /// Replaces the version requirement of dependencies on the given crate in a Cargo
+    /// manifest. Other manifests are left as they are.
+    pub fn set_dependency_version(
+        &mut self,
+        dependency: &str,
+        version: &SemanticVersion,
+    ) -> Result<(), ManifestError> {
+        if ManifestKind::from(&self.path) != Some(ManifestKind::Cargo) {
+            return Ok(());
+        }
+
+        self.after = update_cargo_dependency(&self.after, dependency, &version.to_string())
+            .map_err(|reason| self.error(reason))?;
+
+        return Ok(());
+    }
+
+    pub fn is_changed(&self) -> bool {
+        self.before != self.after
+    }
+
+    /// Unified diff of the update, with the path shown relative to `root`. Versions are
+    /// replaced within lines, so every changed line gets its own hunk. Anything else, like
+    /// a section prepended to a changelog, is shown as a single hunk without context.
+    pub fn diff(&self, root: &Path) -> String {
+        let path = self.path.strip_prefix(root).unwrap_or(&self.path).display();
+        let mut diff = format!("--- {}\n+++ {}\n", path, path);
+        let before: Vec<&str> = self.before.lines().collect();
+        let after: Vec<&str> = self.after.lines().collect();
+
+        if before.len() == after.len() {
+            for (index, (old, new)) in before.iter().zip(after.iter()).enumerate() {
+                if old != new {
+                    let line = index + 1;
+                    diff.push_str(&format!(
+                        "@@ -{},1 +{},1 @@\n-{}\n+{}\n",
+                        line, line, old, new
+                    ));
+                }
+            }
+
+            return diff;
+        }
+
+        let prefix = before
+            .iter()
+            .zip(after.iter())
+            .take_while(|(old, new)| old == new)
+            .count();
+        let suffix = before[prefix..]
+            .iter()
+            .rev()
+            .zip(after[prefix..].iter().rev())
+            .take_while(|(old, new)| old == new)
+            .count();
+        let removed = &before[prefix..before.len() - suffix];
+        let added = &after[prefix..after.len() - suffix];
+
+        diff.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(prefix, removed.len()),
+            hunk_range(prefix, added.len())
+        ));
+        removed
+            .iter()
+            .for_each(|line| diff.push_str(&format!("-{}\n", line)));
+        added
+            .iter()
+            .for_each(|line| diff.push_str(&format!("+{}\n", line)));
+
+        return diff;
+    }
+
+    pub fn write(&self) -> Result<(), ManifestError> {
+        return fs::write(&self.path, &self.after).map_err(|e| self.error(e.to_string()));
+    }
+
+    fn error(&self, reason: String) -> ManifestError {
+        ManifestError {
+            path: self.path.display().to_string(),
+            reason,
+        }
+    }
+}
+
+/// Range of a hunk starting after `skipped` lines. Empty ranges point at the line before.
+fn hunk_range(skipped: usize, length: usize) -> String {
+    match length {
+        0 => format!("{},0", skipped),
+        _ => format!("{},{}", skipped + 1, length),
+    }
+}
+
+/// Known manifests in `root`, followed by manifests of Cargo workspace members.
+pub fn find_manifests(root: &Path) -> Result<Vec<PathBuf>, ManifestError> {
+    let mut manifests: Vec<PathBuf> = find_package_manifests(root);
+
+    let packages = cargo_packages(root).map_err(|e| ManifestError {
+        path: e.path,
+        reason: e.reason,
+    })?;
+    for package in packages.iter().filter(|package| !package.path.is_empty()) {
+        manifests.push(root.join(&package.path).join("Cargo.toml"));
+    }
+
+    return Ok(manifests);
+}
+
+/// Known manifests directly in the package directory.
+pub fn find_package_manifests(directory: &Path) -> Vec<PathBuf> {
+    MANIFEST_FILE_NAMES
+        .iter()
+        .map(|name| directory.join(name))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// Reads the manifest at `path` and computes its content with the version replaced.
+pub fn update_manifest(
+    path: &Path,
+    version: &SemanticVersion,
+) -> Result<ManifestUpdate, ManifestError> {
+    let mut update = ManifestUpdate::read(path)?;
+    update.set_version(version)?;
+
+    return Ok(update);
+}
+
+/// Updates the version of every manifest, as `update_manifest` does. Cargo manifests also
+/// get the requirement of dependencies on members of the workspace in `root` updated, so
+/// a single-version workspace keeps depending on its own members after a major release.
+pub fn update_manifests(
+    root: &Path,
+    manifests: &[PathBuf],
+    version: &SemanticVersion,
+) -> Result<Vec<ManifestUpdate>, ManifestError> {
+    let packages = cargo_packages(root).map_err(|e| ManifestError {
+        path: e.path,
+        reason: e.reason,
+    })?;
+
+    let mut updates: Vec<ManifestUpdate> = vec![];
+    for manifest in manifests {
+        let mut update = update_manifest(manifest, version)?;
+        for package in &packages {
+            update.set_dependency_version(&package.name, version)?;
+        }
+        updates.push(update);
+    }
+
+    return Ok(updates);
+}
+
+/// Sets `version` in every table at the given paths that has a string version.
+fn update_toml(content: &str, tables: &[&[&str]], version: &str) -> Result<String, String> {
+    let mut document = content
+        .parse::<DocumentMut>()
+        .map_err(|e| e.message().to_string())?;
+
+    for path in tables {
+        let mut table: Option<&mut dyn TableLike> = Some(document.as_table_mut());
+        for key in path.iter() {
+            table = table
+                .and_then(|table| table.get_mut(key))
+                .and_then(|item| item.as_table_like_mut());
+        }
+
+        // `version.workspace = true` is a table rather than a value, so it's left alone
+        let value = table
+            .and_then(|table| table.get_mut("version"))
+            .and_then(|item| item.as_value_mut())
+            .filter(|value| value.is_str());
+        if let Some(value) = value {
+            replace_string(value, version);
+        }
+    }
+
+    return Ok(document.to_string());
+}
+
+/// Sets `version` of detailed dependencies on `name`, in all dependency tables.
+fn update_cargo_dependency(content: &str, name: &str, version: &str) -> Result<String, String> {
+    let mut document = content
+        .parse::<DocumentMut>()
+        .map_err(|e| e.message().to_string())?;
+
+    let root = document.as_table_mut();
+    update_dependency_tables(root, name, version);
+    if let Some(workspace) = root
+        .get_mut("workspace")
+        .and_then(|item| item.as_table_like_mut())
+    {
+        update_dependency_tables(workspace, name, version);
+    }
+    if let Some(targets) = root
+        .get_mut("target")
+        .and_then(|item| item.as_table_like_mut())
+    {
+        for (_, target) in targets.iter_mut() {
+            if let Some(target) = target.as_table_like_mut() {
+                update_dependency_tables(target, name, version);
+            }
+        }
+    }
+
+    return Ok(document.to_string());
+}
+
+/// Updates dependencies on `name` in `dependencies`, `dev-dependencies` and
+/// `build-dependencies` of the table.
+fn update_dependency_tables(table: &mut dyn TableLike, name: &str, version: &str) {
+    for (key, dependencies) in table.iter_mut() {
+        let dependencies = match dependencies.as_table_like_mut() {
+            Some(dependencies) if key.get().ends_with("dependencies") => dependencies,
+            _ => continue,
+        };
+
+        for (key, dependency) in dependencies.iter_mut() {
+            let dependency = match dependency.as_table_like_mut() {
+                Some(dependency) => dependency,
+                // plain version requirements refer to the registry
+                None => continue,
+            };
+
+            let crate_name = dependency
+                .get("package")
+                .and_then(|package| package.as_str())
+                .unwrap_or(key.get());
+            if crate_name != name {
+                continue;
+            }
+
+            let value = dependency
+                .get_mut("version")
+                .and_then(|item| item.as_value_mut())
+                .filter(|value| value.is_str());
+            if let Some(value) = value {
+                replace_string(value, version);
+            }
+        }
+    }
+}
+
+/// Replaces a TOML value with a string, keeping whitespace and comments around it.
+fn replace_string(value: &mut Value, text: &str) {
+    let decor = value.decor().clone();
+    *value = Value::from(text);
+    *value.decor_mut() = decor;
+}
+
+/// Replaces the string value of the top level `version` key, leaving the rest untouched.
+fn update_package_json(content: &str, version: &str) -> Result<String, String> {
+    let bytes = content.as_bytes();
+    let mut depth = 0;
+    let mut position = 0;
+
+    while position < bytes.len() {
+        match bytes[position] {
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => depth -= 1,
+            b'"' => {
+                let end = string_end(bytes, position)?;
+                let next = skip_whitespace(bytes, end);
+                let is_key = bytes.get(next) == Some(&b':');
+                if depth == 1 && is_key && &content[position + 1..end - 1] == "version" {
+                    let start = skip_whitespace(bytes, next + 1);
+                    if bytes.get(start) != Some(&b'"') {
+                        return Err(String::from("Version is not a string"));
+                    }
+
+                    let end = string_end(bytes, start)?;
+                    return Ok(format!(
+                        "{}\"{}\"{}",
+                        &content[..start],
+                        version,
+                        &content[end..]
+                    ));
+                }
+
+                position = end;
+                continue;
+            }
+            _ => {}
+        }
+
+        position += 1;
+    }
+
+    return Ok(String::from(content));
+}
+
+/// Index right after the JSON string starting at `start`.
+fn string_end(bytes: &[u8], start: usize) -> Result<usize, String> {
+    let mut position = start + 1;
+    while position < bytes.len() {
+        match bytes[position] {
+            b'\\' => position += 2,
+            b'"' => return Ok(position + 1),
+            _ => position += 1,
+        }
+    }
+
+    return Err(String::from("Unterminated string"));
+}
+
+fn skip_whitespace(bytes: &[u8], start: usize) -> usize {
+    let mut position = start;
+    while position < bytes.len() && bytes[position].is_ascii_whitespace() {
+        position += 1;
+    }
+
+    return position;
+}
+
+/// Replaces the version, keeping surrounding whitespace and a `v` prefix.
+fn update_version_file(content: &str, version: &str) -> String {
+    let current = content.trim();
+    if current.is_empty() {
+        return format!("{}\n", version);
+    }
+
+    let start = content.len() - content.trim_start().len();
+    let prefix = match current.starts_with('v') {
+        true => "v",
+        false => "",
+    };
+
+    return format!(
+        "{}{}{}{}",
+        &content[..start],
+        prefix,
+        version,
+        &content[start + current.len()..]
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::semantic_version::SemanticVersion;
+    use crate::manifest::{
+        find_manifests, update_manifest, update_manifests, update_package_json, update_toml,
+        update_version_file, ManifestUpdate, CARGO_VERSION_TABLES, PYPROJECT_VERSION_TABLES,
+    };
+    use crate::workspace::tests::{create_workspace, write};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn should_update_cargo_package_version_preserving_formatting() {
+        // given
+        let content = r#"[package]
+name = "my-lib"
+version    = "1.2.3" # bumped by releases
+edition = "2021"
+
+[dependencies]
+serde = { version = "1.0" }
+"#;
+
+        // when
+        let actual = update_toml(content, &CARGO_VERSION_TABLES, "1.3.0").unwrap();
+
+        // then
+        assert_eq!(
+            actual,
+            content.replace("\"1.2.3\" # bumped", "\"1.3.0\" # bumped")
+        );
+    }
+
+    #[test]
+    fn should_update_workspace_version_and_leave_inherited_versions_alone() {
+        // given
+        let workspace =
+            "[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.package]\nversion = \"0.4.0\"\n";
+        let member = "[package]\nname = \"core\"\nversion.workspace = true\n";
+        let inline = "[package]\nname = \"api\"\nversion = { workspace = true }\n";
+
+        // then
+        assert_eq!(
+            update_toml(workspace, &CARGO_VERSION_TABLES, "0.5.0").unwrap(),
+            workspace.replace("0.4.0", "0.5.0")
+        );
+        assert_eq!(
+            update_toml(member, &CARGO_VERSION_TABLES, "0.5.0").unwrap(),
+            member
+        );
+        assert_eq!(
+            update_toml(inline, &CARGO_VERSION_TABLES, "0.5.0").unwrap(),
+            inline
+        );
+    }
+
+    #[test]
+    fn should_update_requirements_of_path_dependencies() {
+        // given
+        let content = r#"[workspace.dependencies]
+core = { path = "crates/core", version = "1.0.0" }
+
+[dependencies]
+core = { workspace = true }
+my-core = { package = "core", path = "../core", version = "1.0" } # renamed
+serde = "1.0"
+
+[target.'cfg(unix)'.dev-dependencies.core]
+path = "../core"
+version = "1.0.0"
+"#;
+        let mut update = ManifestUpdate {
+            path: PathBuf::from("Cargo.toml"),
+            before: String::from(content),
+            after: String::from(content),
+        };
+
+        // when
+        update
+            .set_dependency_version("core", &SemanticVersion::new(1, 1, 0, None, None))
+            .unwrap();
+
+        // then
+        assert_eq!(
+            update.after,
+            content
+                .replace("version = \"1.0.0\"", "version = \"1.1.0\"")
+                .replace("version = \"1.0\" }", "version = \"1.1.0\" }")
+        );
+    }
+
+    #[test]
+    fn should_update_pyproject_versions() {
+        // given
+        let content =
+            "[project]\nname = \"tool\"\nversion = \"1.0.0\"\n\n[tool.poetry]\nversion = '1.0.0'\n";
+
+        // when
+        let actual = update_toml(content, &PYPROJECT_VERSION_TABLES, "2.0.0-rc.1");
+
+        // then
+        assert_eq!(
+            actual.unwrap(),
+            "[project]\nname = \"tool\"\nversion = \"2.0.0-rc.1\"\n\n[tool.poetry]\nversion = \"2.0.0-rc.1\"\n"
+        );
+    }
+
+    #[test]
+    fn should_update_only_top_level_version_in_package_json() {
+        // given
+        let content = r#"{
+  "name": "app",
+  "engines": { "version": "18" },
+  "description": "says \"version\": \"0\"",
+  "version":"1.2.3",
+  "dependencies": {}
+}
+"#;
+
+        // when
+        let actual = update_package_json(content, "1.3.0").unwrap();
+
+        // then
+        assert_eq!(actual, content.replace("\"1.2.3\"", "\"1.3.0\""));
+        assert_eq!(update_package_json("{}", "1.3.0").unwrap(), "{}");
+        assert!(update_package_json("{\"version\": 1}", "1.3.0").is_err());
+    }
+
+    #[test]
+    fn should_update_version_file_keeping_prefix_and_newline() {
+        assert_eq!(update_version_file("1.2.3\n", "1.3.0"), "1.3.0\n");
+        assert_eq!(update_version_file("v1.2.3", "1.3.0"), "v1.3.0");
+        assert_eq!(update_version_file("", "1.3.0"), "1.3.0\n");
+    }
+
+    #[test]
+    fn should_render_diff_of_changed_lines() {
+        // given
+        let update = ManifestUpdate {
+            path: PathBuf::from("/project/Cargo.toml"),
+            before: String::from("[package]\nversion = \"1.2.3\"\n"),
+            after: String::from("[package]\nversion = \"1.3.0\"\n"),
+        };
+
+        // when
+        let diff = update.diff(Path::new("/project"));
+
+        // then
+        assert!(update.is_changed());
+        assert_eq!(
+            diff,
+            "--- Cargo.toml\n+++ Cargo.toml\n@@ -2,1 +2,1 @@\n-version = \"1.2.3\"\n+version = \"1.3.0\"\n"
+        );
+    }
+
+    #[test]
+    fn should_render_diff_of_prepended_section_as_single_hunk() {
+        // given
+        let changelog = ManifestUpdate {
+            path: PathBuf::from("/project/CHANGELOG.md"),
+            before: String::from("# Changelog\n\n## 1.0.0\n"),
+            after: String::from("# Changelog\n\n## 1.1.0\n\n## 1.0.0\n"),
+        };
+
+        // when
+        let diff = changelog.diff(Path::new("/project"));
+
+        // then
+        assert_eq!(
+            diff,
+            "--- CHANGELOG.md\n+++ CHANGELOG.md\n@@ -2,0 +3,2 @@\n+## 1.1.0\n+\n"
+        );
+    }
+
+    #[test]
+    fn should_find_and_update_manifests_of_workspace() {
+        // given
+        let root = create_workspace("manifests");
+        write(
+            &root,
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.package]\nversion = \"1.0.0\"\n",
+        );
+        write(
+            &root,
+            "crates/core/Cargo.toml",
+            "[package]\nname = \"core\"\nversion = \"1.0.0\"\n",
+        );
+        write(&root, "VERSION", "1.0.0\n");
+        write(&root, "README.md", "# Readme\n");
+        let version = SemanticVersion::new(1, 1, 0, None, None);
+
+        // when
+        let manifests = find_manifests(&root).unwrap();
+        for manifest in &manifests {
+            update_manifest(manifest, &version)
+                .unwrap()
+                .write()
+                .unwrap();
+        }
+
+        // then
+        assert_eq!(
+            manifests,
+            vec![
+                root.join("Cargo.toml"),
+                root.join("VERSION"),
+                root.join("crates/core/Cargo.toml"),
+            ]
+        );
+        assert_eq!(fs::read_to_string(root.join("VERSION")).unwrap(), "1.1.0\n");
+        assert!(fs::read_to_string(root.join("crates/core/Cargo.toml"))
+            .unwrap()
+            .contains("version = \"1.1.0\""));
+        assert!(update_manifest(&root.join("README.md"), &version).is_err());
+    }
+
+    #[test]
+    fn should_update_requirements_on_members_of_single_version_workspace() {
+        // given
+        let root = create_workspace("single-version");
+        write(
+            &root,
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.package]\nversion = \"1.4.0\"\n\n[workspace.dependencies]\ncore = { path = \"crates/core\", version = \"1.0\" }\n",
+        );
+        write(
+            &root,
+            "crates/core/Cargo.toml",
+            "[package]\nname = \"core\"\nversion.workspace = true\n",
+        );
+        write(
+            &root,
+            "crates/cli/Cargo.toml",
+            "[package]\nname = \"cli\"\nversion = \"1.4.0\"\n\n[dependencies]\ncore = { path = \"../core\", version = \"1.0\" }\nserde = \"1.0\"\n",
+        );
+        let version = SemanticVersion::new(2, 0, 0, None, None);
+
+        // when
+        let manifests = find_manifests(&root).unwrap();
+        let updates = update_manifests(&root, &manifests, &version).unwrap();
+
+        // then
+        assert_eq!(
+            updates[0].after,
+            "[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.package]\nversion = \"2.0.0\"\n\n[workspace.dependencies]\ncore = { path = \"crates/core\", version = \"2.0.0\" }\n"
+        );
+        let cli = updates
+            .iter()
+            .find(|update| update.path == root.join("crates/cli/Cargo.toml"))
+            .unwrap();
+        assert_eq!(
+            cli.after,
+            "[package]\nname = \"cli\"\nversion = \"2.0.0\"\n\n[dependencies]\ncore = { path = \"../core\", version = \"2.0.0\" }\nserde = \"1.0\"\n"
+        );
+    }
+}
@@ -0,0 +1,494 @@
+use crate::config::{Attribution, Config, ConfigError};
+use crate::core::history::HistoryEntry;
+use crate::core::semantic_version::PreReleaseType;
+use crate::git::{GitError, Repository, RevisionRange};
+use crate::manifest::{find_package_manifests, ManifestError, ManifestUpdate};
+use crate::release::{prepare_filtered_release, release_range, require_patch_release, Release};
+use crate::scope::ScopeResolver;
+use crate::workspace::{cargo_dependencies, cargo_packages, Package};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Packages of a repository released independently, with their own versions, changelogs
+/// and `<name>@<version>` tags.
+#[derive(Debug)]
+pub struct Monorepo {
+    pub packages: Vec<Package>,
+    /// Scopes attributing commits to a package in addition to its name, by package name.
+    scopes: HashMap<String, Vec<String>>,
+    /// Packages of the monorepo each package depends on, by package name.
+    dependencies: HashMap<String, Vec<String>>,
+    attribution: Attribution,
+    bump_dependents: bool,
+    /// Finds the package owning a path, the one with the longest path wins.
+    owners: ScopeResolver,
+}
+
+/// Release of a single package of a monorepo.
+#[derive(Debug)]
+pub struct PackageRelease {
+    pub package: Package,
+    pub release: Release,
+}
+
+/// Prefix of release tags of the package, e.g. `core@` for `core@1.2.3`.
+pub fn tag_prefix(package: &Package) -> String {
+    format!("{}@", package.name)
+}
+
+impl Monorepo {
+    pub fn new(
+        packages: Vec<Package>,
+        scopes: HashMap<String, Vec<String>>,
+        dependencies: HashMap<String, Vec<String>>,
+        attribution: Attribution,
+        bump_dependents: bool,
+    ) -> Self {
+        let owners = ScopeResolver::new(vec![], packages.clone());
+
+        return Self {
+            packages,
+            scopes,
+            dependencies,
+            attribution,
+            bump_dependents,
+            owners,
+        };
+    }
+
+    /// Packages configured in `[monorepo]`, with paths relative to the repository `root`,
+    /// or `None` when the repository isn't a monorepo.
+    pub fn from(config: &Config, root: &Path) -> Result<Option<Self>, ConfigError> {
+        let monorepo = &config.monorepo;
+        if monorepo.packages.is_empty() && !monorepo.workspace {
+            return Ok(None);
+        }
+
+        let mut packages: Vec<Package> = monorepo
+            .packages
+            .iter()
+            .map(|package| Package {
+                name: package.name.clone(),
+                path: String::from(package.path.trim_start_matches("./").trim_matches('/')),
+            })
+            .collect();
+        if monorepo.workspace {
+            for package in cargo_packages(root)? {
+                if !packages.iter().any(|p| p.name == package.name) {
+                    packages.push(package);
+                }
+            }
+        }
+
+        let scopes: HashMap<String, Vec<String>> = monorepo
+            .packages
+            .iter()
+            .map(|package| (package.name.clone(), package.scopes.clone()))
+            .collect();
+
+        let mut dependencies: HashMap<String, Vec<String>> = HashMap::new();
+        for package in &packages {
+            let names: Vec<String> = cargo_dependencies(root, package)?
+                .into_iter()
+                .filter(|name| packages.iter().any(|p| &p.name == name))
+                .collect();
+            dependencies.insert(package.name.clone(), names);
+        }
+
+        return Ok(Some(Self::new(
+            packages,
+            scopes,
+            dependencies,
+            monorepo.attribution,
+            monorepo.bump_dependents,
+        )));
+    }
+
+    /// Whether the commit, changing the given paths, belongs to the package.
+    pub fn is_attributed(&self, package: &Package, entry: &HistoryEntry, paths: &[String]) -> bool {
+        let by_scope = || {
+            let extra_scopes = self.scopes.get(&package.name);
+            entry.commit.scopes.as_ref().is_some_and(|scopes| {
+                scopes.iter().any(|scope| {
+                    scope == &package.name || extra_scopes.is_some_and(|s| s.contains(scope))
+                })
+            })
+        };
+        let by_files = || {
+            paths
+                .iter()
+                .any(|path| self.owners.resolve(path) == Some(package.name.as_str()))
+        };
+
+        match self.attribution {
+            Attribution::Files => by_files(),
+            Attribution::Scope => by_scope(),
+            Attribution::FilesOrScope => by_scope() || by_files(),
+        }
+    }
+
+    /// Package released with tags of the prefix, e.g. `core` for `core@`.
+    pub fn find_package(&self, prefix: &str) -> Option<&Package> {
+        self.packages
+            .iter()
+            .find(|package| tag_prefix(package) == prefix)
+    }
+
+    /// Accepts history entries attributed to the package, see [Monorepo::is_attributed].
+    /// Paths changed by commits in `range` are read at once, those of other commits one by one.
+    pub fn package_filter<'a>(
+        &'a self,
+        repository: &'a dyn Repository,
+        package: &'a Package,
+        range: &RevisionRange,
+    ) -> Result<impl FnMut(&HistoryEntry) -> Result<bool, GitError> + 'a, GitError> {
+        let mut changed_paths: HashMap<String, Vec<String>> = match self.attribution {
+            Attribution::Scope => HashMap::new(),
+            _ => repository.changed_paths_in_range(range)?,
+        };
+
+        return Ok(move |entry: &HistoryEntry| {
+            if self.attribution != Attribution::Scope && !changed_paths.contains_key(&entry.id) {
+                let paths = repository.changed_paths(&entry.id)?;
+                changed_paths.insert(entry.id.clone(), paths);
+            }
+
+            let paths = changed_paths.get(&entry.id).map(Vec::as_slice);
+            return Ok(self.is_attributed(package, entry, paths.unwrap_or_default()));
+        });
+    }
+
+    /// Computes the next version of every package from commits attributed to it. Packages
+    /// depending on released packages get at least a patch release, unless disabled.
+    pub fn prepare_releases(
+        &self,
+        repository: &dyn Repository,
+        channel: Option<&PreReleaseType>,
+    ) -> Result<Vec<PackageRelease>, GitError> {
+        let mut releases: Vec<PackageRelease> = vec![];
+
+        for package in &self.packages {
+            let prefix = tag_prefix(package);
+            let range = release_range(repository, &prefix)?;
+            let mut include = self.package_filter(repository, package, &range)?;
+
+            let release = prepare_filtered_release(repository, &prefix, channel, &mut include)?;
+            releases.push(PackageRelease {
+                package: package.clone(),
+                release,
+            });
+        }
+
+        if self.bump_dependents {
+            self.release_dependents(repository, &mut releases, channel)?;
+        }
+
+        return Ok(releases);
+    }
+
+    /// Records released dependencies of every package, giving packages without changes of
+    /// their own a patch release. Repeated until dependents of dependents are released too.
+    fn release_dependents(
+        &self,
+        repository: &dyn Repository,
+        releases: &mut [PackageRelease],
+        channel: Option<&PreReleaseType>,
+    ) -> Result<(), GitError> {
+        loop {
+            let released: HashMap<String, String> = releases
+                .iter()
+                .filter(|package_release| package_release.release.has_changes())
+                .map(|package_release| {
+                    (
+                        package_release.package.name.clone(),
+                        package_release.release.tag_name.clone(),
+                    )
+                })
+                .collect();
+
+            let mut changed = false;
+            for package_release in releases.iter_mut() {
+                let dependencies = self.dependencies.get(&package_release.package.name);
+                for dependency in dependencies.into_iter().flatten() {
+                    let tag_name = match released.get(dependency) {
+                        Some(tag_name) => tag_name,
+                        None => continue,
+                    };
+                    if package_release.release.dependencies.contains(tag_name) {
+                        continue;
+                    }
+
+                    package_release.release.dependencies.push(tag_name.clone());
+                    require_patch_release(
+                        repository,
+                        &mut package_release.release,
+                        &tag_prefix(&package_release.package),
+                        channel,
+                    )?;
+                    changed = true;
+                }
+            }
+
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Changes to manifests for the released packages: versions of the packages themselves
+    /// and version requirements of packages depending on them.
+    pub fn update_manifests(
+        &self,
+        root: &Path,
+        releases: &[PackageRelease],
+    ) -> Result<Vec<ManifestUpdate>, ManifestError> {
+        let mut updates: Vec<ManifestUpdate> = vec![];
+        let released: Vec<&PackageRelease> = releases
+            .iter()
+            .filter(|package_release| package_release.release.has_changes())
+            .collect();
+
+        for package_release in &released {
+            let directory = root.join(&package_release.package.path);
+            for path in find_package_manifests(&directory) {
+                get_update(&mut updates, &path)?.set_version(&package_release.release.version)?;
+            }
+        }
+
+        let mut cargo_manifests: Vec<PathBuf> = self
+            .packages
+            .iter()
+            .map(|package| root.join(&package.path).join("Cargo.toml"))
+            .collect();
+        cargo_manifests.push(root.join("Cargo.toml"));
+        for path in cargo_manifests.iter().filter(|path| path.is_file()) {
+            for package_release in &released {
+                get_update(&mut updates, path)?.set_dependency_version(
+                    &package_release.package.name,
+                    &package_release.release.version,
+                )?;
+            }
+        }
+
+        return Ok(updates
+            .into_iter()
+            .filter(|update| update.is_changed())
+            .collect());
+    }
+}
+
+/// Pending update of the file at `path`, reading the file on first use.
+fn get_update<'a>(
+    updates: &'a mut Vec<ManifestUpdate>,
+    path: &Path,
+) -> Result<&'a mut ManifestUpdate, ManifestError> {
+    let position = match updates.iter().position(|update| update.path == path) {
+        Some(position) => position,
+        None => {
+            updates.push(ManifestUpdate::read(path)?);
+            updates.len() - 1
+        }
+    };
+
+    return Ok(&mut updates[position]);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{Attribution, Config};
+    use crate::core::history::tests::commit;
+    use crate::core::history::HistoryEntry;
+    use crate::core::semantic_version::SemanticVersion;
+    use crate::git::cli::tests::{commit_file, create_repository, git};
+    use crate::git::cli::CliRepository;
+    use crate::git::RevisionRange;
+    use crate::monorepo::Monorepo;
+    use crate::release::find_filtered_releases;
+    use crate::release::notes::prepare_filtered_range_release;
+    use crate::workspace::tests::write;
+    use crate::workspace::Package;
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::Path;
+
+    fn package(name: &str, path: &str) -> Package {
+        Package {
+            name: String::from(name),
+            path: String::from(path),
+        }
+    }
+
+    fn monorepo(attribution: Attribution) -> Monorepo {
+        Monorepo::new(
+            vec![package("core", "crates/core"), package("web", "web")],
+            HashMap::from([(String::from("web"), vec![String::from("ui")])]),
+            HashMap::new(),
+            attribution,
+            true,
+        )
+    }
+
+    /// Cargo workspace with `app` depending on `core`, and unrelated `tool`.
+    fn create_workspace(name: &str) -> std::path::PathBuf {
+        let root = create_repository(name);
+        write(
+            &root,
+            ".conventional-commits.toml",
+            "[monorepo]\nworkspace = true\n",
+        );
+        write(
+            &root,
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.dependencies]\ncore = { path = \"crates/core\", version = \"1.0.0\" }\n",
+        );
+        write(
+            &root,
+            "crates/core/Cargo.toml",
+            "[package]\nname = \"core\"\nversion = \"1.0.0\"\n",
+        );
+        write(
+            &root,
+            "crates/app/Cargo.toml",
+            "[package]\nname = \"app\"\nversion = \"0.3.0\"\n\n[dependencies]\ncore = { workspace = true }\n",
+        );
+        write(
+            &root,
+            "crates/tool/Cargo.toml",
+            "[package]\nname = \"tool\"\nversion = \"2.0.0\"\n",
+        );
+        git(&root, &["add", "."]);
+        git(&root, &["commit", "-q", "-m", "chore: init"]);
+        for tag in ["core@1.0.0", "app@0.3.0", "tool@2.0.0"] {
+            git(&root, &["tag", tag]);
+        }
+
+        return root;
+    }
+
+    #[test]
+    fn should_attribute_commits_by_files_and_scope() {
+        // given
+        let entry = |message: &str| HistoryEntry::from(&commit("a", message)).unwrap();
+        let core = package("core", "crates/core");
+        let web = package("web", "web");
+        let core_paths = vec![String::from("crates/core/src/lib.rs")];
+
+        // when
+        let files = monorepo(Attribution::Files);
+        let scope = monorepo(Attribution::Scope);
+        let any = monorepo(Attribution::FilesOrScope);
+
+        // then
+        assert!(files.is_attributed(&core, &entry("fix: crash"), &core_paths));
+        assert!(!files.is_attributed(&web, &entry("fix(web): crash"), &core_paths));
+        assert!(scope.is_attributed(&web, &entry("fix(web): crash"), &core_paths));
+        assert!(scope.is_attributed(&web, &entry("fix(ui,api): crash"), &[]));
+        assert!(!scope.is_attributed(&core, &entry("fix: crash"), &core_paths));
+        assert!(any.is_attributed(&core, &entry("fix: crash"), &core_paths));
+        assert!(any.is_attributed(&web, &entry("fix(web): crash"), &core_paths));
+        assert!(!any.is_attributed(&web, &entry("fix: crash"), &core_paths));
+    }
+
+    #[test]
+    fn should_release_changed_packages_and_their_dependents() {
+        // given
+        let root = create_workspace("monorepo-release");
+        commit_file(&root, "crates/core/src/lib.rs", "feat: add api");
+        let repository = CliRepository::discover(&root).unwrap();
+        let config = Config::discover(&root).unwrap();
+        let monorepo = Monorepo::from(&config, &root).unwrap().unwrap();
+
+        // when
+        let releases = monorepo.prepare_releases(&repository, None).unwrap();
+
+        // then
+        let tags: Vec<(&str, bool)> = releases
+            .iter()
+            .map(|r| (r.release.tag_name.as_str(), r.release.has_changes()))
+            .collect();
+        assert_eq!(
+            tags,
+            vec![
+                ("app@0.3.1", true),
+                ("core@1.1.0", true),
+                ("tool@2.0.0", false)
+            ]
+        );
+        assert_eq!(releases[0].release.dependencies, vec!["core@1.1.0"]);
+        assert!(releases[0].release.history.is_empty());
+    }
+
+    #[test]
+    fn should_read_package_history_of_attributed_commits_only() {
+        // given
+        let root = create_workspace("monorepo-history");
+        commit_file(&root, "crates/core/src/lib.rs", "feat: add api");
+        commit_file(&root, "crates/tool/src/main.rs", "fix: crash");
+        git(&root, &["tag", "core@1.1.0"]);
+        commit_file(&root, "crates/tool/src/main.rs", "feat: new option");
+        let repository = CliRepository::discover(&root).unwrap();
+        let config = Config::discover(&root).unwrap();
+        let monorepo = Monorepo::from(&config, &root).unwrap().unwrap();
+        let core = monorepo.find_package("core@").unwrap();
+        let mut include = monorepo
+            .package_filter(&repository, core, &RevisionRange::default())
+            .unwrap();
+
+        // when
+        let releases = find_filtered_releases(&repository, "core@", &mut include).unwrap();
+        let (unreleased, _) =
+            prepare_filtered_range_release(&repository, "core@", None, "HEAD", &mut include)
+                .unwrap();
+
+        // then
+        assert_eq!(releases[0].0.tag_name, "core@1.1.0");
+        let descriptions: Vec<&str> = releases[0]
+            .0
+            .history
+            .entries
+            .iter()
+            .map(|entry| entry.commit.description.as_str())
+            .collect();
+        assert_eq!(descriptions, vec!["add api"]);
+        assert!(unreleased.history.entries.is_empty());
+        assert!(monorepo.find_package("v").is_none());
+    }
+
+    #[test]
+    fn should_update_versions_and_requirements_of_released_packages() {
+        // given
+        let root = create_workspace("monorepo-manifests");
+        commit_file(&root, "crates/core/src/lib.rs", "feat: add api");
+        let repository = CliRepository::discover(&root).unwrap();
+        let config = Config::discover(&root).unwrap();
+        let monorepo = Monorepo::from(&config, &root).unwrap().unwrap();
+        let releases = monorepo.prepare_releases(&repository, None).unwrap();
+
+        // when
+        let updates = monorepo.update_manifests(&root, &releases).unwrap();
+        updates.iter().for_each(|update| update.write().unwrap());
+
+        // then
+        let read = |path: &str| fs::read_to_string(root.join(path)).unwrap();
+        let paths: Vec<&Path> = updates
+            .iter()
+            .map(|update| update.path.strip_prefix(&root).unwrap())
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                Path::new("crates/app/Cargo.toml"),
+                Path::new("crates/core/Cargo.toml"),
+                Path::new("Cargo.toml"),
+            ]
+        );
+        assert!(read("crates/app/Cargo.toml").contains("version = \"0.3.1\""));
+        assert!(read("crates/core/Cargo.toml").contains("version = \"1.1.0\""));
+        assert!(read("Cargo.toml").contains("version = \"1.1.0\""));
+        assert!(read("crates/tool/Cargo.toml").contains("version = \"2.0.0\""));
+        assert_eq!(
+            releases[1].release.version,
+            SemanticVersion::new(1, 1, 0, None, None)
+        );
+    }
+}
@@ -0,0 +1,39 @@
+use con_comm::config::Config;
+use con_comm::git;
+use con_comm::overrides::{add_override, process_args, OverrideArgs};
+use std::{env, fs};
+
+/*
+Replaces the message of a commit that is already published and can't be rewritten, e.g.
+`override-message abc123 -m "fix(api): handle empty body"`, or `-F <file>` to read it from
+a file. The message is linted first, and stored as a git note under `history.notes_ref`,
+`refs/notes/conventional` by default, which other tools read instead of the original message.
+Notes are pushed like any other reference, e.g. `git push origin refs/notes/conventional`.
+*/
+fn main() {
+    let args: OverrideArgs = process_args(&env::args().collect::<Vec<String>>());
+
+    let message = match (&args.message, &args.file) {
+        (Some(message), _) => message.clone(),
+        (None, Some(path)) => fs::read_to_string(path)
+            .unwrap_or_else(|e| panic!("Couldn't read message from {}: {}", path, e)),
+        (None, None) => unreachable!("arguments require a message or a file"),
+    };
+
+    let current_dir = env::current_dir()
+        .unwrap_or_else(|e| panic!("Couldn't determine current directory: {}", e));
+    let config = Config::discover(&current_dir)
+        .unwrap_or_else(|e| panic!("Couldn't load configuration: {}", e));
+    let repository =
+        git::open(&current_dir).unwrap_or_else(|e| panic!("Couldn't open repository: {}", e));
+
+    let violations = add_override(repository.as_ref(), &config, &args.revision, &message)
+        .unwrap_or_else(|e| panic!("Couldn't store message override: {}", e));
+    if !violations.is_empty() {
+        let reasons: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+        panic!(
+            "Commit message does not match proper format: {}",
+            reasons.join("; ")
+        );
+    }
+}
@@ -0,0 +1,124 @@
+use crate::config::Config;
+use crate::git::{GitError, Repository};
+use crate::lint::{lint, Violation};
+
+#[derive(Debug, PartialEq)]
+pub struct OverrideArgs {
+    pub revision: String,
+    pub message: Option<String>,
+    /// Read the message from this file instead.
+    pub file: Option<String>,
+}
+
+pub fn process_args(args: &[String]) -> OverrideArgs {
+    let mut revision: Option<String> = None;
+    let mut message: Option<String> = None;
+    let mut file: Option<String> = None;
+
+    let mut iterator = args.iter().skip(1);
+    while let Some(arg) = iterator.next() {
+        match arg.as_str() {
+            "-m" | "--message" => match iterator.next() {
+                Some(value) => message = Some(value.clone()),
+                None => panic!("Missing message after {}", arg),
+            },
+            "-F" | "--file" => match iterator.next() {
+                Some(path) => file = Some(path.clone()),
+                None => panic!("Missing path after {}", arg),
+            },
+            _ if arg.starts_with('-') => panic!("Unknown argument: {}", arg),
+            _ if revision.is_none() => revision = Some(arg.clone()),
+            _ => panic!("Unexpected argument: {}", arg),
+        }
+    }
+
+    if message.is_some() == file.is_some() {
+        panic!("Either a message (-m) or a file (-F) is required");
+    }
+
+    return OverrideArgs {
+        revision: revision.unwrap_or_else(|| panic!("Missing revision of the commit")),
+        message,
+        file,
+    };
+}
+
+/// Stores the message as a replacement of the commit's message in the notes reference of
+/// the `[history]` configuration. Messages violating the configured rules are not stored,
+/// and their violations are returned instead.
+pub fn add_override(
+    repository: &dyn Repository,
+    config: &Config,
+    revision: &str,
+    message: &str,
+) -> Result<Vec<Violation>, GitError> {
+    let notes_ref = &config.history.notes_ref;
+    if notes_ref.is_empty() {
+        return Err(GitError {
+            reason: String::from("Message overrides are disabled with an empty history.notes_ref"),
+        });
+    }
+
+    let violations = lint(message, config);
+    if violations.is_empty() {
+        repository.add_note(notes_ref, revision, message)?;
+    }
+
+    return Ok(violations);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+    use crate::git::cli::tests::{commit_file, create_repository};
+    use crate::git::cli::CliRepository;
+    use crate::git::Repository;
+    use crate::overrides::{add_override, process_args, OverrideArgs};
+
+    #[test]
+    fn should_process_args() {
+        // given
+        let args: Vec<String> = ["override-message", "abc123", "-m", "fix: crash"]
+            .iter()
+            .map(|arg| String::from(*arg))
+            .collect();
+
+        // when
+        let args = process_args(&args);
+
+        // then
+        assert_eq!(
+            args,
+            OverrideArgs {
+                revision: String::from("abc123"),
+                message: Some(String::from("fix: crash")),
+                file: None,
+            }
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_require_either_message_or_file() {
+        process_args(&[String::from("override-message"), String::from("HEAD")]);
+    }
+
+    #[test]
+    fn should_store_valid_overrides_only() {
+        // given
+        let root = create_repository("overrides");
+        let id = commit_file(&root, "a.txt", "fixed things");
+        let repository = CliRepository::discover(&root).unwrap();
+        let config = Config::default();
+
+        // when
+        let invalid = add_override(&repository, &config, "HEAD", "fixed things").unwrap();
+        let valid = add_override(&repository, &config, "HEAD", "fix: crash on start").unwrap();
+
+        // then
+        assert!(!invalid.is_empty());
+        assert!(valid.is_empty());
+        let notes = repository.notes("refs/notes/conventional").unwrap();
+        assert_eq!(notes[&id], "fix: crash on start\n");
+    }
+}
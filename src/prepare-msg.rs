@@ -1,37 +1,60 @@
+use con_comm::config::Config;
+use con_comm::git;
 use con_comm::hooks::prepare_msg::{
-    can_use_template, get_template, process_args, PrepareMessageArgs,
+    get_template, get_template_action, prepare_message, process_args, PrepareMessageArgs,
+    TemplateAction, TemplateContext,
 };
-use std::env;
-use std::fs::File;
-use std::io::Write;
+use con_comm::scope::ScopeResolver;
+use std::{env, fs};
 
-fn main() -> () {
+fn main() {
     /*
     The `prepare-commit-msg` is executed before we see actual editor that lets us write commit message.
-    Our binary will receive three arguments: path to the file with initial commit message,
-    the type of the commit and commit SHA-1.
+    Our binary will receive up to three arguments: path to the file with initial commit message,
+    the source of the commit message and commit SHA-1.
     */
-    let args: PrepareMessageArgs = process_args(&env::args().collect());
+    let args: PrepareMessageArgs = process_args(&env::args().collect::<Vec<String>>());
 
-    if !can_use_template(&args) {
+    let action = get_template_action(&args);
+    if action == TemplateAction::Skip {
         return;
     }
 
-    let mut file = match File::create(&args.filename) {
-        Err(e) => panic!("Couldn't open file {}: {}", &args.filename, e),
-        Ok(file) => file,
+    let current_dir = env::current_dir()
+        .unwrap_or_else(|e| panic!("Couldn't determine current directory: {}", e));
+    let config = Config::discover(&current_dir)
+        .unwrap_or_else(|e| panic!("Couldn't load configuration: {}", e));
+    let template =
+        get_template(&config).unwrap_or_else(|e| panic!("Couldn't load template: {}", e));
+
+    // git always creates the file, but an empty message is a sane fallback
+    let current_content = fs::read_to_string(&args.filename).unwrap_or_default();
+    // the hook runs inside a repository, but the template is still useful without one
+    let repository = git::open(&current_dir).ok();
+    let resolver = ScopeResolver::from(&config)
+        .unwrap_or_else(|e| panic!("Couldn't prepare scope inference: {}", e));
+    let inferred_scopes = match (&repository, resolver.is_empty()) {
+        (Some(repository), false) => resolver.infer(&repository.staged_paths().unwrap_or_default()),
+        _ => vec![],
     };
 
-    if let Err(e) = file.write_all(get_template().as_bytes()) {
-        panic!("Couldn't write template to file {}: {}", &args.filename, e);
-    }
+    let branch = repository.and_then(|repository| repository.current_branch().ok().flatten());
+    let context = TemplateContext::from(branch, inferred_scopes, &config)
+        .unwrap_or_else(|e| panic!("Couldn't process branch name: {}", e));
 
-    let _ = file.flush();
+    let message = prepare_message(&action, &current_content, &template, &config, &context);
+    if let Some(message) = message {
+        if let Err(e) = fs::write(&args.filename, message) {
+            panic!("Couldn't write template to file {}: {}", &args.filename, e);
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use con_comm::hooks::prepare_msg::{can_use_template, process_args, PrepareMessageArgs};
+    use con_comm::hooks::prepare_msg::{
+        get_template_action, process_args, CommitSource, PrepareMessageArgs, TemplateAction,
+    };
     #[test]
     fn should_process_args_with_only_path_to_commit_file() {
         // given
@@ -43,12 +66,12 @@ mod tests {
         // then
         let expected: PrepareMessageArgs = PrepareMessageArgs {
             filename: String::from(".git/some/file"),
-            commit_type: None,
+            source: None,
             id: None,
         };
 
         assert_eq!(expected.filename, actual.filename);
-        assert_eq!(expected.commit_type, actual.commit_type);
+        assert_eq!(expected.source, actual.source);
         assert_eq!(expected.id, actual.id);
     }
 
@@ -68,12 +91,12 @@ mod tests {
         // then
         let expected: PrepareMessageArgs = PrepareMessageArgs {
             filename: String::from(".git/some/file"),
-            commit_type: Some(String::from("merge")),
+            source: Some(CommitSource::Merge),
             id: Some(String::from("head")),
         };
 
         assert_eq!(expected.filename, actual.filename);
-        assert_eq!(expected.commit_type, actual.commit_type);
+        assert_eq!(expected.source, actual.source);
         assert_eq!(expected.id, actual.id);
     }
 
@@ -81,7 +104,7 @@ mod tests {
     #[should_panic]
     fn should_panic_when_no_args_are_passed_to_process_args() {
         // given
-        let args = vec![];
+        let args: Vec<String> = vec![];
 
         // when
         process_args(&args);
@@ -98,34 +121,53 @@ mod tests {
     }
 
     #[test]
-    fn can_use_template_should_return_true_when_id_is_none() {
+    fn should_write_template_when_no_source_is_given() {
         // given
         let args: PrepareMessageArgs = PrepareMessageArgs {
             filename: String::from(".git/some/file"),
-            commit_type: None,
+            source: None,
             id: None,
         };
 
         // when
-        let actual = can_use_template(&args);
+        let actual = get_template_action(&args);
 
         // then
-        assert_eq!(true, actual);
+        assert_eq!(TemplateAction::Write, actual);
     }
 
     #[test]
-    fn can_use_template_should_return_false_when_id_is_set() {
+    fn should_expand_template_when_source_is_template() {
         // given
-        let args: PrepareMessageArgs = PrepareMessageArgs {
-            filename: String::from(".git/some/file"),
-            commit_type: Some(String::from("merge")),
-            id: Some(String::from("head")),
-        };
+        let args = process_args(&[
+            String::from("DontCare"),
+            String::from(".git/some/file"),
+            String::from("template"),
+        ]);
 
         // when
-        let actual = can_use_template(&args);
+        let actual = get_template_action(&args);
 
         // then
-        assert_eq!(false, actual);
+        assert_eq!(TemplateAction::Expand, actual);
+    }
+
+    #[test]
+    fn should_skip_template_for_message_merge_squash_and_commit_sources() {
+        for source in ["message", "merge", "squash", "commit", "unknown"] {
+            // given
+            let args = process_args(&[
+                String::from("DontCare"),
+                String::from(".git/some/file"),
+                String::from(source),
+                String::from("HEAD"),
+            ]);
+
+            // when
+            let actual = get_template_action(&args);
+
+            // then
+            assert_eq!(TemplateAction::Skip, actual, "source: {}", source);
+        }
     }
 }
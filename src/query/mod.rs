@@ -0,0 +1,370 @@
+use crate::core::history::{History, HistoryEntry};
+use crate::git::RevisionRange;
+use chrono::NaiveDate;
+
+#[derive(Debug)]
+pub struct LogArgs {
+    pub range: RevisionRange,
+    pub query: HistoryQuery,
+}
+
+pub fn process_args(args: &[String]) -> LogArgs {
+    let mut range: Option<RevisionRange> = None;
+    let mut since: Option<String> = None;
+    let mut until: Option<String> = None;
+    let mut query = HistoryQuery::default();
+
+    let mut iterator = args.iter().skip(1);
+    while let Some(arg) = iterator.next() {
+        let mut value = |name: &str| match iterator.next() {
+            Some(value) => value.clone(),
+            None => panic!("Missing value after {}", name),
+        };
+
+        match arg.as_str() {
+            "--type" => query.types.push(value(arg)),
+            "--scope" => query.scopes.push(value(arg)),
+            "--breaking" => query.breaking = Some(true),
+            "--not-breaking" => query.breaking = Some(false),
+            "--since" => since = Some(value(arg)),
+            "--until" => until = Some(value(arg)),
+            "--after" => query.after = Some(parse_date(&value(arg))),
+            "--before" => query.before = Some(parse_date(&value(arg))),
+            "--author" => query.authors.push(value(arg)),
+            "--trailer" => query.trailers.push(TrailerFilter::from(&value(arg))),
+            "--missing-trailer" => query.trailers.push(TrailerFilter::missing(&value(arg))),
+            _ if arg.starts_with("--") => panic!("Unknown argument: {}", arg),
+            _ => range = Some(RevisionRange::from(arg)),
+        }
+    }
+
+    // `--since` and `--until` complete a positional range, but can't contradict it
+    let mut range = match range {
+        Some(range) => {
+            if since.is_some() && range.from.is_some() {
+                panic!("Both a range and --since given, use only one of them");
+            }
+            if until.is_some() && range.to != "HEAD" {
+                panic!("Both a range and --until given, use only one of them");
+            }
+            range
+        }
+        None => RevisionRange::default(),
+    };
+    if let Some(since) = since {
+        range.from = Some(since);
+    }
+    if let Some(until) = until {
+        range.to = until;
+    }
+
+    return LogArgs { range, query };
+}
+
+fn parse_date(date: &str) -> NaiveDate {
+    return NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .unwrap_or_else(|_| panic!("Invalid date, expected YYYY-MM-DD: {}", date));
+}
+
+/// Conditions on conventional commits of a history. Every condition that is set has to hold,
+/// while a commit has to match only one of the values listed for a condition, e.g. one of
+/// the types.
+#[derive(Debug, Default, PartialEq)]
+pub struct HistoryQuery {
+    pub types: Vec<String>,
+    pub scopes: Vec<String>,
+    pub breaking: Option<bool>,
+    /// Earliest commit date, inclusive.
+    pub after: Option<NaiveDate>,
+    /// Latest commit date, inclusive.
+    pub before: Option<NaiveDate>,
+    /// Parts of author names or emails, matched ignoring case, like `git log --author`.
+    pub authors: Vec<String>,
+    pub trailers: Vec<TrailerFilter>,
+}
+
+/// Presence, absence or value of a trailer, with its key matched ignoring case.
+#[derive(Debug, PartialEq)]
+pub struct TrailerFilter {
+    pub key: String,
+    /// Required value, any value when `None`.
+    pub value: Option<String>,
+    /// Whether the commit must not have the trailer at all.
+    pub missing: bool,
+}
+
+impl TrailerFilter {
+    /// Parses `Key` or `Key=value`.
+    pub fn from(filter: &str) -> Self {
+        let (key, value) = match filter.split_once('=') {
+            Some((key, value)) => (key, Some(String::from(value))),
+            None => (filter, None),
+        };
+
+        return Self {
+            key: String::from(key),
+            value,
+            missing: false,
+        };
+    }
+
+    pub fn missing(key: &str) -> Self {
+        return Self {
+            key: String::from(key),
+            value: None,
+            missing: true,
+        };
+    }
+
+    pub fn matches(&self, entry: &HistoryEntry) -> bool {
+        let found = match &entry.commit.footer {
+            Some(footer) => footer.elements.iter().any(|element| {
+                element.key().eq_ignore_ascii_case(&self.key)
+                    && match &self.value {
+                        Some(value) => element.value() == value,
+                        None => true,
+                    }
+            }),
+            None => false,
+        };
+
+        return found != self.missing;
+    }
+}
+
+impl HistoryQuery {
+    pub fn is_empty(&self) -> bool {
+        self == &HistoryQuery::default()
+    }
+
+    pub fn matches(&self, entry: &HistoryEntry) -> bool {
+        let commit = &entry.commit;
+        if !self.types.is_empty() && !self.types.iter().any(|t| t == commit.commit_type.as_str()) {
+            return false;
+        }
+
+        let scopes = commit.scopes.as_deref().unwrap_or_default();
+        if !self.scopes.is_empty() && !scopes.iter().any(|scope| self.scopes.contains(scope)) {
+            return false;
+        }
+
+        if self
+            .breaking
+            .is_some_and(|breaking| breaking != commit.is_breaking_change)
+        {
+            return false;
+        }
+
+        let date = entry.timestamp.date_naive();
+        if self.after.is_some_and(|after| date < after)
+            || self.before.is_some_and(|before| date > before)
+        {
+            return false;
+        }
+
+        if !self.authors.is_empty() && !self.authors.iter().any(|author| is_author(entry, author)) {
+            return false;
+        }
+
+        return self.trailers.iter().all(|trailer| trailer.matches(entry));
+    }
+
+    /// Entries of the history matching the query, newest first.
+    pub fn apply<'a>(&self, history: &'a History) -> Vec<&'a HistoryEntry> {
+        return history
+            .entries
+            .iter()
+            .filter(|entry| self.matches(entry))
+            .collect();
+    }
+}
+
+fn is_author(entry: &HistoryEntry, author: &str) -> bool {
+    let author = author.to_lowercase();
+    return entry.author.name.to_lowercase().contains(&author)
+        || entry.author.email.to_lowercase().contains(&author);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::history::tests::commit;
+    use crate::core::history::History;
+    use crate::git::RevisionRange;
+    use crate::query::{process_args, HistoryQuery, TrailerFilter};
+    use chrono::NaiveDate;
+
+    fn history() -> History {
+        return History::from(&[
+            commit("e", "docs: readme"),
+            commit("d", "feat(api)!: drop v1\n\nReviewed-by: Z <z@example.com>"),
+            commit("c", "fix(api): crash\n\nRefs: #12"),
+            commit("b", "feat(cli): flag"),
+            commit("a", "not conventional"),
+        ]);
+    }
+
+    fn ids(history: &History, query: &HistoryQuery) -> Vec<String> {
+        return query
+            .apply(history)
+            .iter()
+            .map(|entry| entry.id.clone())
+            .collect();
+    }
+
+    #[test]
+    fn should_filter_by_type_scope_and_breaking_change() {
+        // given
+        let history = history();
+
+        // then
+        assert_eq!(
+            ids(&history, &HistoryQuery::default()),
+            vec!["e", "d", "c", "b"]
+        );
+        assert_eq!(
+            ids(
+                &history,
+                &HistoryQuery {
+                    types: vec![String::from("feat"), String::from("docs")],
+                    ..HistoryQuery::default()
+                }
+            ),
+            vec!["e", "d", "b"]
+        );
+        assert_eq!(
+            ids(
+                &history,
+                &HistoryQuery {
+                    scopes: vec![String::from("api")],
+                    breaking: Some(true),
+                    ..HistoryQuery::default()
+                }
+            ),
+            vec!["d"]
+        );
+    }
+
+    #[test]
+    fn should_filter_by_date_and_author() {
+        // given
+        let history = history();
+        let day = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+
+        // then
+        let query = HistoryQuery {
+            after: Some(day),
+            before: Some(day),
+            authors: vec![String::from("JANE@")],
+            ..HistoryQuery::default()
+        };
+        assert_eq!(ids(&history, &query).len(), 4);
+        let query = HistoryQuery {
+            after: day.succ_opt(),
+            ..HistoryQuery::default()
+        };
+        assert!(ids(&history, &query).is_empty());
+        let query = HistoryQuery {
+            authors: vec![String::from("john")],
+            ..HistoryQuery::default()
+        };
+        assert!(ids(&history, &query).is_empty());
+    }
+
+    #[test]
+    fn should_filter_by_trailers() {
+        // given
+        let history = history();
+        let query = |filter: TrailerFilter| HistoryQuery {
+            trailers: vec![filter],
+            ..HistoryQuery::default()
+        };
+
+        // then
+        assert_eq!(
+            ids(&history, &query(TrailerFilter::from("reviewed-by"))),
+            vec!["d"]
+        );
+        assert_eq!(
+            ids(&history, &query(TrailerFilter::from("Refs=#12"))),
+            vec!["c"]
+        );
+        assert!(ids(&history, &query(TrailerFilter::from("Refs=#13"))).is_empty());
+        assert_eq!(
+            ids(&history, &query(TrailerFilter::missing("Reviewed-by"))),
+            vec!["e", "c", "b"]
+        );
+    }
+
+    #[test]
+    fn should_process_args() {
+        // given
+        let args: Vec<String> = [
+            "log",
+            "--type",
+            "feat",
+            "--scope",
+            "api",
+            "--since",
+            "v2.0.0",
+            "--breaking",
+            "--missing-trailer",
+            "Reviewed-by",
+            "--after",
+            "2024-05-01",
+        ]
+        .iter()
+        .map(|arg| String::from(*arg))
+        .collect();
+
+        // when
+        let args = process_args(&args);
+
+        // then
+        assert_eq!(args.range, RevisionRange::new(Some("v2.0.0"), "HEAD"));
+        assert_eq!(
+            args.query,
+            HistoryQuery {
+                types: vec![String::from("feat")],
+                scopes: vec![String::from("api")],
+                breaking: Some(true),
+                after: NaiveDate::from_ymd_opt(2024, 5, 1),
+                trailers: vec![TrailerFilter::missing("Reviewed-by")],
+                ..HistoryQuery::default()
+            }
+        );
+        assert_eq!(
+            process_args(&[String::from("log"), String::from("v1.0.0..main")]).range,
+            RevisionRange::new(Some("v1.0.0"), "main")
+        );
+    }
+
+    #[test]
+    fn should_complete_positional_range_with_since_and_until() {
+        // given
+        let args = |args: &[&str]| {
+            let args: Vec<String> = args.iter().map(|arg| String::from(*arg)).collect();
+            return process_args(&args).range;
+        };
+
+        // then
+        assert_eq!(
+            args(&["log", "--since", "v2.0.0", "main"]),
+            RevisionRange::new(Some("v2.0.0"), "main")
+        );
+        assert_eq!(
+            args(&["log", "v2.0.0..", "--until", "main"]),
+            RevisionRange::new(Some("v2.0.0"), "main")
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Both a range and --since given")]
+    fn should_panic_for_range_contradicting_since() {
+        process_args(&[
+            String::from("log"),
+            String::from("v1.0.0..main"),
+            String::from("--since"),
+            String::from("v2.0.0"),
+        ]);
+    }
+}
@@ -0,0 +1,95 @@
+use con_comm::changelog::links::Links;
+use con_comm::changelog::ChangelogRenderer;
+use con_comm::config::Config;
+use con_comm::git;
+use con_comm::git::RevisionRange;
+use con_comm::monorepo::Monorepo;
+use con_comm::release::notes::{
+    prepare_filtered_range_release, prepare_range_release, process_args, ReleaseNotesArgs,
+};
+use std::env;
+use std::fs;
+
+/*
+Renders notes of a single release: the commits in `--from <rev>..--to <rev>`, with a summary
+of breaking changes, contributors and statistics. `--from` defaults to the latest final release
+before `--to`, which defaults to HEAD. The version is taken from `--to` when it is a release
+tag, and computed from the commits otherwise, on top of the latest final release contained in
+`--from`; without changes requiring a release, no notes are rendered. With `--plain`, the
+notes are plain text suitable as a tag message. With `--output <file>`, they are written to
+the file instead of the standard output.
+
+In a monorepo, a package's tag prefix, e.g. `--tag-prefix core@`, limits the notes to the
+commits attributed to that package.
+*/
+fn main() {
+    let args: ReleaseNotesArgs = process_args(&env::args().collect::<Vec<String>>());
+
+    let current_dir = env::current_dir()
+        .unwrap_or_else(|e| panic!("Couldn't determine current directory: {}", e));
+    let config = Config::discover(&current_dir)
+        .unwrap_or_else(|e| panic!("Couldn't load configuration: {}", e));
+    let options = config
+        .read_options()
+        .unwrap_or_else(|e| panic!("Couldn't load configuration: {}", e));
+    let repository = git::open_history(&current_dir, options)
+        .unwrap_or_else(|e| panic!("Couldn't open repository: {}", e));
+
+    let prefix = args
+        .tag_prefix
+        .as_ref()
+        .unwrap_or(&config.release.tag_prefix);
+    let monorepo = Monorepo::from(&config, repository.root())
+        .unwrap_or_else(|e| panic!("Couldn't read monorepo packages: {}", e));
+    let package = monorepo
+        .as_ref()
+        .and_then(|monorepo| Some((monorepo, monorepo.find_package(prefix)?)));
+    let (release, date) = match package {
+        Some((monorepo, package)) => {
+            let range = RevisionRange::new(args.from.as_deref(), &args.to);
+            let mut include = monorepo
+                .package_filter(repository.as_ref(), package, &range)
+                .unwrap_or_else(|e| panic!("Couldn't read changed paths: {}", e));
+            prepare_filtered_range_release(
+                repository.as_ref(),
+                prefix,
+                args.from.as_deref(),
+                &args.to,
+                &mut include,
+            )
+        }
+        None => prepare_range_release(repository.as_ref(), prefix, args.from.as_deref(), &args.to),
+    }
+    .unwrap_or_else(|e| panic!("Couldn't read commits: {}", e));
+
+    // without anything to release, the computed version would be the previous one again
+    if !release.has_changes() {
+        let since = match &release.previous {
+            Some(tag) => tag.name.as_str(),
+            None => "the first commit",
+        };
+        eprintln!("No changes requiring a release since {}", since);
+        return;
+    }
+
+    let remote_url = repository
+        .remote_url(&config.changelog.links.remote)
+        .unwrap_or_else(|e| panic!("Couldn't read remote URL: {}", e));
+    let links = Links::from(&config.changelog.links, remote_url.as_deref())
+        .unwrap_or_else(|e| panic!("Couldn't configure changelog links: {}", e));
+    let identities = config
+        .identities(repository.root())
+        .unwrap_or_else(|e| panic!("Couldn't load contributor identities: {}", e));
+    let notes = ChangelogRenderer::release_notes(args.plain, &config.changelog.sections)
+        .with_links(links)
+        .with_identities(identities)
+        .render_release(&release, date)
+        .unwrap_or_else(|e| panic!("Couldn't render release notes: {}", e));
+
+    match &args.output {
+        Some(path) => {
+            fs::write(path, notes).unwrap_or_else(|e| panic!("Couldn't write {}: {}", path, e))
+        }
+        None => print!("{}", notes),
+    }
+}
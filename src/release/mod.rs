@@ -0,0 +1,754 @@
+pub mod notes;
+
+use crate::changelog::presets::Preset;
+use crate::changelog::ChangelogRenderer;
+use crate::core::contributors::Identities;
+use crate::core::history::{History, HistoryEntry};
+use crate::core::semantic_version::{Increment, PreRelease, PreReleaseType, SemanticVersion};
+use crate::core::version_range::VersionRange;
+use crate::git::{GitError, Repository, RevisionRange};
+use chrono::{Local, NaiveDate};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug)]
+pub struct BumpArgs {
+    /// Overrides `release.tag_prefix` from the configuration.
+    pub tag_prefix: Option<String>,
+    /// Create an annotated tag with the release notes as its message.
+    pub create_tag: bool,
+    /// Release a pre-release on this channel, e.g. `rc` for `2.0.0-rc.1`.
+    pub pre: Option<PreReleaseType>,
+    /// Write the version into project manifests.
+    pub write: bool,
+    /// Print what would be changed instead of writing manifests or creating a tag.
+    pub dry_run: bool,
+}
+
+pub fn process_args(args: &[String]) -> BumpArgs {
+    let mut tag_prefix: Option<String> = None;
+    let mut create_tag = false;
+    let mut pre: Option<PreReleaseType> = None;
+    let mut write = false;
+    let mut dry_run = false;
+
+    let mut iterator = args.iter().skip(1);
+    while let Some(arg) = iterator.next() {
+        match arg.as_str() {
+            "--tag-prefix" => match iterator.next() {
+                Some(prefix) => tag_prefix = Some(prefix.clone()),
+                None => panic!("Missing prefix after --tag-prefix"),
+            },
+            "--tag" => create_tag = true,
+            "--write" => write = true,
+            "--dry-run" => dry_run = true,
+            "--pre" => match iterator.next() {
+                Some(channel) if channel.parse::<u64>().is_err() => {
+                    match PreReleaseType::from_identifier(channel) {
+                        Some(channel) => pre = Some(channel),
+                        None => panic!("Invalid pre-release channel: {}", channel),
+                    }
+                }
+                Some(channel) => panic!("Pre-release channel can't be a number: {}", channel),
+                None => panic!("Missing channel after --pre"),
+            },
+            _ => panic!("Unknown argument: {}", arg),
+        }
+    }
+
+    return BumpArgs {
+        tag_prefix,
+        create_tag,
+        pre,
+        write,
+        dry_run,
+    };
+}
+
+/// Tag marking a released version.
+#[derive(Debug, PartialEq, Clone)]
+pub struct VersionTag {
+    pub name: String,
+    /// Id of the tagged commit.
+    pub target: String,
+    pub version: SemanticVersion,
+}
+
+#[derive(Debug)]
+pub struct Release {
+    /// Release the changes are counted from: the latest final release reachable from HEAD,
+    /// or the latest release of any kind for pre-releases.
+    pub previous: Option<VersionTag>,
+    pub version: SemanticVersion,
+    pub tag_name: String,
+    /// Commits since the previous release.
+    pub history: History,
+    /// Tags of dependencies released along with this release, in a monorepo.
+    pub dependencies: Vec<String>,
+}
+
+impl Release {
+    /// Whether there is anything to release since the previous release.
+    pub fn has_changes(&self) -> bool {
+        match &self.previous {
+            Some(previous) => previous.version != self.version,
+            None => self.version != SemanticVersion::new(0, 0, 0, None, None),
+        }
+    }
+}
+
+/// Version of a tag named `<prefix><version>`.
+pub fn parse_tag_version(name: &str, prefix: &str) -> Option<SemanticVersion> {
+    let version = name.strip_prefix(prefix)?;
+    if version.starts_with('v') {
+        // the version parser accepts a `v`, which is a part of the prefix here
+        return None;
+    }
+
+    return version.parse().ok();
+}
+
+/// Release tags reachable from HEAD.
+pub fn find_version_tags(
+    repository: &dyn Repository,
+    prefix: &str,
+) -> Result<Vec<VersionTag>, GitError> {
+    let reachable: HashSet<String> = repository
+        .commits(&RevisionRange::default())?
+        .into_iter()
+        .map(|commit| commit.id)
+        .collect();
+
+    return Ok(repository
+        .tags()?
+        .into_iter()
+        .filter(|tag| reachable.contains(&tag.target))
+        .filter_map(|tag| {
+            parse_tag_version(&tag.name, prefix).map(|version| VersionTag {
+                name: tag.name,
+                target: tag.target,
+                version,
+            })
+        })
+        .collect());
+}
+
+/// Releases tagged with the prefix and reachable from HEAD, newest first, with the dates of
+/// the tagged commits. Each release counts commits since the release preceding it in version
+/// order, pre-releases included, except for commits reverted within the same release.
+pub fn find_releases(
+    repository: &dyn Repository,
+    prefix: &str,
+) -> Result<Vec<(Release, NaiveDate)>, GitError> {
+    return find_filtered_releases(repository, prefix, &mut |_| Ok(true));
+}
+
+/// Same as [find_releases], but only takes commits accepted by `include` into account,
+/// e.g. commits attributed to a single package of a monorepo.
+pub fn find_filtered_releases(
+    repository: &dyn Repository,
+    prefix: &str,
+    include: &mut dyn FnMut(&HistoryEntry) -> Result<bool, GitError>,
+) -> Result<Vec<(Release, NaiveDate)>, GitError> {
+    let dates: HashMap<String, NaiveDate> = repository
+        .commits(&RevisionRange::default())?
+        .into_iter()
+        .map(|commit| (commit.id, commit.committer.timestamp.date_naive()))
+        .collect();
+    let mut tags: Vec<VersionTag> = repository
+        .tags()?
+        .into_iter()
+        .filter(|tag| dates.contains_key(&tag.target))
+        .filter_map(|tag| {
+            parse_tag_version(&tag.name, prefix).map(|version| VersionTag {
+                name: tag.name,
+                target: tag.target,
+                version,
+            })
+        })
+        .collect();
+    tags.sort_by(|a, b| a.version.cmp(&b.version));
+
+    let mut releases: Vec<(Release, NaiveDate)> = vec![];
+    let mut previous: Option<VersionTag> = None;
+    for tag in tags {
+        let from = previous.as_ref().map(|previous| previous.target.as_str());
+        let commits = repository.history_commits(&RevisionRange::new(from, &tag.target))?;
+        let release = Release {
+            previous: previous.clone(),
+            version: tag.version.clone(),
+            tag_name: tag.name.clone(),
+            history: filter_history(History::from(&commits).cancel_reverts(), include)?,
+            dependencies: vec![],
+        };
+        releases.push((release, dates[&tag.target]));
+        previous = Some(tag);
+    }
+    releases.reverse();
+
+    return Ok(releases);
+}
+
+/// Finds the release tag with the highest version among tags reachable from HEAD.
+pub fn find_latest_tag(
+    repository: &dyn Repository,
+    prefix: &str,
+) -> Result<Option<VersionTag>, GitError> {
+    return Ok(get_latest(find_version_tags(repository, prefix)?));
+}
+
+/// Release tags reachable from HEAD with versions in the given range, lowest version first.
+pub fn find_tags_in_range(
+    repository: &dyn Repository,
+    prefix: &str,
+    range: &VersionRange,
+) -> Result<Vec<VersionTag>, GitError> {
+    let mut tags: Vec<VersionTag> = find_version_tags(repository, prefix)?
+        .into_iter()
+        .filter(|tag| range.matches(&tag.version))
+        .collect();
+    tags.sort_by(|a, b| a.version.cmp(&b.version));
+
+    return Ok(tags);
+}
+
+/// Computes the next version from commits made since the latest final release reachable
+/// from HEAD. Without any release tags, the whole history is taken into account, starting
+/// from `0.0.0`. Commits reverted since that release don't count.
+///
+/// With a pre-release channel, the version gets a pre-release part, e.g. `2.0.0-rc.1`.
+/// Numbering continues when the latest release is a pre-release of the same version on the
+/// same channel. Without a channel, the latest pre-release is promoted to a final release.
+pub fn prepare_release(
+    repository: &dyn Repository,
+    prefix: &str,
+    channel: Option<&PreReleaseType>,
+) -> Result<Release, GitError> {
+    return prepare_filtered_release(repository, prefix, channel, &mut |_| Ok(true));
+}
+
+/// Commits the next version is computed from by [prepare_release], those made since the
+/// latest final release reachable from HEAD.
+pub fn release_range(repository: &dyn Repository, prefix: &str) -> Result<RevisionRange, GitError> {
+    let latest_final = get_latest(
+        find_version_tags(repository, prefix)?
+            .into_iter()
+            .filter(|tag| !tag.version.is_pre_release())
+            .collect(),
+    );
+
+    return Ok(RevisionRange::new(
+        latest_final.as_ref().map(|tag| tag.target.as_str()),
+        "HEAD",
+    ));
+}
+
+/// Same as [prepare_release], but only takes commits accepted by `include` into account,
+/// e.g. commits attributed to a single package of a monorepo.
+pub fn prepare_filtered_release(
+    repository: &dyn Repository,
+    prefix: &str,
+    channel: Option<&PreReleaseType>,
+    include: &mut dyn FnMut(&HistoryEntry) -> Result<bool, GitError>,
+) -> Result<Release, GitError> {
+    let tags = find_version_tags(repository, prefix)?;
+    let latest = get_latest(tags.to_vec());
+    let latest_final = get_latest(
+        tags.into_iter()
+            .filter(|tag| !tag.version.is_pre_release())
+            .collect(),
+    );
+
+    // the version a release leads to is decided by all changes since the latest final release
+    let history = get_history(repository, latest_final.as_ref(), include)?;
+    let increment = history.increment();
+    let target = match &latest_final {
+        Some(tag) => increment_version(&tag.version, increment)?,
+        None => increment_version(&SemanticVersion::new(0, 0, 0, None, None), increment)?,
+    };
+
+    let channel = match channel {
+        Some(channel) if increment != Increment::None => channel,
+        _ => {
+            return Ok(Release {
+                tag_name: format!("{}{}", prefix, target),
+                previous: latest_final,
+                version: target,
+                history,
+                dependencies: vec![],
+            })
+        }
+    };
+
+    // pre-releases are counted from the latest release of any kind
+    let history = match latest == latest_final {
+        true => history,
+        false => get_history(repository, latest.as_ref(), include)?,
+    };
+    let version = match get_continued_pre_release(latest.as_ref(), &target, channel) {
+        // nothing new since the latest pre-release, so it stays the current one
+        Some(pre_release) if history.increment() == Increment::None => {
+            target.with_pre_release(Some(pre_release.clone()))
+        }
+        Some(pre_release) => target.with_pre_release(Some(pre_release.next())),
+        None => target.with_pre_release(Some(PreRelease::first(channel.clone()))),
+    };
+
+    return Ok(Release {
+        tag_name: format!("{}{}", prefix, version),
+        previous: latest,
+        version,
+        history,
+        dependencies: vec![],
+    });
+}
+
+/// Turns a release without changes of its own into a patch release, e.g. to publish
+/// a package of a monorepo with its updated dependencies. Releases with changes are left
+/// as they are.
+pub fn require_patch_release(
+    repository: &dyn Repository,
+    release: &mut Release,
+    prefix: &str,
+    channel: Option<&PreReleaseType>,
+) -> Result<(), GitError> {
+    if release.has_changes() {
+        return Ok(());
+    }
+
+    let target = increment_version(&release.version, Increment::Patch)?;
+    release.version = match channel {
+        Some(channel) => {
+            let latest = find_latest_tag(repository, prefix)?;
+            match get_continued_pre_release(latest.as_ref(), &target, channel) {
+                Some(pre_release) => target.with_pre_release(Some(pre_release.next())),
+                None => target.with_pre_release(Some(PreRelease::first(channel.clone()))),
+            }
+        }
+        None => target,
+    };
+    release.tag_name = format!("{}{}", prefix, release.version);
+
+    return Ok(());
+}
+
+/// Pre-release of the latest release, when it's on the channel and leads to the target.
+fn get_continued_pre_release<'a>(
+    latest: Option<&'a VersionTag>,
+    target: &SemanticVersion,
+    channel: &PreReleaseType,
+) -> Option<&'a PreRelease> {
+    latest.and_then(|tag| {
+        tag.version
+            .pre_release()
+            .filter(|pre_release| pre_release.is_on_channel(channel))
+            .filter(|_| tag.version.has_same_core(target))
+    })
+}
+
+fn get_latest(tags: Vec<VersionTag>) -> Option<VersionTag> {
+    tags.into_iter().max_by(|a, b| a.version.cmp(&b.version))
+}
+
+fn get_history(
+    repository: &dyn Repository,
+    since: Option<&VersionTag>,
+    include: &mut dyn FnMut(&HistoryEntry) -> Result<bool, GitError>,
+) -> Result<History, GitError> {
+    let range = RevisionRange::new(since.map(|tag| tag.target.as_str()), "HEAD");
+    let history = History::from(&repository.history_commits(&range)?).cancel_reverts();
+
+    return filter_history(history, include);
+}
+
+/// Keeps entries accepted by `include`; commits that aren't conventional are kept as well.
+fn filter_history(
+    mut history: History,
+    include: &mut dyn FnMut(&HistoryEntry) -> Result<bool, GitError>,
+) -> Result<History, GitError> {
+    let mut entries: Vec<HistoryEntry> = vec![];
+    for entry in history.entries {
+        if include(&entry)? {
+            entries.push(entry);
+        }
+    }
+    history.entries = entries;
+
+    return Ok(history);
+}
+
+/// The version incremented, or an error when it can't be incremented any further.
+fn increment_version(
+    version: &SemanticVersion,
+    increment: Increment,
+) -> Result<SemanticVersion, GitError> {
+    return version.increment(increment).ok_or_else(|| GitError {
+        reason: format!("Version {} can't be incremented any further", version),
+    });
+}
+
+/// Plain text summary of the release, suitable as a tag message.
+pub fn release_notes(release: &Release, identities: &Identities) -> String {
+    return ChangelogRenderer::preset(Preset::Plain)
+        .with_identities(identities.clone())
+        .render_release(release, Local::now().date_naive())
+        .expect("built-in changelog templates should render");
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::contributors::Identities;
+    use crate::core::history::tests::commit;
+    use crate::core::history::History;
+    use crate::core::semantic_version::{PreReleaseType, SemanticVersion};
+    use crate::core::version_range::VersionRange;
+    use crate::git::cli::tests::{commit_file, create_repository, git};
+    use crate::git::cli::CliRepository;
+    use crate::release::{
+        find_latest_tag, find_releases, find_tags_in_range, parse_tag_version,
+        prepare_filtered_release, prepare_release, process_args, release_notes,
+        require_patch_release, Release,
+    };
+
+    #[test]
+    fn should_process_args() {
+        // given
+        let args = vec![
+            String::from("DontCare"),
+            String::from("--tag"),
+            String::from("--tag-prefix"),
+            String::from("mylib-v"),
+            String::from("--pre"),
+            String::from("rc"),
+            String::from("--write"),
+            String::from("--dry-run"),
+        ];
+
+        // when
+        let actual = process_args(&args);
+
+        // then
+        assert_eq!(actual.tag_prefix, Some(String::from("mylib-v")));
+        assert!(actual.create_tag);
+        assert_eq!(actual.pre, Some(PreReleaseType::RC));
+        assert!(actual.write);
+        assert!(actual.dry_run);
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_panic_for_numeric_pre_release_channel() {
+        process_args(&[
+            String::from("DontCare"),
+            String::from("--pre"),
+            String::from("1"),
+        ]);
+    }
+
+    #[test]
+    fn should_parse_versions_of_tags_with_prefix() {
+        assert_eq!(
+            parse_tag_version("v1.2.3", "v"),
+            Some(SemanticVersion::new(1, 2, 3, None, None))
+        );
+        assert_eq!(
+            parse_tag_version("mylib-v1.2.3", "mylib-v"),
+            Some(SemanticVersion::new(1, 2, 3, None, None))
+        );
+        assert_eq!(parse_tag_version("mylib-v1.2.3", "v"), None);
+        assert_eq!(parse_tag_version("vv1.2.3", "v"), None);
+        assert_eq!(parse_tag_version("v1.2", "v"), None);
+    }
+
+    #[test]
+    fn should_find_latest_reachable_tag_with_prefix() {
+        // given
+        let root = create_repository("release-latest");
+        commit_file(&root, "a.txt", "feat: first");
+        git(&root, &["tag", "v1.0.0"]);
+        let second = commit_file(&root, "a.txt", "feat: second");
+        git(&root, &["tag", "-a", "v1.1.0", "-m", "release"]);
+        git(&root, &["tag", "mylib-v3.0.0"]);
+        git(&root, &["tag", "latest"]);
+        git(&root, &["checkout", "-q", "-b", "other"]);
+        commit_file(&root, "b.txt", "feat: unreleased elsewhere");
+        git(&root, &["tag", "v9.0.0"]);
+        git(&root, &["checkout", "-q", "main"]);
+        let repository = CliRepository::discover(&root).unwrap();
+
+        // when
+        let latest = find_latest_tag(&repository, "v").unwrap().unwrap();
+        let prefixed = find_latest_tag(&repository, "mylib-v").unwrap().unwrap();
+
+        // then
+        assert_eq!(latest.name, "v1.1.0");
+        assert_eq!(latest.target, second);
+        assert_eq!(prefixed.version, SemanticVersion::new(3, 0, 0, None, None));
+        assert!(find_latest_tag(&repository, "other-").unwrap().is_none());
+    }
+
+    #[test]
+    fn should_find_tags_in_version_range() {
+        // given
+        let root = create_repository("release-range");
+        for version in ["0.9.0", "1.2.0", "1.0.0", "2.0.0-rc.1", "2.0.0"] {
+            commit_file(&root, "a.txt", &format!("feat: {}", version));
+            git(&root, &["tag", &format!("v{}", version)]);
+        }
+        let repository = CliRepository::discover(&root).unwrap();
+        let range = VersionRange::from(">=1.0.0, <3").unwrap();
+
+        // when
+        let tags = find_tags_in_range(&repository, "v", &range).unwrap();
+
+        // then
+        let names: Vec<&str> = tags.iter().map(|tag| tag.name.as_str()).collect();
+        assert_eq!(names, vec!["v1.0.0", "v1.2.0", "v2.0.0"]);
+    }
+
+    #[test]
+    fn should_find_releases_newest_first() {
+        // given
+        let root = create_repository("release-history");
+        commit_file(&root, "a.txt", "feat: first");
+        git(&root, &["tag", "v1.0.0"]);
+        commit_file(&root, "a.txt", "fix: second");
+        commit_file(&root, "a.txt", "not conventional");
+        git(&root, &["tag", "v1.0.1"]);
+        git(&root, &["tag", "other-1.0.0"]);
+        commit_file(&root, "a.txt", "feat: unreleased");
+        let repository = CliRepository::discover(&root).unwrap();
+
+        // when
+        let releases = find_releases(&repository, "v").unwrap();
+
+        // then
+        let tags: Vec<(&str, Option<&str>, usize, usize)> = releases
+            .iter()
+            .map(|(release, _)| {
+                (
+                    release.tag_name.as_str(),
+                    release.previous.as_ref().map(|tag| tag.name.as_str()),
+                    release.history.entries.len(),
+                    release.history.skipped.len(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            tags,
+            vec![("v1.0.1", Some("v1.0.0"), 1, 1), ("v1.0.0", None, 1, 0)]
+        );
+    }
+
+    #[test]
+    fn should_compute_next_version_from_commits_since_latest_tag() {
+        // given
+        let root = create_repository("release-next");
+        commit_file(&root, "a.txt", "feat!: first");
+        git(&root, &["tag", "v1.0.0"]);
+        commit_file(&root, "a.txt", "fix: crash");
+        commit_file(&root, "a.txt", "feat(api): add endpoint");
+        commit_file(&root, "a.txt", "Not conventional");
+        let repository = CliRepository::discover(&root).unwrap();
+
+        // when
+        let release = prepare_release(&repository, "v", None).unwrap();
+
+        // then
+        assert_eq!(release.version, SemanticVersion::new(1, 1, 0, None, None));
+        assert_eq!(release.tag_name, "v1.1.0");
+        assert_eq!(release.history.entries.len(), 2);
+        assert_eq!(release.history.skipped.len(), 1);
+        assert!(release.has_changes());
+    }
+
+    #[test]
+    fn should_not_count_commits_reverted_since_latest_tag() {
+        // given
+        let root = create_repository("release-revert");
+        commit_file(&root, "a.txt", "feat: first");
+        git(&root, &["tag", "v1.0.0"]);
+        commit_file(&root, "a.txt", "fix: crash");
+        commit_file(&root, "a.txt", "feat!: drop api");
+        git(&root, &["revert", "--no-edit", "HEAD"]);
+        let repository = CliRepository::discover(&root).unwrap();
+
+        // when
+        let release = prepare_release(&repository, "v", None).unwrap();
+
+        // then
+        assert_eq!(release.tag_name, "v1.0.1");
+        assert_eq!(release.history.entries.len(), 1);
+        assert_eq!(release.history.reverted.len(), 2);
+    }
+
+    #[test]
+    fn should_fail_when_version_cannot_be_incremented() {
+        // given
+        let root = create_repository("release-overflow");
+        commit_file(&root, "a.txt", "feat: first");
+        git(&root, &["tag", "v1.4294967295.0"]);
+        commit_file(&root, "a.txt", "feat: search");
+        let repository = CliRepository::discover(&root).unwrap();
+
+        // when
+        let actual = prepare_release(&repository, "v", None);
+
+        // then
+        assert_eq!(
+            actual.unwrap_err().reason,
+            "Version 1.4294967295.0 can't be incremented any further"
+        );
+    }
+
+    #[test]
+    fn should_start_from_initial_version_without_tags() {
+        // given
+        let root = create_repository("release-initial");
+        commit_file(&root, "a.txt", "fix: first");
+        let repository = CliRepository::discover(&root).unwrap();
+
+        // when
+        let release = prepare_release(&repository, "v", None).unwrap();
+
+        // then
+        assert!(release.previous.is_none());
+        assert_eq!(release.tag_name, "v0.0.1");
+    }
+
+    #[test]
+    fn should_number_pre_releases_and_promote_them() {
+        // given
+        let root = create_repository("release-pre");
+        let repository = CliRepository::discover(&root).unwrap();
+        let next = |channel: Option<&str>| {
+            let channel = channel.and_then(PreReleaseType::from_identifier);
+            let release = prepare_release(&repository, "v", channel.as_ref()).unwrap();
+            if release.has_changes() {
+                git(&root, &["tag", &release.tag_name]);
+            }
+            return release.tag_name;
+        };
+        commit_file(&root, "a.txt", "feat: first");
+        git(&root, &["tag", "v1.2.0"]);
+
+        // when
+        commit_file(&root, "a.txt", "feat!: breaking");
+        let first_rc = next(Some("rc"));
+        commit_file(&root, "a.txt", "fix: crash");
+        let second_rc = next(Some("rc"));
+        commit_file(&root, "a.txt", "docs: readme");
+        let unchanged_rc = next(Some("rc"));
+        let nightly = next(Some("nightly"));
+        let promoted = next(None);
+        commit_file(&root, "a.txt", "fix: another crash");
+        let after_release = next(Some("beta"));
+
+        // then
+        assert_eq!(first_rc, "v2.0.0-rc.1");
+        assert_eq!(second_rc, "v2.0.0-rc.2");
+        assert_eq!(unchanged_rc, "v2.0.0-rc.2");
+        assert_eq!(nightly, "v2.0.0-nightly.1");
+        assert_eq!(promoted, "v2.0.0");
+        assert_eq!(after_release, "v2.0.1-beta.1");
+    }
+
+    #[test]
+    fn should_restart_pre_release_numbering_when_target_version_changes() {
+        // given
+        let root = create_repository("release-pre-target");
+        commit_file(&root, "a.txt", "feat: first");
+        git(&root, &["tag", "v1.2.0"]);
+        commit_file(&root, "a.txt", "fix: crash");
+        git(&root, &["tag", "v1.2.1-rc.1"]);
+        commit_file(&root, "a.txt", "feat: new api");
+        let repository = CliRepository::discover(&root).unwrap();
+
+        // when
+        let release = prepare_release(&repository, "v", Some(&PreReleaseType::RC)).unwrap();
+
+        // then
+        assert_eq!(release.tag_name, "v1.3.0-rc.1");
+        assert_eq!(release.previous.unwrap().name, "v1.2.1-rc.1");
+        assert_eq!(release.history.entries.len(), 1);
+    }
+
+    #[test]
+    fn should_render_release_notes_by_section() {
+        // given
+        let release = Release {
+            previous: None,
+            version: SemanticVersion::new(2, 0, 0, None, None),
+            tag_name: String::from("v2.0.0"),
+            history: History::from(&[
+                commit("d", "docs: readme"),
+                commit("c", "fix(cli,api): crash"),
+                commit("b", "feat(api)!: add endpoint"),
+                commit(
+                    "a",
+                    "feat: drop config\n\nBREAKING CHANGE: config file is gone",
+                ),
+            ]),
+            dependencies: vec![],
+        };
+
+        // when
+        let notes = release_notes(&release, &Identities::default());
+
+        // then
+        assert_eq!(
+            notes,
+            "Release 2.0.0\n\n\
+            Breaking changes:\n- api: add endpoint\n- config file is gone\n\n\
+            Features:\n- api: add endpoint\n- drop config\n\n\
+            Bug fixes:\n- cli, api: crash\n"
+        );
+    }
+
+    #[test]
+    fn should_only_count_included_commits() {
+        // given
+        let root = create_repository("release-filtered");
+        commit_file(&root, "a.txt", "feat(api): first");
+        git(&root, &["tag", "api@1.0.0"]);
+        commit_file(&root, "a.txt", "feat(cli): unrelated");
+        commit_file(&root, "a.txt", "fix(api): crash");
+        let repository = CliRepository::discover(&root).unwrap();
+
+        // when
+        let release = prepare_filtered_release(&repository, "api@", None, &mut |entry| {
+            Ok(entry.commit.scopes == Some(vec![String::from("api")]))
+        })
+        .unwrap();
+
+        // then
+        assert_eq!(release.tag_name, "api@1.0.1");
+        assert_eq!(release.history.entries.len(), 1);
+    }
+
+    #[test]
+    fn should_require_patch_release_without_changes() {
+        // given
+        let root = create_repository("release-patch");
+        commit_file(&root, "a.txt", "feat: first");
+        git(&root, &["tag", "app@1.0.0"]);
+        let repository = CliRepository::discover(&root).unwrap();
+        let rc = PreReleaseType::RC;
+
+        // when
+        let mut release = prepare_release(&repository, "app@", None).unwrap();
+        let unchanged = release.has_changes();
+        require_patch_release(&repository, &mut release, "app@", None).unwrap();
+        let mut first_rc = prepare_release(&repository, "app@", Some(&rc)).unwrap();
+        require_patch_release(&repository, &mut first_rc, "app@", Some(&rc)).unwrap();
+        git(&root, &["tag", &first_rc.tag_name]);
+        let mut second_rc = prepare_release(&repository, "app@", Some(&rc)).unwrap();
+        require_patch_release(&repository, &mut second_rc, "app@", Some(&rc)).unwrap();
+
+        // then
+        assert!(!unchanged);
+        assert!(release.has_changes());
+        assert_eq!(release.tag_name, "app@1.0.1");
+        assert_eq!(first_rc.tag_name, "app@1.0.1-rc.1");
+        assert_eq!(second_rc.tag_name, "app@1.0.1-rc.2");
+    }
+}
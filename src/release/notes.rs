@@ -0,0 +1,274 @@
+use crate::core::history::{History, HistoryEntry};
+use crate::core::semantic_version::SemanticVersion;
+use crate::git::{GitError, Repository, RevisionRange};
+use crate::release::{filter_history, increment_version, parse_tag_version, Release, VersionTag};
+use chrono::{Local, NaiveDate};
+use std::collections::HashSet;
+
+#[derive(Debug)]
+pub struct ReleaseNotesArgs {
+    /// Revision the notes start after, the latest final release before `to` by default.
+    pub from: Option<String>,
+    pub to: String,
+    /// Overrides `release.tag_prefix` from the configuration.
+    pub tag_prefix: Option<String>,
+    /// Plain text suitable as a tag message, instead of markdown.
+    pub plain: bool,
+    /// Write the notes to this file instead of the standard output.
+    pub output: Option<String>,
+}
+
+pub fn process_args(args: &[String]) -> ReleaseNotesArgs {
+    let mut from: Option<String> = None;
+    let mut to = String::from("HEAD");
+    let mut tag_prefix: Option<String> = None;
+    let mut plain = false;
+    let mut output: Option<String> = None;
+
+    let mut iterator = args.iter().skip(1);
+    while let Some(arg) = iterator.next() {
+        match arg.as_str() {
+            "--from" => match iterator.next() {
+                Some(revision) => from = Some(revision.clone()),
+                None => panic!("Missing revision after --from"),
+            },
+            "--to" => match iterator.next() {
+                Some(revision) => to = revision.clone(),
+                None => panic!("Missing revision after --to"),
+            },
+            "--tag-prefix" => match iterator.next() {
+                Some(prefix) => tag_prefix = Some(prefix.clone()),
+                None => panic!("Missing prefix after --tag-prefix"),
+            },
+            "--plain" => plain = true,
+            "--output" => match iterator.next() {
+                Some(path) => output = Some(path.clone()),
+                None => panic!("Missing path after --output"),
+            },
+            _ => panic!("Unknown argument: {}", arg),
+        }
+    }
+
+    return ReleaseNotesArgs {
+        from,
+        to,
+        tag_prefix,
+        plain,
+        output,
+    };
+}
+
+/// Release made of the commits in `from..to`, dated with the newest of them. The version comes
+/// from `to` when it is a release tag, and is computed from the commits otherwise, on top of
+/// `from` when it is a release tag, or the latest final release it contains.
+pub fn prepare_range_release(
+    repository: &dyn Repository,
+    prefix: &str,
+    from: Option<&str>,
+    to: &str,
+) -> Result<(Release, NaiveDate), GitError> {
+    return prepare_filtered_range_release(repository, prefix, from, to, &mut |_| Ok(true));
+}
+
+/// Same as [prepare_range_release], but only takes commits accepted by `include` into account,
+/// e.g. commits attributed to a single package of a monorepo.
+pub fn prepare_filtered_range_release(
+    repository: &dyn Repository,
+    prefix: &str,
+    from: Option<&str>,
+    to: &str,
+    include: &mut dyn FnMut(&HistoryEntry) -> Result<bool, GitError>,
+) -> Result<(Release, NaiveDate), GitError> {
+    let previous = match from {
+        Some(from) => match parse_tag_version(from, prefix) {
+            Some(version) => Some(VersionTag {
+                name: String::from(from),
+                target: repository.resolve(from)?,
+                version,
+            }),
+            // any other revision follows the latest release it contains
+            None => find_latest_release(repository, prefix, from, true)?,
+        },
+        None => find_latest_release(repository, prefix, to, false)?,
+    };
+
+    let since = match (from, &previous) {
+        (Some(from), _) => Some(String::from(from)),
+        (None, Some(tag)) => Some(tag.target.clone()),
+        (None, None) => None,
+    };
+    let commits = repository.history_commits(&RevisionRange::new(since.as_deref(), to))?;
+    let date = match commits.first() {
+        Some(commit) => commit.committer.timestamp.date_naive(),
+        None => Local::now().date_naive(),
+    };
+    let history = filter_history(History::from(&commits).cancel_reverts(), include)?;
+
+    let (version, tag_name) = match parse_tag_version(to, prefix) {
+        Some(version) => (version, String::from(to)),
+        None => {
+            let base = match &previous {
+                Some(tag) => tag.version.clone(),
+                None => SemanticVersion::new(0, 0, 0, None, None),
+            };
+            let version = increment_version(&base, history.increment())?;
+            let tag_name = format!("{}{}", prefix, version);
+            (version, tag_name)
+        }
+    };
+
+    return Ok((
+        Release {
+            previous,
+            version,
+            tag_name,
+            history,
+            dependencies: vec![],
+        },
+        date,
+    ));
+}
+
+/// Final release tag with the highest version among tags reachable from `revision`. Tags of
+/// `revision` itself are only taken into account with `include_own`.
+fn find_latest_release(
+    repository: &dyn Repository,
+    prefix: &str,
+    revision: &str,
+    include_own: bool,
+) -> Result<Option<VersionTag>, GitError> {
+    let target = repository.resolve(revision)?;
+    let reachable: HashSet<String> = repository
+        .commits(&RevisionRange::new(None, revision))?
+        .into_iter()
+        .map(|commit| commit.id)
+        .collect();
+
+    return Ok(repository
+        .tags()?
+        .into_iter()
+        .filter(|tag| (include_own || tag.target != target) && reachable.contains(&tag.target))
+        .filter_map(|tag| {
+            parse_tag_version(&tag.name, prefix).map(|version| VersionTag {
+                name: tag.name,
+                target: tag.target,
+                version,
+            })
+        })
+        .filter(|tag| !tag.version.is_pre_release())
+        .max_by(|a, b| a.version.cmp(&b.version)));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::git::cli::tests::{commit_file, create_repository, git};
+    use crate::git::cli::CliRepository;
+    use crate::release::notes::{prepare_range_release, process_args};
+
+    #[test]
+    fn should_process_args() {
+        // given
+        let args: Vec<String> = ["release-notes", "--from", "v1.0.0", "--plain"]
+            .iter()
+            .map(|arg| String::from(*arg))
+            .collect();
+
+        // when
+        let args = process_args(&args);
+
+        // then
+        assert_eq!(args.from, Some(String::from("v1.0.0")));
+        assert_eq!(args.to, "HEAD");
+        assert!(args.plain);
+        assert_eq!(args.output, None);
+    }
+
+    #[test]
+    fn should_prepare_release_of_tagged_range() {
+        // given
+        let root = create_repository("notes-tagged");
+        commit_file(&root, "a.txt", "feat: first");
+        git(&root, &["tag", "v1.0.0"]);
+        commit_file(&root, "a.txt", "fix: second");
+        git(&root, &["tag", "v1.0.1-rc.1"]);
+        commit_file(&root, "a.txt", "feat: third");
+        git(&root, &["tag", "v1.1.0"]);
+        commit_file(&root, "a.txt", "feat!: unreleased");
+        let repository = CliRepository::discover(&root).unwrap();
+
+        // when
+        let (tagged, _) = prepare_range_release(&repository, "v", None, "v1.1.0").unwrap();
+        let (unreleased, _) = prepare_range_release(&repository, "v", None, "HEAD").unwrap();
+        let (explicit, _) =
+            prepare_range_release(&repository, "v", Some("v1.0.1-rc.1"), "v1.1.0").unwrap();
+
+        // then
+        assert_eq!(tagged.tag_name, "v1.1.0");
+        assert_eq!(tagged.previous.unwrap().name, "v1.0.0");
+        assert_eq!(tagged.history.entries.len(), 2);
+        assert_eq!(unreleased.tag_name, "v2.0.0");
+        assert_eq!(unreleased.history.entries.len(), 1);
+        assert_eq!(explicit.previous.unwrap().name, "v1.0.1-rc.1");
+        assert_eq!(explicit.history.entries.len(), 1);
+    }
+
+    #[test]
+    fn should_compute_version_from_untagged_revisions() {
+        // given
+        let root = create_repository("notes-untagged");
+        let first = commit_file(&root, "a.txt", "feat: first");
+        git(&root, &["tag", "v1.0.0"]);
+        let second = commit_file(&root, "a.txt", "fix: second");
+        git(&root, &["branch", "base"]);
+        commit_file(&root, "a.txt", "feat: third");
+        let repository = CliRepository::discover(&root).unwrap();
+
+        // when
+        let (from_branch, _) =
+            prepare_range_release(&repository, "v", Some("base"), "HEAD").unwrap();
+        let (from_tagged, _) =
+            prepare_range_release(&repository, "v", Some(&first), &second).unwrap();
+
+        // then
+        assert_eq!(from_branch.previous.unwrap().name, "v1.0.0");
+        assert_eq!(from_branch.tag_name, "v1.1.0");
+        assert_eq!(from_branch.history.entries.len(), 1);
+        assert_eq!(from_tagged.previous.unwrap().name, "v1.0.0");
+        assert_eq!(from_tagged.tag_name, "v1.0.1");
+        assert_eq!(from_tagged.history.entries.len(), 1);
+    }
+
+    #[test]
+    fn should_compute_version_from_zero_without_releases() {
+        // given
+        let root = create_repository("notes-unreleased");
+        let first = commit_file(&root, "a.txt", "feat: first");
+        commit_file(&root, "a.txt", "fix: second");
+        let repository = CliRepository::discover(&root).unwrap();
+
+        // when
+        let (release, _) = prepare_range_release(&repository, "v", Some(&first), "HEAD").unwrap();
+
+        // then
+        assert!(release.previous.is_none());
+        assert_eq!(release.tag_name, "v0.0.1");
+        assert_eq!(release.history.entries.len(), 1);
+    }
+
+    #[test]
+    fn should_have_no_changes_without_commits_calling_for_release() {
+        // given
+        let root = create_repository("notes-no-changes");
+        commit_file(&root, "a.txt", "feat: first");
+        git(&root, &["tag", "v1.0.0"]);
+        commit_file(&root, "a.txt", "docs: typo");
+        let repository = CliRepository::discover(&root).unwrap();
+
+        // when
+        let (release, _) = prepare_range_release(&repository, "v", None, "HEAD").unwrap();
+
+        // then
+        assert!(!release.has_changes());
+        assert_eq!(release.history.entries.len(), 1);
+    }
+}
@@ -0,0 +1,140 @@
+use crate::config::{Config, ConfigError, CONFIG_FILE_NAME};
+use crate::core::glob::Glob;
+use crate::workspace::{cargo_packages, Package};
+
+/// Maps changed paths to commit scopes, using glob rules first and workspace packages second.
+#[derive(Debug)]
+pub struct ScopeResolver {
+    rules: Vec<(Glob, String)>,
+    packages: Vec<Package>,
+}
+
+impl ScopeResolver {
+    pub fn new(rules: Vec<(Glob, String)>, packages: Vec<Package>) -> Self {
+        Self { rules, packages }
+    }
+
+    pub fn from(config: &Config) -> Result<Self, ConfigError> {
+        let mut rules: Vec<(Glob, String)> = vec![];
+        for rule in &config.scope_inference.rules {
+            let glob = Glob::new(&rule.glob).map_err(|e| ConfigError {
+                path: String::from(CONFIG_FILE_NAME),
+                reason: format!("Invalid scope rule {}: {}", rule.glob, e),
+            })?;
+            rules.push((glob, rule.scope.clone()));
+        }
+
+        let packages = match config.scope_inference.workspace {
+            true => cargo_packages(&config.root)?,
+            false => vec![],
+        };
+
+        return Ok(Self::new(rules, packages));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty() && self.packages.is_empty()
+    }
+
+    /// Scope of a single path, if any rule or package matches it.
+    pub fn resolve(&self, path: &str) -> Option<&str> {
+        if let Some((_, scope)) = self.rules.iter().find(|(glob, _)| glob.matches(path)) {
+            return Some(scope);
+        }
+
+        return self
+            .packages
+            .iter()
+            .filter(|package| package.contains(path))
+            .max_by_key(|package| package.path.len())
+            .map(|package| package.name.as_str());
+    }
+
+    /// Unique scopes of all given paths, in order of their first appearance.
+    pub fn infer(&self, paths: &[String]) -> Vec<String> {
+        let mut scopes: Vec<String> = vec![];
+
+        for path in paths {
+            if let Some(scope) = self.resolve(path) {
+                if !scopes.iter().any(|s| s == scope) {
+                    scopes.push(String::from(scope));
+                }
+            }
+        }
+
+        return scopes;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::glob::Glob;
+    use crate::scope::ScopeResolver;
+    use crate::workspace::Package;
+
+    fn resolver() -> ScopeResolver {
+        ScopeResolver::new(
+            vec![
+                (Glob::new("src/api/**").unwrap(), String::from("api")),
+                (Glob::new("*.md").unwrap(), String::from("docs")),
+            ],
+            vec![
+                Package {
+                    name: String::from("root"),
+                    path: String::new(),
+                },
+                Package {
+                    name: String::from("cli"),
+                    path: String::from("crates/cli"),
+                },
+            ],
+        )
+    }
+
+    #[test]
+    fn should_infer_scopes_from_rules_before_packages() {
+        // given
+        let paths = vec![
+            String::from("src/api/routes.rs"),
+            String::from("crates/cli/README.md"),
+            String::from("crates/cli/src/main.rs"),
+            String::from("src/api/mod.rs"),
+        ];
+
+        // when
+        let actual = resolver().infer(&paths);
+
+        // then
+        assert_eq!(
+            actual,
+            vec![
+                String::from("api"),
+                String::from("docs"),
+                String::from("cli")
+            ]
+        );
+    }
+
+    #[test]
+    fn should_prefer_the_most_nested_package() {
+        // given
+        let resolver = resolver();
+
+        // then
+        assert_eq!(resolver.resolve("crates/cli/src/main.rs"), Some("cli"));
+        assert_eq!(resolver.resolve("build.rs"), Some("root"));
+    }
+
+    #[test]
+    fn should_not_infer_anything_without_rules_and_packages() {
+        // given
+        let resolver = ScopeResolver::new(vec![], vec![]);
+
+        // when
+        let actual = resolver.infer(&[String::from("src/lib.rs")]);
+
+        // then
+        assert!(resolver.is_empty());
+        assert!(actual.is_empty());
+    }
+}
@@ -0,0 +1,569 @@
+use crate::core::contributors::{Contributor, Identities};
+use crate::core::history::{History, HistoryEntry};
+use crate::git::{Commit, RevisionRange};
+use crate::release::VersionTag;
+use chrono::{Datelike, Duration, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Version of the JSON report structure, increased on incompatible changes.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub struct StatsArgs {
+    pub range: RevisionRange,
+    /// Overrides `release.tag_prefix` from the configuration.
+    pub tag_prefix: Option<String>,
+    pub json: bool,
+    /// Write the report to this file instead of the standard output.
+    pub output: Option<String>,
+}
+
+pub fn process_args(args: &[String]) -> StatsArgs {
+    let mut range = RevisionRange::default();
+    let mut tag_prefix: Option<String> = None;
+    let mut json = false;
+    let mut output: Option<String> = None;
+
+    let mut iterator = args.iter().skip(1);
+    while let Some(arg) = iterator.next() {
+        match arg.as_str() {
+            "--tag-prefix" => match iterator.next() {
+                Some(prefix) => tag_prefix = Some(prefix.clone()),
+                None => panic!("Missing prefix after --tag-prefix"),
+            },
+            "--format" => match iterator.next().map(|name| name.as_str()) {
+                Some("text") => json = false,
+                Some("json") => json = true,
+                Some(name) => panic!("Unknown format: {}", name),
+                None => panic!("Missing format after --format"),
+            },
+            "--output" => match iterator.next() {
+                Some(path) => output = Some(path.clone()),
+                None => panic!("Missing path after --output"),
+            },
+            _ if arg.starts_with("--") => panic!("Unknown argument: {}", arg),
+            _ => range = RevisionRange::from(arg),
+        }
+    }
+
+    return StatsArgs {
+        range,
+        tag_prefix,
+        json,
+        output,
+    };
+}
+
+/// Report on how a range of history follows the conventional commits format.
+#[derive(Debug, Serialize)]
+pub struct Statistics {
+    pub commits: usize,
+    pub conventional: usize,
+    /// Percentage of conventional commits, 100 for an empty range.
+    pub compliance: f64,
+    /// Most frequent first.
+    pub types: Vec<Count>,
+    /// Most frequent first.
+    pub scopes: Vec<Count>,
+    /// Newest first, starting with unreleased commits if there are any.
+    pub releases: Vec<ReleaseStatistics>,
+    /// Most active first.
+    pub authors: Vec<AuthorStatistics>,
+    /// Every ISO week from the oldest commit to the newest one, oldest first.
+    pub weeks: Vec<WeekStatistics>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Count {
+    pub name: String,
+    pub count: usize,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ReleaseStatistics {
+    /// Release tag, `None` for commits not released yet.
+    pub tag: Option<String>,
+    pub commits: usize,
+    pub breaking_changes: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AuthorStatistics {
+    pub name: String,
+    pub email: String,
+    pub commits: usize,
+    pub conventional: usize,
+    pub compliance: f64,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct WeekStatistics {
+    /// ISO week, e.g. `2024-W18`.
+    pub week: String,
+    /// Monday of the week, as `YYYY-MM-DD`.
+    pub start: String,
+    pub commits: usize,
+    pub conventional: usize,
+}
+
+#[derive(Serialize)]
+struct JsonStatistics<'a> {
+    schema_version: u32,
+    #[serde(flatten)]
+    statistics: &'a Statistics,
+}
+
+impl Statistics {
+    /// Statistics of commits, newest first, attributed to the releases tagged on them or on
+    /// their descendants. Authors are resolved with the mailmap of `identities`.
+    pub fn from(commits: &[Commit], tags: &[VersionTag], identities: &Identities) -> Self {
+        let history = History::from(commits);
+        let entries: HashMap<&str, &HistoryEntry> = history
+            .entries
+            .iter()
+            .map(|entry| (entry.id.as_str(), entry))
+            .collect();
+
+        let mut types: Vec<Count> = vec![];
+        let mut scopes: Vec<Count> = vec![];
+        for entry in &history.entries {
+            increment(&mut types, entry.commit.commit_type.as_str());
+            for scope in entry.commit.scopes.as_deref().unwrap_or_default() {
+                increment(&mut scopes, scope);
+            }
+        }
+        sort_counts(&mut types);
+        sort_counts(&mut scopes);
+
+        return Self {
+            commits: commits.len(),
+            conventional: history.entries.len(),
+            compliance: compliance(history.entries.len(), commits.len()),
+            types,
+            scopes,
+            releases: get_releases(commits, tags, &entries),
+            authors: get_authors(commits, &entries, identities),
+            weeks: get_weeks(commits, &entries),
+        };
+    }
+
+    pub fn render_text(&self) -> String {
+        let mut text = format!(
+            "Commits: {} ({} conventional, {} not conventional, {:.1}% compliance)\n",
+            self.commits,
+            self.conventional,
+            self.commits - self.conventional,
+            self.compliance
+        );
+
+        let counts = |title: &str, counts: &[Count]| -> String {
+            let rows: Vec<(String, String)> = counts
+                .iter()
+                .map(|count| (count.name.clone(), count.count.to_string()))
+                .collect();
+            return render_table(title, &rows);
+        };
+        text.push_str(&counts("Types", &self.types));
+        text.push_str(&counts("Scopes", &self.scopes));
+
+        let releases: Vec<(String, String)> = self
+            .releases
+            .iter()
+            .map(|release| {
+                (
+                    release.tag.clone().unwrap_or(String::from("Unreleased")),
+                    format!(
+                        "{} commits, {} breaking changes",
+                        release.commits, release.breaking_changes
+                    ),
+                )
+            })
+            .collect();
+        text.push_str(&render_table("Releases", &releases));
+
+        let authors: Vec<(String, String)> = self
+            .authors
+            .iter()
+            .map(|author| {
+                (
+                    format!("{} <{}>", author.name, author.email),
+                    format!(
+                        "{} commits, {:.1}% compliance",
+                        author.commits, author.compliance
+                    ),
+                )
+            })
+            .collect();
+        text.push_str(&render_table("Authors", &authors));
+
+        let weeks: Vec<(String, String)> = self
+            .weeks
+            .iter()
+            .map(|week| {
+                (
+                    week.week.clone(),
+                    format!(
+                        "{} commits, {} conventional",
+                        week.commits, week.conventional
+                    ),
+                )
+            })
+            .collect();
+        text.push_str(&render_table("Weeks", &weeks));
+
+        return text;
+    }
+
+    /// Pretty printed JSON with `schema_version` along with the statistics.
+    pub fn render_json(&self) -> String {
+        let statistics = JsonStatistics {
+            schema_version: JSON_SCHEMA_VERSION,
+            statistics: self,
+        };
+        let json =
+            serde_json::to_string_pretty(&statistics).expect("statistics should serialize to JSON");
+
+        return format!("{}\n", json);
+    }
+}
+
+fn compliance(conventional: usize, commits: usize) -> f64 {
+    if commits == 0 {
+        return 100.0;
+    }
+
+    return conventional as f64 * 100.0 / commits as f64;
+}
+
+fn increment(counts: &mut Vec<Count>, name: &str) {
+    match counts.iter_mut().find(|count| count.name == name) {
+        Some(count) => count.count += 1,
+        None => counts.push(Count {
+            name: String::from(name),
+            count: 1,
+        }),
+    }
+}
+
+fn sort_counts(counts: &mut [Count]) {
+    counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+}
+
+/// Commits older than a tagged commit belong to its release until the next tagged one. With
+/// several release tags on a commit, the highest version names the release.
+fn get_releases(
+    commits: &[Commit],
+    tags: &[VersionTag],
+    entries: &HashMap<&str, &HistoryEntry>,
+) -> Vec<ReleaseStatistics> {
+    let mut releases: Vec<ReleaseStatistics> = vec![];
+    for commit in commits {
+        let tag = tags
+            .iter()
+            .filter(|tag| tag.target == commit.id)
+            .max_by(|a, b| a.version.cmp(&b.version));
+        if releases.is_empty() || tag.is_some() {
+            releases.push(ReleaseStatistics {
+                tag: tag.map(|tag| tag.name.clone()),
+                commits: 0,
+                breaking_changes: 0,
+            });
+        }
+
+        let release = releases.last_mut().unwrap();
+        release.commits += 1;
+        if entries
+            .get(commit.id.as_str())
+            .is_some_and(|entry| entry.commit.is_breaking_change)
+        {
+            release.breaking_changes += 1;
+        }
+    }
+
+    return releases;
+}
+
+fn get_authors(
+    commits: &[Commit],
+    entries: &HashMap<&str, &HistoryEntry>,
+    identities: &Identities,
+) -> Vec<AuthorStatistics> {
+    let mut authors: Vec<AuthorStatistics> = vec![];
+    for commit in commits {
+        let author =
+            identities.resolve(&Contributor::new(&commit.author.name, &commit.author.email));
+        let email = author.email.to_lowercase();
+        let index = match authors.iter().position(|author| author.email == email) {
+            Some(index) => index,
+            None => {
+                authors.push(AuthorStatistics {
+                    name: author.name,
+                    email,
+                    commits: 0,
+                    conventional: 0,
+                    compliance: 0.0,
+                });
+                authors.len() - 1
+            }
+        };
+
+        authors[index].commits += 1;
+        if entries.contains_key(commit.id.as_str()) {
+            authors[index].conventional += 1;
+        }
+    }
+
+    for author in &mut authors {
+        author.compliance = compliance(author.conventional, author.commits);
+    }
+    authors.sort_by(|a, b| b.commits.cmp(&a.commits).then_with(|| a.name.cmp(&b.name)));
+
+    return authors;
+}
+
+fn get_weeks(commits: &[Commit], entries: &HashMap<&str, &HistoryEntry>) -> Vec<WeekStatistics> {
+    let monday = |commit: &Commit| -> NaiveDate {
+        let date = commit.committer.timestamp.with_timezone(&Utc).date_naive();
+        return date - Duration::days(date.weekday().num_days_from_monday() as i64);
+    };
+
+    let (first, last) = match (
+        commits.iter().map(monday).min(),
+        commits.iter().map(monday).max(),
+    ) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return vec![],
+    };
+
+    let mut weeks: Vec<WeekStatistics> = vec![];
+    let mut start = first;
+    while start <= last {
+        let week = start.iso_week();
+        weeks.push(WeekStatistics {
+            week: format!("{}-W{:02}", week.year(), week.week()),
+            start: start.format("%Y-%m-%d").to_string(),
+            commits: 0,
+            conventional: 0,
+        });
+        start += Duration::days(7);
+    }
+
+    for commit in commits {
+        let index = ((monday(commit) - first).num_days() / 7) as usize;
+        weeks[index].commits += 1;
+        if entries.contains_key(commit.id.as_str()) {
+            weeks[index].conventional += 1;
+        }
+    }
+
+    return weeks;
+}
+
+fn render_table(title: &str, rows: &[(String, String)]) -> String {
+    if rows.is_empty() {
+        return String::new();
+    }
+
+    let width = rows
+        .iter()
+        .map(|(name, _)| name.chars().count())
+        .max()
+        .unwrap();
+    let mut table = format!("\n{}:\n", title);
+    for (name, value) in rows {
+        table.push_str(&format!("  {:width$}  {}\n", name, value, width = width));
+    }
+
+    return table;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::contributors::Identities;
+    use crate::core::history::tests::commit;
+    use crate::git::{Commit, RevisionRange};
+    use crate::release::VersionTag;
+    use crate::stats::{process_args, Count, ReleaseStatistics, Statistics};
+    use chrono::DateTime;
+
+    fn commit_at(id: &str, message: &str, email: &str, date: &str) -> Commit {
+        let mut commit = commit(id, message);
+        commit.author.email = String::from(email);
+        commit.committer.timestamp = DateTime::parse_from_rfc3339(date).unwrap();
+        return commit;
+    }
+
+    fn tag(name: &str, target: &str) -> VersionTag {
+        return VersionTag {
+            name: String::from(name),
+            target: String::from(target),
+            version: name[1..].parse().unwrap(),
+        };
+    }
+
+    fn commits() -> Vec<Commit> {
+        return vec![
+            commit_at(
+                "f",
+                "fix(api): crash",
+                "jane@example.com",
+                "2024-05-20T10:00:00Z",
+            ),
+            commit_at(
+                "e",
+                "feat(api)!: drop v1",
+                "jane@example.com",
+                "2024-05-06T10:00:00Z",
+            ),
+            commit_at("d", "WIP", "john@example.com", "2024-05-05T10:00:00Z"),
+            commit_at(
+                "c",
+                "feat(cli): flag",
+                "JOHN@example.com",
+                "2024-05-03T10:00:00Z",
+            ),
+            commit_at("b", "fix: typo", "jane@example.com", "2024-05-01T10:00:00Z"),
+            commit_at("a", "initial", "jane@example.com", "2024-05-01T09:00:00Z"),
+        ];
+    }
+
+    #[test]
+    fn should_count_types_scopes_and_compliance() {
+        // when
+        let statistics = Statistics::from(&commits(), &[], &Identities::default());
+
+        // then
+        assert_eq!(statistics.commits, 6);
+        assert_eq!(statistics.conventional, 4);
+        assert!((statistics.compliance - 66.666).abs() < 0.01);
+        assert_eq!(
+            statistics.types,
+            vec![
+                Count {
+                    name: String::from("feat"),
+                    count: 2
+                },
+                Count {
+                    name: String::from("fix"),
+                    count: 2
+                },
+            ]
+        );
+        assert_eq!(statistics.scopes[0].name, "api");
+        assert_eq!(statistics.scopes[0].count, 2);
+        assert_eq!(
+            Statistics::from(&[], &[], &Identities::default()).compliance,
+            100.0
+        );
+    }
+
+    #[test]
+    fn should_count_breaking_changes_per_release() {
+        // given
+        let tags = vec![
+            tag("v1.0.0", "b"),
+            tag("v2.0.0", "e"),
+            tag("v2.0.0-rc.1", "e"),
+        ];
+
+        // when
+        let statistics = Statistics::from(&commits(), &tags, &Identities::default());
+
+        // then
+        assert_eq!(
+            statistics.releases,
+            vec![
+                ReleaseStatistics {
+                    tag: None,
+                    commits: 1,
+                    breaking_changes: 0
+                },
+                ReleaseStatistics {
+                    tag: Some(String::from("v2.0.0")),
+                    commits: 3,
+                    breaking_changes: 1
+                },
+                ReleaseStatistics {
+                    tag: Some(String::from("v1.0.0")),
+                    commits: 2,
+                    breaking_changes: 0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_break_down_by_author_and_week() {
+        // when
+        let statistics = Statistics::from(&commits(), &[], &Identities::default());
+
+        // then
+        let authors: Vec<(&str, usize, usize)> = statistics
+            .authors
+            .iter()
+            .map(|a| (a.email.as_str(), a.commits, a.conventional))
+            .collect();
+        assert_eq!(
+            authors,
+            vec![("jane@example.com", 4, 3), ("john@example.com", 2, 1)]
+        );
+
+        let weeks: Vec<(&str, usize, usize)> = statistics
+            .weeks
+            .iter()
+            .map(|w| (w.week.as_str(), w.commits, w.conventional))
+            .collect();
+        assert_eq!(
+            weeks,
+            vec![
+                ("2024-W18", 4, 2),
+                ("2024-W19", 1, 1),
+                ("2024-W20", 0, 0),
+                ("2024-W21", 1, 1),
+            ]
+        );
+        assert_eq!(statistics.weeks[0].start, "2024-04-29");
+    }
+
+    #[test]
+    fn should_render_text_and_json() {
+        // given
+        let statistics =
+            Statistics::from(&commits(), &[tag("v1.0.0", "b")], &Identities::default());
+
+        // when
+        let text = statistics.render_text();
+        let json: serde_json::Value = serde_json::from_str(&statistics.render_json()).unwrap();
+
+        // then
+        assert!(
+            text.starts_with("Commits: 6 (4 conventional, 2 not conventional, 66.7% compliance)\n")
+        );
+        assert!(text.contains("\nTypes:\n  feat  2\n  fix   2\n"));
+        assert!(text.contains("  Unreleased  4 commits, 1 breaking changes\n"));
+        assert!(text.contains("  Jane Doe <jane@example.com>  4 commits, 75.0% compliance\n"));
+        assert!(text.contains("  2024-W20  0 commits, 0 conventional\n"));
+        assert_eq!(json["schema_version"], 1);
+        assert_eq!(json["conventional"], 4);
+        assert_eq!(json["releases"][1]["tag"], "v1.0.0");
+        assert_eq!(json["weeks"][0]["start"], "2024-04-29");
+    }
+
+    #[test]
+    fn should_process_args() {
+        // given
+        let args: Vec<String> = ["stats", "v1.0.0..HEAD", "--format", "json"]
+            .iter()
+            .map(|arg| String::from(*arg))
+            .collect();
+
+        // when
+        let args = process_args(&args);
+
+        // then
+        assert_eq!(args.range, RevisionRange::new(Some("v1.0.0"), "HEAD"));
+        assert!(args.json);
+        assert_eq!(args.tag_prefix, None);
+    }
+}
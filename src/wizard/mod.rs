@@ -0,0 +1,450 @@
+use crate::config::Config;
+use crate::core::base::ParseError;
+use crate::core::conventional_commit::{ConventionalCommit, FooterElement};
+use crate::lint::lint;
+use std::io;
+use std::io::{BufRead, ErrorKind, Write};
+use std::str::FromStr;
+
+/// Answer that clears a suggested default value.
+const EMPTY_ANSWER: &str = "-";
+
+#[derive(Debug)]
+pub struct CommitArgs {
+    /// Write the message to this file instead of running `git commit`.
+    pub message_file: Option<String>,
+    /// Arguments passed through to `git commit`.
+    pub git_args: Vec<String>,
+}
+
+pub fn process_args(args: &[String]) -> CommitArgs {
+    let mut message_file: Option<String> = None;
+    let mut git_args: Vec<String> = vec![];
+
+    let mut iterator = args.iter().skip(1);
+    while let Some(arg) = iterator.next() {
+        match arg.as_str() {
+            "--message-file" => match iterator.next() {
+                Some(path) => message_file = Some(path.clone()),
+                None => panic!("Missing path after --message-file"),
+            },
+            "--" => git_args.extend(iterator.by_ref().cloned()),
+            _ => git_args.push(arg.clone()),
+        }
+    }
+
+    return CommitArgs {
+        message_file,
+        git_args,
+    };
+}
+
+pub trait Prompter {
+    /// Asks a question and returns the answer without surrounding whitespace.
+    fn ask(&mut self, question: &str) -> io::Result<String>;
+    fn say(&mut self, message: &str) -> io::Result<()>;
+}
+
+pub struct IoPrompter<R: BufRead, W: Write> {
+    input: R,
+    output: W,
+}
+
+impl<R: BufRead, W: Write> IoPrompter<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        Self { input, output }
+    }
+}
+
+impl<R: BufRead, W: Write> Prompter for IoPrompter<R, W> {
+    fn ask(&mut self, question: &str) -> io::Result<String> {
+        write!(self.output, "{}", question)?;
+        self.output.flush()?;
+
+        let mut answer = String::new();
+        if self.input.read_line(&mut answer)? == 0 {
+            return Err(io::Error::new(
+                ErrorKind::UnexpectedEof,
+                "Input ended before the commit message was complete",
+            ));
+        }
+
+        return Ok(String::from(answer.trim()));
+    }
+
+    fn say(&mut self, message: &str) -> io::Result<()> {
+        writeln!(self.output, "{}", message)
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Answers {
+    pub commit_type: String,
+    pub scope: Option<String>,
+    pub description: String,
+    pub body: Option<String>,
+    pub breaking_change: Option<String>,
+    /// Complete trailers, e.g. `Refs: JIRA-123`.
+    pub references: Vec<String>,
+}
+
+impl Answers {
+    /// The answers as a commit, validated against the grammar.
+    pub fn to_commit(&self) -> Result<ConventionalCommit, ParseError> {
+        let mut builder = ConventionalCommit::builder()
+            .commit_type(&self.commit_type)
+            .description(&self.description);
+        for scope in self.scope.iter().flat_map(|scope| scope.split(',')) {
+            builder = builder.scope(scope);
+        }
+        if let Some(body) = &self.body {
+            builder = builder.body(body);
+        }
+        if let Some(breaking_change) = &self.breaking_change {
+            builder = builder.breaking(breaking_change);
+        }
+        for reference in &self.references {
+            let trailer = FooterElement::try_from(reference.as_str())?;
+            builder = builder.trailer(trailer.key(), trailer.value());
+        }
+
+        return builder.build();
+    }
+
+    pub fn to_message(&self) -> Result<String, ParseError> {
+        return Ok(self.to_commit()?.to_message());
+    }
+}
+
+/// Asks for every part of the commit message, repeating a question until its answer is valid.
+pub fn run(
+    prompter: &mut dyn Prompter,
+    config: &Config,
+    defaults: &Answers,
+) -> io::Result<Answers> {
+    let mut answers = Answers::default();
+
+    prompter.say("Select the type of change:")?;
+    for (index, t) in config.types.iter().enumerate() {
+        match &t.description {
+            Some(description) => {
+                prompter.say(&format!("  {}) {} - {}", index + 1, t.name, description))?
+            }
+            None => prompter.say(&format!("  {}) {}", index + 1, t.name))?,
+        }
+    }
+    answers.commit_type = ask_until_valid(
+        prompter,
+        "Type",
+        non_empty(&defaults.commit_type),
+        |answer| parse_type(answer, config),
+    )?;
+
+    if !config.scopes.is_empty() {
+        prompter.say(&format!("Known scopes: {}", config.scopes.join(", ")))?;
+    }
+    answers.scope = ask_until_valid(
+        prompter,
+        "Scope (optional)",
+        defaults.scope.as_deref(),
+        |answer| match answer.is_empty() {
+            true => Ok(None),
+            false => validate_scope(&answers.commit_type, answer).map(Some),
+        },
+    )?;
+
+    answers.description = ask_until_valid(prompter, "Short description", None, |answer| {
+        let header = Answers {
+            commit_type: answers.commit_type.clone(),
+            scope: answers.scope.clone(),
+            description: String::from(answer),
+            ..Answers::default()
+        };
+        validate_answers(&header, config).map(|_| String::from(answer))
+    })?;
+
+    prompter.say("Longer description (optional, finish with an empty line):")?;
+    let mut body_lines: Vec<String> = vec![];
+    loop {
+        let line = prompter.ask("> ")?;
+        if line.is_empty() {
+            break;
+        }
+        body_lines.push(line);
+    }
+    if !body_lines.is_empty() {
+        answers.body = Some(body_lines.join("\n"));
+        if let Err(reason) = validate_body(&answers, config) {
+            prompter.say(&reason)?;
+            prompter.say("The body was dropped, amend it in your editor if needed.")?;
+            answers.body = None;
+        }
+    }
+
+    answers.breaking_change = ask_until_valid(
+        prompter,
+        "Describe the breaking change (leave empty if none)",
+        defaults.breaking_change.as_deref(),
+        |answer| Ok(Some(String::from(answer)).filter(|answer| !answer.is_empty())),
+    )?;
+
+    let default_references = defaults.references.join(", ");
+    answers.references = ask_until_valid(
+        prompter,
+        "Issue references, e.g. \"Closes #12, JIRA-34\" (optional)",
+        non_empty(&default_references),
+        |answer| parse_references(answer, &config.branches.ticket_trailer),
+    )?;
+
+    if let Err(reason) = validate_answers(&answers, config) {
+        return Err(io::Error::new(ErrorKind::InvalidData, reason));
+    }
+
+    return Ok(answers);
+}
+
+fn ask_until_valid<T>(
+    prompter: &mut dyn Prompter,
+    question: &str,
+    default: Option<&str>,
+    validate: impl Fn(&str) -> Result<T, String>,
+) -> io::Result<T> {
+    let question = match default {
+        Some(default) => format!("{} [{}]: ", question, default),
+        None => format!("{}: ", question),
+    };
+
+    loop {
+        let answer = prompter.ask(&question)?;
+        let answer = match (answer.as_str(), default) {
+            ("", Some(default)) => String::from(default),
+            (EMPTY_ANSWER, _) => String::new(),
+            _ => answer,
+        };
+
+        match validate(&answer) {
+            Ok(value) => return Ok(value),
+            Err(reason) => prompter.say(&reason)?,
+        }
+    }
+}
+
+fn non_empty(value: &str) -> Option<&str> {
+    Some(value).filter(|value| !value.is_empty())
+}
+
+/// Accepts either the name of a registered type or its position on the list.
+fn parse_type(answer: &str, config: &Config) -> Result<String, String> {
+    if let Ok(position) = answer.parse::<usize>() {
+        if let Some(t) = position.checked_sub(1).and_then(|i| config.types.get(i)) {
+            return Ok(t.name.clone());
+        }
+    }
+
+    return match config.find_type(answer) {
+        Some(t) => Ok(t.name.clone()),
+        None => Err(format!("Unknown commit type: {}", answer)),
+    };
+}
+
+fn validate_scope(commit_type: &str, scope: &str) -> Result<String, String> {
+    let header = format!("{}({}): description", commit_type, scope);
+    let expected: Vec<String> = scope.split(',').map(String::from).collect();
+
+    return match ConventionalCommit::from_str(&header) {
+        Ok(commit) if commit.scopes == Some(expected) && !scope.contains(char::is_whitespace) => {
+            Ok(String::from(scope))
+        }
+        _ => Err(format!("Invalid scope: {}", scope)),
+    };
+}
+
+fn validate_message(message: &str, config: &Config) -> Result<(), String> {
+    let violations = lint(message, config);
+    if violations.is_empty() {
+        return Ok(());
+    }
+
+    return Err(violations
+        .iter()
+        .map(|violation| violation.to_string())
+        .collect::<Vec<String>>()
+        .join("\n"));
+}
+
+/// Lints the message the answers form, once they form a commit at all.
+fn validate_answers(answers: &Answers, config: &Config) -> Result<(), String> {
+    let message = answers.to_message().map_err(|e| e.to_string())?;
+    return validate_message(&message, config);
+}
+
+fn validate_body(answers: &Answers, config: &Config) -> Result<(), String> {
+    // with an answered header, only a body ending with git trailers can't form the commit
+    if answers.to_commit().is_err() {
+        return Err(String::from(
+            "The last paragraph of the body would be read as git trailers",
+        ));
+    }
+
+    return validate_answers(answers, config);
+}
+
+/// Turns `Closes #12, JIRA-34` into `Closes: #12` and `<default_key>: JIRA-34` trailers.
+fn parse_references(answer: &str, default_key: &str) -> Result<Vec<String>, String> {
+    let mut references: Vec<String> = vec![];
+
+    for reference in answer.split(',').map(str::trim).filter(|r| !r.is_empty()) {
+        let trailer = match reference.split_once([' ', ':']) {
+            Some((key, value))
+                if !value.trim().is_empty()
+                    && key.chars().all(|c| c.is_ascii_alphabetic() || c == '-') =>
+            {
+                format!("{}: {}", key, value.trim())
+            }
+            _ => format!("{}: {}", default_key, reference),
+        };
+
+        if FooterElement::try_from(trailer.as_str()).is_err() {
+            return Err(format!("Invalid issue reference: {}", reference));
+        }
+        references.push(trailer);
+    }
+
+    return Ok(references);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+    use crate::wizard::{parse_references, process_args, run, Answers, IoPrompter};
+    use std::io::Cursor;
+
+    fn run_with_input(input: &str, defaults: &Answers) -> (Result<Answers, String>, String) {
+        let mut output: Vec<u8> = vec![];
+        let result = {
+            let mut prompter = IoPrompter::new(Cursor::new(input.as_bytes()), &mut output);
+            run(&mut prompter, &Config::default(), defaults).map_err(|e| e.to_string())
+        };
+
+        return (result, String::from_utf8(output).unwrap());
+    }
+
+    #[test]
+    fn should_assemble_complete_message_from_answers() {
+        // given
+        let input = "feat\napi\nadd endpoint\nFirst line\nsecond line\n\nold endpoint is gone\nCloses #12, JIRA-34\n";
+
+        // when
+        let (result, _) = run_with_input(input, &Answers::default());
+
+        // then
+        assert_eq!(
+            result.unwrap().to_message().unwrap(),
+            "feat(api): add endpoint\n\nFirst line\nsecond line\n\nBREAKING CHANGE: old endpoint is gone\nCloses: #12\nRefs: JIRA-34\n"
+        );
+    }
+
+    #[test]
+    fn should_accept_type_by_its_position_and_use_defaults() {
+        // given
+        let defaults = Answers {
+            scope: Some(String::from("cli")),
+            references: vec![String::from("Refs: JIRA-1")],
+            ..Answers::default()
+        };
+        let input = "1\n\nhandle empty input\n\n\n\n";
+
+        // when
+        let (result, _) = run_with_input(input, &defaults);
+
+        // then
+        assert_eq!(
+            result.unwrap().to_message().unwrap(),
+            "fix(cli): handle empty input\n\nRefs: JIRA-1\n"
+        );
+    }
+
+    #[test]
+    fn should_repeat_question_until_answer_is_valid() {
+        // given
+        let input = "wip\nfeat\nnot valid\n-\ndo something\n\n\n-\n";
+        let defaults = Answers {
+            scope: Some(String::from("cli")),
+            ..Answers::default()
+        };
+
+        // when
+        let (result, output) = run_with_input(input, &defaults);
+
+        // then
+        assert_eq!(
+            result.unwrap().to_message().unwrap(),
+            "feat: do something\n"
+        );
+        assert!(output.contains("Unknown commit type: wip"));
+        assert!(output.contains("Invalid scope: not valid"));
+    }
+
+    #[test]
+    fn should_drop_body_that_would_be_read_as_trailers() {
+        // given
+        let input = "feat\n\ndo something\nRefs: #1\n\n\n\n";
+
+        // when
+        let (result, output) = run_with_input(input, &Answers::default());
+
+        // then
+        assert_eq!(
+            result.unwrap().to_message().unwrap(),
+            "feat: do something\n"
+        );
+        assert!(output.contains("The last paragraph of the body would be read as git trailers"));
+    }
+
+    #[test]
+    fn should_fail_when_input_ends_early() {
+        // when
+        let (result, _) = run_with_input("feat\n", &Answers::default());
+
+        // then
+        assert!(result.is_err(), "An Error should have been returned");
+    }
+
+    #[test]
+    fn should_parse_references_into_trailers() {
+        // when
+        let actual = parse_references("Closes #12, Fixes: #3 ,JIRA-34", "Refs").unwrap();
+
+        // then
+        assert_eq!(
+            actual,
+            vec![
+                String::from("Closes: #12"),
+                String::from("Fixes: #3"),
+                String::from("Refs: JIRA-34"),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_split_wizard_and_git_arguments() {
+        // given
+        let args = vec![
+            String::from("DontCare"),
+            String::from("--message-file"),
+            String::from(".git/COMMIT_EDITMSG"),
+            String::from("--"),
+            String::from("--no-verify"),
+        ];
+
+        // when
+        let actual = process_args(&args);
+
+        // then
+        assert_eq!(
+            actual.message_file,
+            Some(String::from(".git/COMMIT_EDITMSG"))
+        );
+        assert_eq!(actual.git_args, vec![String::from("--no-verify")]);
+    }
+}
@@ -0,0 +1,328 @@
+use crate::config::ConfigError;
+use crate::core::glob::Glob;
+use std::fs;
+use std::path::Path;
+use toml::Value;
+
+/// Package of a Cargo workspace.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Package {
+    pub name: String,
+    /// Slash separated path of the package directory, relative to the workspace root.
+    pub path: String,
+}
+
+impl Package {
+    pub fn contains(&self, path: &str) -> bool {
+        self.path.is_empty() || path == self.path || path.starts_with(&format!("{}/", self.path))
+    }
+}
+
+/// Reads members of the Cargo workspace rooted at `root`.
+///
+/// A root manifest without a `[workspace]` table yields no packages.
+pub fn cargo_packages(root: &Path) -> Result<Vec<Package>, ConfigError> {
+    let manifest = root.join("Cargo.toml");
+    if !manifest.is_file() {
+        return Ok(vec![]);
+    }
+
+    let content = read_manifest(&manifest)?;
+    let workspace = match content.get("workspace") {
+        Some(workspace) => workspace,
+        None => return Ok(vec![]),
+    };
+
+    let members = string_array(workspace.get("members"));
+    let excluded = string_array(workspace.get("exclude"));
+
+    let mut packages: Vec<Package> = vec![];
+    for member in members {
+        for path in expand_member(root, &member)? {
+            if excluded.contains(&path) || packages.iter().any(|p| p.path == path) {
+                continue;
+            }
+
+            let member_manifest = root.join(&path).join("Cargo.toml");
+            if !member_manifest.is_file() {
+                continue;
+            }
+
+            let name = read_manifest(&member_manifest)?
+                .get("package")
+                .and_then(|package| package.get("name"))
+                .and_then(|name| name.as_str())
+                .map(String::from);
+            if let Some(name) = name {
+                packages.push(Package { name, path });
+            }
+        }
+    }
+
+    // the root package of a non-virtual workspace owns files outside of other members
+    if let Some(name) = content
+        .get("package")
+        .and_then(|package| package.get("name"))
+        .and_then(|name| name.as_str())
+    {
+        packages.push(Package {
+            name: String::from(name),
+            path: String::new(),
+        });
+    }
+
+    return Ok(packages);
+}
+
+/// Names of crates the package depends on, including build dependencies and dependencies
+/// for specific targets, but not dev-dependencies.
+pub fn cargo_dependencies(root: &Path, package: &Package) -> Result<Vec<String>, ConfigError> {
+    let manifest = root.join(&package.path).join("Cargo.toml");
+    if !manifest.is_file() {
+        return Ok(vec![]);
+    }
+
+    let content = read_manifest(&manifest)?;
+    let mut tables: Vec<&Value> = vec![&content];
+    if let Some(targets) = content.get("target").and_then(|targets| targets.as_table()) {
+        tables.extend(targets.values());
+    }
+
+    let mut dependencies: Vec<String> = vec![];
+    for table in tables {
+        for key in ["dependencies", "build-dependencies"] {
+            let entries = match table.get(key).and_then(|entries| entries.as_table()) {
+                Some(entries) => entries,
+                None => continue,
+            };
+
+            for (name, dependency) in entries {
+                // renamed dependencies name the actual crate in `package`
+                let name = dependency
+                    .get("package")
+                    .and_then(|package| package.as_str())
+                    .unwrap_or(name);
+                if !dependencies.iter().any(|d| d == name) {
+                    dependencies.push(String::from(name));
+                }
+            }
+        }
+    }
+
+    return Ok(dependencies);
+}
+
+fn read_manifest(path: &Path) -> Result<Value, ConfigError> {
+    let content = fs::read_to_string(path).map_err(|e| ConfigError {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+    })?;
+
+    return toml::from_str::<Value>(&content).map_err(|e| ConfigError {
+        path: path.display().to_string(),
+        reason: e.message().to_string(),
+    });
+}
+
+fn string_array(value: Option<&Value>) -> Vec<String> {
+    match value.and_then(|value| value.as_array()) {
+        Some(values) => values
+            .iter()
+            .filter_map(|value| value.as_str())
+            .map(|value| String::from(value.trim_end_matches('/')))
+            .collect(),
+        None => vec![],
+    }
+}
+
+/// Expands glob characters in a workspace member path, segment by segment.
+fn expand_member(root: &Path, member: &str) -> Result<Vec<String>, ConfigError> {
+    let mut paths: Vec<String> = vec![String::new()];
+
+    for segment in member.split('/').filter(|s| !s.is_empty() && *s != ".") {
+        let mut expanded: Vec<String> = vec![];
+        let has_wildcard = segment.contains('*') || segment.contains('?');
+
+        for path in paths {
+            let join = |name: &str| match path.is_empty() {
+                true => String::from(name),
+                false => format!("{}/{}", path, name),
+            };
+
+            if !has_wildcard {
+                expanded.push(join(segment));
+                continue;
+            }
+
+            let glob = Glob::new(segment).map_err(|e| ConfigError {
+                path: String::from("Cargo.toml"),
+                reason: format!("Invalid workspace member {}: {}", member, e),
+            })?;
+            let mut names: Vec<String> = match fs::read_dir(root.join(&path)) {
+                Ok(entries) => entries
+                    .filter_map(|entry| entry.ok())
+                    .filter(|entry| entry.path().is_dir())
+                    .filter_map(|entry| entry.file_name().into_string().ok())
+                    .filter(|name| glob.matches(name))
+                    .collect(),
+                Err(_) => vec![],
+            };
+            names.sort();
+            expanded.extend(names.iter().map(|name| join(name)));
+        }
+
+        paths = expanded;
+    }
+
+    return Ok(paths);
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::workspace::{cargo_dependencies, cargo_packages, Package};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::process;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static WORKSPACES: AtomicUsize = AtomicUsize::new(0);
+
+    /// Creates an empty temporary directory, unique to the test run.
+    pub fn create_workspace(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!(
+            "con-comm-workspace-{}-{}-{}",
+            name,
+            process::id(),
+            WORKSPACES.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        return root;
+    }
+
+    /// Writes a file relative to `root`, creating missing directories.
+    pub fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn should_read_workspace_members_with_wildcards() {
+        // given
+        let root = create_workspace("wildcards");
+        write(
+            &root,
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/*\", \"tools/cli\"]\nexclude = [\"crates/old\"]\n",
+        );
+        write(
+            &root,
+            "crates/core/Cargo.toml",
+            "[package]\nname = \"my-core\"\n",
+        );
+        write(
+            &root,
+            "crates/api/Cargo.toml",
+            "[package]\nname = \"my-api\"\n",
+        );
+        write(
+            &root,
+            "crates/old/Cargo.toml",
+            "[package]\nname = \"my-old\"\n",
+        );
+        write(
+            &root,
+            "tools/cli/Cargo.toml",
+            "[package]\nname = \"my-cli\"\n",
+        );
+
+        // when
+        let packages = cargo_packages(&root).unwrap();
+
+        // then
+        assert_eq!(
+            packages,
+            vec![
+                Package {
+                    name: String::from("my-api"),
+                    path: String::from("crates/api"),
+                },
+                Package {
+                    name: String::from("my-core"),
+                    path: String::from("crates/core"),
+                },
+                Package {
+                    name: String::from("my-cli"),
+                    path: String::from("tools/cli"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_return_no_packages_without_workspace() {
+        // given
+        let root = create_workspace("single");
+        write(&root, "Cargo.toml", "[package]\nname = \"single\"\n");
+
+        // when
+        let packages = cargo_packages(&root).unwrap();
+
+        // then
+        assert!(packages.is_empty());
+    }
+
+    #[test]
+    fn should_read_dependencies_without_dev_dependencies() {
+        // given
+        let root = create_workspace("dependencies");
+        write(
+            &root,
+            "crates/app/Cargo.toml",
+            r#"[package]
+name = "app"
+
+[dependencies]
+core = { path = "../core", version = "1.0.0" }
+http = { package = "my-http", path = "../http" }
+serde = "1"
+
+[build-dependencies]
+codegen = { path = "../codegen" }
+
+[dev-dependencies]
+testing = { path = "../testing" }
+
+[target.'cfg(windows)'.dependencies]
+winapi = "0.3"
+"#,
+        );
+        let package = Package {
+            name: String::from("app"),
+            path: String::from("crates/app"),
+        };
+
+        // when
+        let dependencies = cargo_dependencies(&root, &package).unwrap();
+
+        // then
+        assert_eq!(
+            dependencies,
+            vec!["core", "my-http", "serde", "codegen", "winapi"]
+        );
+    }
+
+    #[test]
+    fn should_check_whether_path_belongs_to_package() {
+        // given
+        let package = Package {
+            name: String::from("api"),
+            path: String::from("crates/api"),
+        };
+
+        // then
+        assert!(package.contains("crates/api/src/lib.rs"));
+        assert!(!package.contains("crates/api-client/src/lib.rs"));
+    }
+}